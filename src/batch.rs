@@ -0,0 +1,140 @@
+//! This is documentation for the `batch` module.
+//!
+//! The batch module verifies many signatures at once for the schemes whose
+//! verification equation is [S]G = R + [k]A, such as EdDSA and Schnorr
+//! signatures. The equations are multiplied by random coefficients z_i and
+//! added, so that a single multi-scalar multiplication checks
+//!
+//! [Σ z_i S_i]G = Σ [z_i]R_i + Σ [z_i k_i]A_i.
+//!
+//! The check is cofactored, i.e., the difference of both sides must be of
+//! small order. Otherwise, the small-order components of signatures that
+//! are valid under the cofactored equation would not cancel, and the result
+//! would depend on the coefficients.
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::ellipticcurve::{EcPoint, EllipticCurve};
+use crate::field::Field;
+use crate::scalarfield::{Scalar, Zq};
+use crate::Error;
+
+/// Equation is the verification equation [S]G = R + [k]A of a signature.
+pub(crate) struct Equation<P> {
+    pub(crate) s: Scalar,
+    pub(crate) r: P,
+    pub(crate) k: Scalar,
+    pub(crate) a: P,
+}
+
+/// Width of the non-adjacent forms of the scalars.
+const WINDOW: usize = 5;
+
+/// Multiplies each point by its scalar and adds the results, using the
+/// interleaved wNAF method (Straus-Shamir), so that all the points share the
+/// doublings.
+pub(crate) fn multi_scalar_mul<E>(
+    ec: &E,
+    points: &[E::Point],
+    scalars: &[Scalar],
+) -> Result<E::Point, Error>
+where
+    E: EllipticCurve<Scalar = Scalar>,
+    E::Point: Clone,
+{
+    if points.len() != scalars.len() {
+        return Err(Error::InvalidParameters);
+    }
+    // odd multiples P, 3P, ..., (2^(w-1)-1)P of each point.
+    let tables = points
+        .iter()
+        .map(|p| {
+            let p2 = p.try_add(p)?;
+            let mut table = vec![p.clone()];
+            for i in 1..1 << (WINDOW - 2) {
+                table.push(table[i - 1].try_add(&p2)?);
+            }
+            Ok(table)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let digits: Vec<Vec<i8>> = scalars.iter().map(|k| k.wnaf(WINDOW)).collect();
+    let len = digits.iter().map(Vec::len).max().unwrap_or(0);
+    let mut q = ec.identity();
+    for j in (0..len).rev() {
+        q = q.try_add(&q)?;
+        for (d, table) in digits.iter().zip(&tables) {
+            match d.get(j) {
+                Some(&d) if d > 0 => q = q.try_add(&table[d as usize / 2])?,
+                Some(&d) if d < 0 => q = q - &table[d.unsigned_abs() as usize / 2],
+                _ => {}
+            }
+        }
+    }
+    Ok(q)
+}
+
+/// Checks all the equations at once using random coefficients of 128 bits,
+/// so an invalid batch passes with probability at most 2^-128.
+pub(crate) fn verify_equations<E, R>(
+    ec: &E,
+    eqs: &[Equation<E::Point>],
+    rng: &mut R,
+) -> Result<bool, Error>
+where
+    E: EllipticCurve<Scalar = Scalar, ScalarField = Zq>,
+    E::Point: Clone,
+    R: RngCore + CryptoRng,
+{
+    let fq = ec.get_scalar_field();
+    let mut s = fq.zero();
+    let mut points = vec![ec.get_generator()];
+    let mut scalars = Vec::with_capacity(2 * eqs.len() + 1);
+    for eq in eqs {
+        let mut z = [0u8; 16];
+        rng.fill_bytes(&mut z);
+        let z = fq.from_bytes_wide_le(&z);
+        s = s + &z * &eq.s;
+        points.push(eq.r.clone());
+        points.push(eq.a.clone());
+        scalars.push(z.clone());
+        scalars.push(z * &eq.k);
+    }
+    scalars.insert(0, -s);
+    let p = multi_scalar_mul(ec, &points, &scalars)?;
+    Ok(p.is_small_order())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::multi_scalar_mul;
+    use crate::ellipticcurve::{EcPoint, EllipticCurve};
+    use crate::instances::{GetCurve, EDWARDS448, P256};
+    use crate::ops::FromFactory;
+    use crate::Error;
+
+    #[test]
+    fn msm() {
+        let ec = P256.get();
+        let fq = ec.get_scalar_field();
+        let g = ec.get_generator();
+        let points: Vec<_> = (1..6).map(|i| &g * &fq.from(i * 7919)).collect();
+        let scalars: Vec<_> = (1..6).map(|i| fq.from(-i * 104729)).collect();
+        let want = points
+            .iter()
+            .zip(&scalars)
+            .fold(ec.identity(), |q, (p, k)| q + p * k);
+        assert!(multi_scalar_mul(&ec, &points, &scalars).unwrap() == want);
+        assert!(multi_scalar_mul(&ec, &[], &[]).unwrap().is_zero());
+        assert_eq!(
+            multi_scalar_mul(&ec, &points, &scalars[1..]).err(),
+            Some(Error::InvalidParameters)
+        );
+
+        let ec = EDWARDS448.get();
+        let fq = ec.get_scalar_field();
+        let g = ec.get_generator();
+        let k = fq.from("0x1234567890abcdef1234567890abcdef");
+        let got = multi_scalar_mul(&ec, &[g.clone(), g.clone()], &[k.clone(), -&k]).unwrap();
+        assert!(got.is_zero());
+    }
+}
@@ -0,0 +1,835 @@
+//! This is documentation for the `bip32` module.
+//!
+//! The bip32 module derives hierarchical deterministic keys as in BIP-32,
+//! and as in SLIP-0010, which extends BIP-32 to other curves. The master
+//! key is derived from a seed, and every key derives children identified by
+//! a 32-bit index. The hardened children are derived from the secret key,
+//! whereas the normal children are also derived from the public key.
+//!
+//! The keys of secp256k1 and P256 support both kinds of children, whereas
+//! the keys of Ed25519 only have hardened children.
+//!
+//! ```
+//!  use redox_ecc::bip32::{Curve, ExtendedPrivateKey, Version};
+//!
+//!  let seed = [7u8; 32];
+//!  let master = ExtendedPrivateKey::from_seed(Curve::Secp256k1, &seed).unwrap();
+//!  let account = master.derive_path(&"m/44'/0'/0'".parse().unwrap()).unwrap();
+//!  let xpub = account.extended_public_key();
+//!  let xprv = account.to_base58(Version::XPRV);
+//!  assert!(xprv.starts_with("xprv"));
+//!
+//!  // the normal children are derived from the public key too.
+//!  let child = "m/44'/0'/0'/0/0".parse().unwrap();
+//!  let public = xpub.derive_path(&"m/0/0".parse().unwrap()).unwrap();
+//!  let secret = master.derive_path(&child).unwrap();
+//!  assert!(secret.extended_public_key() == public);
+//! ```
+
+mod path;
+
+pub use crate::bip32::path::{ChildNumber, DerivationPath};
+
+use hmac::{Hmac, Mac};
+use num_traits::identities::Zero;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode};
+use crate::instances::{GetCurve, EDWARDS25519, P256, SECP256K1};
+use crate::keys::{PublicKey, SecretKey};
+use crate::ops::{Deserialize, Serialize};
+use crate::{DecodeError, Error};
+
+/// Curve selects the curve of a hierarchy of keys, and hence, the key of
+/// the HMAC that derives the master key from the seed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    Secp256k1,
+    P256,
+    Ed25519,
+}
+
+impl Curve {
+    fn hmac_key(self) -> &'static [u8] {
+        match self {
+            Curve::Secp256k1 => b"Bitcoin seed",
+            Curve::P256 => b"Nist256p1 seed",
+            Curve::Ed25519 => b"ed25519 seed",
+        }
+    }
+}
+
+/// Version is the prefix of a serialized extended key, which determines its
+/// first characters in Base58, e.g., "xprv" and "xpub".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Version(pub [u8; 4]);
+
+impl Version {
+    pub const XPRV: Self = Version([0x04, 0x88, 0xad, 0xe4]);
+    pub const XPUB: Self = Version([0x04, 0x88, 0xb2, 0x1e]);
+    pub const TPRV: Self = Version([0x04, 0x35, 0x83, 0x94]);
+    pub const TPUB: Self = Version([0x04, 0x35, 0x87, 0xcf]);
+}
+
+/// Size in bytes of chain codes, and of the halves of the HMAC output.
+const SIZE: usize = 32;
+/// Size in bytes of a serialized extended key before Base58Check.
+const SERIALIZED_SIZE: usize = 78;
+
+type Half = [u8; SIZE];
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> (Half, Half) {
+    let mut mac = <Hmac<Sha512> as Mac>::new_from_slice(key).unwrap();
+    data.iter().for_each(|d| mac.update(d));
+    let i = mac.finalize().into_bytes();
+    let (il, ir) = i.split_at(SIZE);
+    (il.try_into().unwrap(), ir.try_into().unwrap())
+}
+
+/// Computes I = HMAC-SHA512(c, data) and passes IL to `key`, which returns
+/// None when IL does not yield a valid key; in such case, I is computed
+/// again from `retry(IL, IR)` as SLIP-0010 prescribes. Without `retry`, as
+/// BIP-32 prescribes for secp256k1, it fails instead, so the caller moves
+/// on to the next seed or index. Returns the key and IR, i.e., the chain
+/// code.
+fn derive_key<T>(
+    c: &[u8],
+    data: &[&[u8]],
+    retry: Option<impl Fn(&Half, &Half) -> Vec<u8>>,
+    mut key: impl FnMut(&Half) -> Option<T>,
+) -> Result<(T, Half), Error> {
+    let (mut il, mut ir) = hmac_sha512(c, data);
+    loop {
+        if let Some(k) = key(&il) {
+            return Ok((k, ir));
+        }
+        let retry = retry.as_ref().ok_or(Error::InvalidParameters)?;
+        (il, ir) = hmac_sha512(c, &[&retry(&il, &ir)]);
+    }
+}
+
+/// Encodes a public key as in the derivation and in the serialization: the
+/// compressed SEC1 point for Weierstrass curves, and the RFC 8032 encoding
+/// prefixed by a zero byte for Ed25519.
+fn ser_p(public: &PublicKey) -> Vec<u8> {
+    match public {
+        PublicKey::Weierstrass(_, p) => p.encode(true),
+        _ => [&[0][..], &public.encode(true)].concat(),
+    }
+}
+
+/// Returns HASH160 of the public key, i.e., RIPEMD-160 of SHA-256.
+fn identifier(public: &PublicKey) -> Vec<u8> {
+    Ripemd160::digest(Sha256::digest(ser_p(public))).to_vec()
+}
+
+/// Node holds the position of an extended key in the hierarchy, and its
+/// chain code.
+#[derive(Clone, PartialEq, Eq)]
+struct Node {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: ChildNumber,
+    chain_code: Half,
+}
+
+impl Node {
+    fn child(&self, public: &PublicKey, c: ChildNumber, chain_code: Half) -> Result<Self, Error> {
+        let depth = self.depth.checked_add(1).ok_or(Error::InvalidParameters)?;
+        Ok(Node {
+            depth,
+            parent_fingerprint: identifier(public)[..4].try_into().unwrap(),
+            child_number: c,
+            chain_code,
+        })
+    }
+    fn serialize(&self, version: Version, key: &[u8]) -> String {
+        let buf = [
+            &version.0[..],
+            &[self.depth],
+            &self.parent_fingerprint,
+            &self.child_number.0.to_be_bytes(),
+            &self.chain_code,
+            key,
+        ]
+        .concat();
+        bs58::encode(buf).with_check().into_string()
+    }
+    /// Decodes the serialization and returns the 33 bytes of the key.
+    fn deserialize(s: &str, version: Version) -> Result<(Self, Vec<u8>), Error> {
+        let buf = bs58::decode(s)
+            .with_check(None)
+            .into_vec()
+            .map_err(|_| DecodeError::Malformed)?;
+        if buf.len() != SERIALIZED_SIZE {
+            return Err(DecodeError::WrongLength.into());
+        }
+        if buf[..4] != version.0 {
+            return Err(DecodeError::InvalidTag.into());
+        }
+        let node = Node {
+            depth: buf[4],
+            parent_fingerprint: buf[5..9].try_into().unwrap(),
+            child_number: ChildNumber(u32::from_be_bytes(buf[9..13].try_into().unwrap())),
+            chain_code: buf[13..45].try_into().unwrap(),
+        };
+        // the master key has neither parent nor index.
+        if node.depth == 0 && (node.parent_fingerprint != [0; 4] || node.child_number.0 != 0) {
+            return Err(DecodeError::Malformed.into());
+        }
+        Ok((node, buf[45..].to_vec()))
+    }
+}
+
+/// ExtendedPrivateKey is a secret key of a hierarchy, together with the
+/// chain code and the position that determine its children.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtendedPrivateKey {
+    key: SecretKey,
+    node: Node,
+}
+
+/// ExtendedPublicKey is a public key of a hierarchy, together with the
+/// chain code and the position that determine its normal children.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtendedPublicKey {
+    key: PublicKey,
+    node: Node,
+}
+
+macro_rules! impl_node_getters {
+    ($target:ident) => {
+        impl $target {
+            /// Returns the number of derivations from the master key.
+            pub fn depth(&self) -> u8 {
+                self.node.depth
+            }
+            /// Returns the first four bytes of the identifier of the parent
+            /// key, or zeros for the master key.
+            pub fn parent_fingerprint(&self) -> [u8; 4] {
+                self.node.parent_fingerprint
+            }
+            pub fn child_number(&self) -> ChildNumber {
+                self.node.child_number
+            }
+            pub fn chain_code(&self) -> &[u8] {
+                &self.node.chain_code
+            }
+        }
+    };
+}
+
+impl_node_getters!(ExtendedPrivateKey);
+impl_node_getters!(ExtendedPublicKey);
+
+impl ExtendedPrivateKey {
+    /// Derives the master key from a seed of 16 to 64 bytes. For secp256k1,
+    /// a seed that yields an invalid key is rejected, as BIP-32 prescribes,
+    /// whereas SLIP-0010 derives the key again for the other curves.
+    pub fn from_seed(curve: Curve, seed: &[u8]) -> Result<Self, Error> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err(Error::InvalidParameters);
+        }
+        // the seed is replaced by I.
+        let retry = |il: &Half, ir: &Half| [&il[..], ir].concat();
+        let (key, chain_code) = match curve {
+            Curve::Secp256k1 | Curve::P256 => {
+                let id = if curve == Curve::P256 {
+                    P256
+                } else {
+                    SECP256K1
+                };
+                let fq = id.get().get_scalar_field();
+                let retry = (id != SECP256K1).then_some(retry);
+                let (k, c) = derive_key(curve.hmac_key(), &[seed], retry, |il| {
+                    fq.from_bytes_be(il).ok().filter(|k| !k.is_zero())
+                })?;
+                (SecretKey::Weierstrass(id, k), c)
+            }
+            Curve::Ed25519 => {
+                let (k, c) = derive_key(curve.hmac_key(), &[seed], Some(retry), |il| {
+                    Some(il.to_vec())
+                })?;
+                (SecretKey::Edwards(EDWARDS25519, k), c)
+            }
+        };
+        let node = Node {
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: ChildNumber(0),
+            chain_code,
+        };
+        Ok(ExtendedPrivateKey { key, node })
+    }
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.key
+    }
+    /// Returns the extended public key, which has the same position and
+    /// chain code.
+    pub fn extended_public_key(&self) -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            key: self.key.public_key(),
+            node: self.node.clone(),
+        }
+    }
+    /// Returns HASH160 of the public key, which identifies the key.
+    pub fn identifier(&self) -> Vec<u8> {
+        identifier(&self.key.public_key())
+    }
+    /// Derives a child key (CKDpriv). The keys of Ed25519 only have
+    /// hardened children. For secp256k1, an index that yields an invalid
+    /// key is rejected, and the next index should be used instead.
+    pub fn derive_child(&self, c: ChildNumber) -> Result<Self, Error> {
+        let i = c.0.to_be_bytes();
+        let chain_code = &self.node.chain_code;
+        let retry = |_: &Half, ir: &Half| [&[1], &ir[..], &i].concat();
+        let public = self.key.public_key();
+        let (key, child_code) = match &self.key {
+            SecretKey::Weierstrass(id, k) => {
+                let data = if c.is_hardened() {
+                    [&[0][..], &k.to_bytes_be()].concat()
+                } else {
+                    ser_p(&public)
+                };
+                let fq = id.get().get_scalar_field();
+                let retry = (*id != SECP256K1).then_some(retry);
+                let (k, c) = derive_key(chain_code, &[&data, &i], retry, |il| {
+                    let t = fq.from_bytes_be(il).ok()?;
+                    Some(t + k).filter(|k| !k.is_zero())
+                })?;
+                (SecretKey::Weierstrass(*id, k), c)
+            }
+            SecretKey::Edwards(id, k) if c.is_hardened() => {
+                let data = [&[0][..], k].concat();
+                let (k, c) = derive_key(chain_code, &[&data, &i], Some(retry), |il| {
+                    Some(il.to_vec())
+                })?;
+                (SecretKey::Edwards(*id, k), c)
+            }
+            _ => return Err(Error::Unsupported),
+        };
+        let node = self.node.child(&public, c, child_code)?;
+        Ok(ExtendedPrivateKey { key, node })
+    }
+    /// Derives the descendant key at the path, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, Error> {
+        let mut key = self.clone();
+        for c in path.as_ref() {
+            key = key.derive_child(*c)?;
+        }
+        Ok(key)
+    }
+    /// Serializes the key and encodes it with Base58Check. The key data is
+    /// the secret key prefixed by a zero byte.
+    pub fn to_base58(&self, version: Version) -> String {
+        let k = match &self.key {
+            SecretKey::Weierstrass(_, k) => k.to_bytes_be(),
+            SecretKey::Edwards(_, k) | SecretKey::Montgomery(_, k) => k.clone(),
+        };
+        self.node.serialize(version, &[&[0][..], &k].concat())
+    }
+    /// Decodes a key of the curve, which must have been serialized with the
+    /// given version.
+    pub fn from_base58(curve: Curve, s: &str, version: Version) -> Result<Self, Error> {
+        let (node, buf) = Node::deserialize(s, version)?;
+        if buf[0] != 0 {
+            return Err(DecodeError::InvalidTag.into());
+        }
+        let key = match curve {
+            Curve::Secp256k1 | Curve::P256 => {
+                let id = if curve == Curve::P256 {
+                    P256
+                } else {
+                    SECP256K1
+                };
+                let k = id.get().get_scalar_field().from_bytes_be(&buf[1..])?;
+                if k.is_zero() {
+                    return Err(DecodeError::Malformed.into());
+                }
+                SecretKey::Weierstrass(id, k)
+            }
+            Curve::Ed25519 => SecretKey::Edwards(EDWARDS25519, buf[1..].to_vec()),
+        };
+        Ok(ExtendedPrivateKey { key, node })
+    }
+}
+
+impl ExtendedPublicKey {
+    pub fn public_key(&self) -> &PublicKey {
+        &self.key
+    }
+    /// Returns HASH160 of the public key, which identifies the key.
+    pub fn identifier(&self) -> Vec<u8> {
+        identifier(&self.key)
+    }
+    /// Derives a normal child key (CKDpub). Hardened children, and the
+    /// children of Ed25519 keys, require the secret key. As in CKDpriv, an
+    /// index that yields an invalid key of secp256k1 is rejected.
+    pub fn derive_child(&self, c: ChildNumber) -> Result<Self, Error> {
+        let PublicKey::Weierstrass(id, p) = &self.key else {
+            return Err(Error::Unsupported);
+        };
+        if c.is_hardened() {
+            return Err(Error::Unsupported);
+        }
+        let ec = id.get();
+        let fq = ec.get_scalar_field();
+        let i = c.0.to_be_bytes();
+        let retry = |_: &Half, ir: &Half| [&[1], &ir[..], &i].concat();
+        let retry = (*id != SECP256K1).then_some(retry);
+        let data = [&ser_p(&self.key)[..], &i];
+        let (q, child_code) = derive_key(&self.node.chain_code, &data, retry, |il| {
+            let t = fq.from_bytes_be(il).ok()?;
+            Some(ec.get_generator() * &t + p).filter(|q| !q.is_zero())
+        })?;
+        let node = self.node.child(&self.key, c, child_code)?;
+        Ok(ExtendedPublicKey {
+            key: PublicKey::Weierstrass(*id, q),
+            node,
+        })
+    }
+    /// Derives the descendant key at the path, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, Error> {
+        let mut key = self.clone();
+        for c in path.as_ref() {
+            key = key.derive_child(*c)?;
+        }
+        Ok(key)
+    }
+    /// Serializes the key and encodes it with Base58Check.
+    pub fn to_base58(&self, version: Version) -> String {
+        self.node.serialize(version, &ser_p(&self.key))
+    }
+    /// Decodes a key of the curve, which must have been serialized with the
+    /// given version.
+    pub fn from_base58(curve: Curve, s: &str, version: Version) -> Result<Self, Error> {
+        let (node, buf) = Node::deserialize(s, version)?;
+        let key = match curve {
+            Curve::Secp256k1 | Curve::P256 => {
+                let id = if curve == Curve::P256 {
+                    P256
+                } else {
+                    SECP256K1
+                };
+                if buf[0] != 0x02 && buf[0] != 0x03 {
+                    return Err(DecodeError::InvalidTag.into());
+                }
+                PublicKey::Weierstrass(id, id.get().decode(&buf)?)
+            }
+            Curve::Ed25519 => {
+                if buf[0] != 0 {
+                    return Err(DecodeError::InvalidTag.into());
+                }
+                PublicKey::Edwards(EDWARDS25519, EDWARDS25519.get().decode(&buf[1..])?)
+            }
+        };
+        Ok(ExtendedPublicKey { key, node })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        derive_key, ChildNumber, Curve, DerivationPath, ExtendedPrivateKey, ExtendedPublicKey,
+        Half, Version,
+    };
+    use crate::ellipticcurve::Encode;
+    use crate::keys::SecretKey;
+    use crate::ops::Serialize;
+    use crate::{DecodeError, Error};
+
+    type Chain<'a> = (&'a str, &'a str, &'a str);
+
+    #[test]
+    fn bip32_vectors() {
+        // taken from BIP-32.
+        let vectors: [(&str, &[Chain]); 4] = [
+        (
+            "000102030405060708090a0b0c0d0e0f",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+                    "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+                ),
+                (
+                    "m/0'",
+                    "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7",
+                    "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+                ),
+                (
+                    "m/0'/1",
+                    "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs",
+                    "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ",
+                ),
+                (
+                    "m/0'/1/2'",
+                    "xprv9z4pot5VBttmtdRTWfWQmoH1taj2axGVzFqSb8C9xaxKymcFzXBDptWmT7FwuEzG3ryjH4ktypQSAewRiNMjANTtpgP4mLTj34bhnZX7UiM",
+                    "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5",
+                ),
+                (
+                    "m/0'/1/2'/2",
+                    "xprvA2JDeKCSNNZky6uBCviVfJSKyQ1mDYahRjijr5idH2WwLsEd4Hsb2Tyh8RfQMuPh7f7RtyzTtdrbdqqsunu5Mm3wDvUAKRHSC34sJ7in334",
+                    "xpub6FHa3pjLCk84BayeJxFW2SP4XRrFd1JYnxeLeU8EqN3vDfZmbqBqaGJAyiLjTAwm6ZLRQUMv1ZACTj37sR62cfN7fe5JnJ7dh8zL4fiyLHV",
+                ),
+                (
+                    "m/0'/1/2'/2/1000000000",
+                    "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76",
+                    "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy",
+                ),
+            ],
+        ),
+        (
+            "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K31xYSDQpPDxsXRTUcvj2iNHm5NUtrGiGG5e2DtALGdso3pGz6ssrdK4PFmM8NSpSBHNqPqm55Qn3LqFtT2emdEXVYsCzC2U",
+                    "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB",
+                ),
+                (
+                    "m/0",
+                    "xprv9vHkqa6EV4sPZHYqZznhT2NPtPCjKuDKGY38FBWLvgaDx45zo9WQRUT3dKYnjwih2yJD9mkrocEZXo1ex8G81dwSM1fwqWpWkeS3v86pgKt",
+                    "xpub69H7F5d8KSRgmmdJg2KhpAK8SR3DjMwAdkxj3ZuxV27CprR9LgpeyGmXUbC6wb7ERfvrnKZjXoUmmDznezpbZb7ap6r1D3tgFxHmwMkQTPH",
+                ),
+                (
+                    "m/0/2147483647'",
+                    "xprv9wSp6B7kry3Vj9m1zSnLvN3xH8RdsPP1Mh7fAaR7aRLcQMKTR2vidYEeEg2mUCTAwCd6vnxVrcjfy2kRgVsFawNzmjuHc2YmYRmagcEPdU9",
+                    "xpub6ASAVgeehLbnwdqV6UKMHVzgqAG8Gr6riv3Fxxpj8ksbH9ebxaEyBLZ85ySDhKiLDBrQSARLq1uNRts8RuJiHjaDMBU4Zn9h8LZNnBC5y4a",
+                ),
+                (
+                    "m/0/2147483647'/1",
+                    "xprv9zFnWC6h2cLgpmSA46vutJzBcfJ8yaJGg8cX1e5StJh45BBciYTRXSd25UEPVuesF9yog62tGAQtHjXajPPdbRCHuWS6T8XA2ECKADdw4Ef",
+                    "xpub6DF8uhdarytz3FWdA8TvFSvvAh8dP3283MY7p2V4SeE2wyWmG5mg5EwVvmdMVCQcoNJxGoWaU9DCWh89LojfZ537wTfunKau47EL2dhHKon",
+                ),
+                (
+                    "m/0/2147483647'/1/2147483646'",
+                    "xprvA1RpRA33e1JQ7ifknakTFpgNXPmW2YvmhqLQYMmrj4xJXXWYpDPS3xz7iAxn8L39njGVyuoseXzU6rcxFLJ8HFsTjSyQbLYnMpCqE2VbFWc",
+                    "xpub6ERApfZwUNrhLCkDtcHTcxd75RbzS1ed54G1LkBUHQVHQKqhMkhgbmJbZRkrgZw4koxb5JaHWkY4ALHY2grBGRjaDMzQLcgJvLJuZZvRcEL",
+                ),
+                (
+                    "m/0/2147483647'/1/2147483646'/2",
+                    "xprvA2nrNbFZABcdryreWet9Ea4LvTJcGsqrMzxHx98MMrotbir7yrKCEXw7nadnHM8Dq38EGfSh6dqA9QWTyefMLEcBYJUuekgW4BYPJcr9E7j",
+                    "xpub6FnCn6nSzZAw5Tw7cgR9bi15UV96gLZhjDstkXXxvCLsUXBGXPdSnLFbdpq8p9HmGsApME5hQTZ3emM2rnY5agb9rXpVGyy3bdW6EEgAtqt",
+                ),
+            ],
+        ),
+        (
+            "4b381541583be4423346c643850da4b320e46a87ae3d2a4e6da11eba819cd4acba45d239319ac14f863b8d5ab5a0d0c64d2e8a1e7d1457df2e5a3c51c73235be",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K25QhxbucbDDuQ4naNntJRi4KUfWT7xo4EKsHt2QJDu7KXp1A3u7Bi1j8ph3EGsZ9Xvz9dGuVrtHHs7pXeTzjuxBrCmmhgC6",
+                    "xpub661MyMwAqRbcEZVB4dScxMAdx6d4nFc9nvyvH3v4gJL378CSRZiYmhRoP7mBy6gSPSCYk6SzXPTf3ND1cZAceL7SfJ1Z3GC8vBgp2epUt13",
+                ),
+                (
+                    "m/0'",
+                    "xprv9uPDJpEQgRQfDcW7BkF7eTya6RPxXeJCqCJGHuCJ4GiRVLzkTXBAJMu2qaMWPrS7AANYqdq6vcBcBUdJCVVFceUvJFjaPdGZ2y9WACViL4L",
+                    "xpub68NZiKmJWnxxS6aaHmn81bvJeTESw724CRDs6HbuccFQN9Ku14VQrADWgqbhhTHBaohPX4CjNLf9fq9MYo6oDaPPLPxSb7gwQN3ih19Zm4Y",
+                ),
+            ],
+        ),
+        (
+            "3ddd5602285899a946114506157c7997e5444528f3003f6134712147db19b678",
+            &[
+                (
+                    "m",
+                    "xprv9s21ZrQH143K48vGoLGRPxgo2JNkJ3J3fqkirQC2zVdk5Dgd5w14S7fRDyHH4dWNHUgkvsvNDCkvAwcSHNAQwhwgNMgZhLtQC63zxwhQmRv",
+                    "xpub661MyMwAqRbcGczjuMoRm6dXaLDEhW1u34gKenbeYqAix21mdUKJyuyu5F1rzYGVxyL6tmgBUAEPrEz92mBXjByMRiJdba9wpnN37RLLAXa",
+                ),
+                (
+                    "m/0'",
+                    "xprv9vB7xEWwNp9kh1wQRfCCQMnZUEG21LpbR9NPCNN1dwhiZkjjeGRnaALmPXCX7SgjFTiCTT6bXes17boXtjq3xLpcDjzEuGLQBM5ohqkao9G",
+                    "xpub69AUMk3qDBi3uW1sXgjCmVjJ2G6WQoYSnNHyzkmdCHEhSZ4tBok37xfFEqHd2AddP56Tqp4o56AePAgCjYdvpW2PU2jbUPFKsav5ut6Ch1m",
+                ),
+                (
+                    "m/0'/1'",
+                    "xprv9xJocDuwtYCMNAo3Zw76WENQeAS6WGXQ55RCy7tDJ8oALr4FWkuVoHJeHVAcAqiZLE7Je3vZJHxspZdFHfnBEjHqU5hG1Jaj32dVoS6XLT1",
+                    "xpub6BJA1jSqiukeaesWfxe6sNK9CCGaujFFSJLomWHprUL9DePQ4JDkM5d88n49sMGJxrhpjazuXYWdMf17C9T5XnxkopaeS7jGk1GyyVziaMt",
+                ),
+            ],
+        ),
+        ];
+        for (seed, chains) in vectors {
+            let master =
+                ExtendedPrivateKey::from_seed(Curve::Secp256k1, &hex::decode(seed).unwrap())
+                    .unwrap();
+            let mut parent: Option<ExtendedPublicKey> = None;
+            for (path, xprv, xpub) in chains {
+                let path: DerivationPath = path.parse().unwrap();
+                let key = master.derive_path(&path).unwrap();
+                let public = key.extended_public_key();
+                assert_eq!(key.to_base58(Version::XPRV), *xprv);
+                assert_eq!(public.to_base58(Version::XPUB), *xpub);
+                let decoded =
+                    ExtendedPrivateKey::from_base58(Curve::Secp256k1, xprv, Version::XPRV);
+                assert!(decoded.unwrap() == key);
+                let decoded = ExtendedPublicKey::from_base58(Curve::Secp256k1, xpub, Version::XPUB);
+                assert!(decoded.unwrap() == public);
+                assert_eq!(key.depth() as usize, path.as_ref().len());
+
+                // the normal children are derived from the public key too.
+                let c = key.child_number();
+                match parent {
+                    Some(p) if !c.is_hardened() => assert!(p.derive_child(c).unwrap() == public),
+                    Some(p) => assert_eq!(p.derive_child(c).err(), Some(Error::Unsupported)),
+                    None => {}
+                }
+                parent = Some(public);
+            }
+        }
+    }
+
+    #[test]
+    fn bip32_invalid_keys() {
+        // taken from test vector 5 of BIP-32.
+        let xprv = [
+            // a private key version with a public key.
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGTQQD3dC4H2D5GBj7vWvSQaaBv5cxi9gafk7NF3pnBju6dwKvH", DecodeError::InvalidTag),
+            // invalid prefixes of the private key.
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFGpWnsj83BHtEy5Zt8CcDr1UiRXuWCmTQLxEK9vbz5gPstX92JQ", DecodeError::InvalidTag),
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD9y5gkZ6Eq3Rjuahrv17fEQ3Qen6J", DecodeError::InvalidTag),
+            // zero depth with a parent fingerprint, or with an index.
+            ("xprv9s2SPatNQ9Vc6GTbVMFPFo7jsaZySyzk7L8n2uqKXJen3KUmvQNTuLh3fhZMBoG3G4ZW1N2kZuHEPY53qmbZzCHshoQnNf4GvELZfqTUrcv", DecodeError::Malformed),
+            ("xprv9s21ZrQYdgnodnKW4Drm1Qg7poU6Gf2WUDsjPxvYiK7iLBMrsjbnF1wsZZQgmXNeMSG3s7jmHk1b3JrzhG5w8mwXGxqFxfrweico7k8DtxR", DecodeError::Malformed),
+            // an unknown version.
+            ("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHGMQzT7ayAmfo4z3gY5KfbrZWZ6St24UVf2Qgo6oujFktLHdHY4", DecodeError::InvalidTag),
+            // the private key is 0, or n.
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzF93Y5wvzdUayhgkkFoicQZcP3y52uPPxFnfoLZB21Teqt1VvEHx", DecodeError::Malformed),
+            ("xprv9s21ZrQH143K24Mfq5zL5MhWK9hUhhGbd45hLXo2Pq2oqzMMo63oStZzFAzHGBP2UuGCqWLTAPLcMtD5SDKr24z3aiUvKr9bJpdrcLg1y3G", DecodeError::NonCanonical),
+            // an invalid checksum.
+            ("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHL", DecodeError::Malformed),
+        ];
+        for (s, err) in xprv {
+            let got = ExtendedPrivateKey::from_base58(Curve::Secp256k1, s, Version::XPRV);
+            assert_eq!(got.err(), Some(err.into()), "{s}");
+        }
+        let xpub = [
+            // a public key version with a private key.
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6LBpB85b3D2yc8sfvZU521AAwdZafEz7mnzBBsz4wKY5fTtTQBm", DecodeError::InvalidTag),
+            // invalid prefixes of the public key.
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Txnt3siSujt9RCVYsx4qHZGc62TG4McvMGcAUjeuwZdduYEvFn", DecodeError::InvalidTag),
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6N8ZMMXctdiCjxTNq964yKkwrkBJJwpzZS4HS2fxvyYUA4q2Xe4", DecodeError::InvalidTag),
+            // an x-coordinate that is not on the curve.
+            ("xpub661MyMwAqRbcEYS8w7XLSVeEsBXy79zSzH1J8vCdxAZningWLdN3zgtU6Q5JXayek4PRsn35jii4veMimro1xefsM58PgBMrvdYre8QyULY", DecodeError::NotOnCurve),
+            // zero depth with a parent fingerprint, or with an index.
+            ("xpub661no6RGEX3uJkY4bNnPcw4URcQTrSibUZ4NqJEw5eBkv7ovTwgiT91XX27VbEXGENhYRCf7hyEbWrR3FewATdCEebj6znwMfQkhRYHRLpJ", DecodeError::Malformed),
+            ("xpub661MyMwAuDcm6CRQ5N4qiHKrJ39Xe1R1NyfouMKTTWcguwVcfrZJaNvhpebzGerh7gucBvzEQWRugZDuDXjNDRmXzSZe4c7mnTK97pTvGS8", DecodeError::Malformed),
+            // an unknown version.
+            ("DMwo58pR1QLEFihHiXPVykYB6fJmsTeHvyTp7hRThAtCX8CvYzgPcn8XnmdfHPmHJiEDXkTiJTVV9rHEBUem2mwVbbNfvT2MTcAqj3nesx8uBf9", DecodeError::InvalidTag),
+        ];
+        for (s, err) in xpub {
+            let got = ExtendedPublicKey::from_base58(Curve::Secp256k1, s, Version::XPUB);
+            assert_eq!(got.err(), Some(err.into()), "{s}");
+        }
+    }
+
+    #[test]
+    fn invalid_derivation() {
+        // IL is rejected once, then SLIP-0010 derives the key again, whereas
+        // BIP-32 fails.
+        let retry = |il: &Half, ir: &Half| [&il[..], ir].concat();
+        let mut first = true;
+        let got = derive_key(b"key", &[b"data"], Some(retry), |il| {
+            Some(*il).filter(|_| !std::mem::take(&mut first))
+        });
+        assert!(got.is_ok());
+        let mut first = true;
+        let got = derive_key(
+            b"key",
+            &[b"data"],
+            None::<fn(&Half, &Half) -> Vec<u8>>,
+            |il| Some(*il).filter(|_| !std::mem::take(&mut first)),
+        );
+        assert_eq!(got.err(), Some(Error::InvalidParameters));
+    }
+
+    fn secret_bytes(key: &ExtendedPrivateKey) -> Vec<u8> {
+        match key.secret_key() {
+            SecretKey::Weierstrass(_, k) => k.to_bytes_be(),
+            SecretKey::Edwards(_, k) | SecretKey::Montgomery(_, k) => k.clone(),
+        }
+    }
+
+    #[test]
+    fn slip10_vectors() {
+        // taken from SLIP-0010: (seed, path, fingerprint, chain code, secret
+        // key, public key).
+        let vectors = [
+            (
+                Curve::P256,
+                "000102030405060708090a0b0c0d0e0f",
+                "m",
+                "00000000",
+                "beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea",
+                "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2",
+                "0266874dc6ade47b3ecd096745ca09bcd29638dd52c2c12117b11ed3e458cfa9e8",
+            ),
+            (
+                Curve::P256,
+                "000102030405060708090a0b0c0d0e0f",
+                "m/0'",
+                "be6105b5",
+                "3460cea53e6a6bb5fb391eeef3237ffd8724bf0a40e94943c98b83825342ee11",
+                "6939694369114c67917a182c59ddb8cafc3004e63ca5d3b84403ba8613debc0c",
+                "0384610f5ecffe8fda089363a41f56a5c7ffc1d81b59a612d0d649b2d22355590c",
+            ),
+            // IL is not less than q when deriving the last child.
+            (
+                Curve::P256,
+                "000102030405060708090a0b0c0d0e0f",
+                "m/28578'/33941",
+                "3e2b7bc6",
+                "9e87fe95031f14736774cd82f25fd885065cb7c358c1edf813c72af535e83071",
+                "092154eed4af83e078ff9b84322015aefe5769e31270f62c3f66c33888335f3a",
+                "0235bfee614c0d5b2cae260000bb1d0d84b270099ad790022c1ae0b2e782efe120",
+            ),
+            // IL is not less than q when deriving the master key.
+            (
+                Curve::P256,
+                "a7305bc8df8d0951f0cb224c0e95d7707cbdf2c6ce7e8d481fec69c7ff5e9446",
+                "m",
+                "00000000",
+                "7762f9729fed06121fd13f326884c82f59aa95c57ac492ce8c9654e60efd130c",
+                "3b8c18469a4634517d6d0b65448f8e6c62091b45540a1743c5846be55d47d88f",
+                "0383619fadcde31063d8c5cb00dbfe1713f3e6fa169d8541a798752a1c1ca0cb20",
+            ),
+            (
+                Curve::Ed25519,
+                "000102030405060708090a0b0c0d0e0f",
+                "m",
+                "00000000",
+                "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+                "00a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed",
+            ),
+            (
+                Curve::Ed25519,
+                "000102030405060708090a0b0c0d0e0f",
+                "m/0'",
+                "ddebc675",
+                "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+                "008c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c",
+            ),
+            (
+                Curve::Ed25519,
+                "000102030405060708090a0b0c0d0e0f",
+                "m/0'/1'/2'/2'/1000000000'",
+                "d6322ccd",
+                "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+                "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+                "003c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a",
+            ),
+            (
+                Curve::Ed25519,
+                "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542",
+                "m/0'/2147483647'/1'/2147483646'/2'",
+                "422c654b",
+                "5d70af781f3a37b829f0d060924d5e960bdc02e85423494afc0b1a41bbe196d4",
+                "551d333177df541ad876a60ea71f00447931c0a9da16f227c11ea080d7391b8d",
+                "0047150c75db263559a70d5778bf36abbab30fb061ad69f69ece61a72b0cfa4fc0",
+            ),
+        ];
+        for (curve, seed, path, fingerprint, chain_code, secret, public) in vectors {
+            let master = ExtendedPrivateKey::from_seed(curve, &hex::decode(seed).unwrap());
+            let key = master.unwrap().derive_path(&path.parse().unwrap()).unwrap();
+            let got = [
+                hex::encode(key.parent_fingerprint()),
+                hex::encode(key.chain_code()),
+                hex::encode(secret_bytes(&key)),
+                hex::encode(key.extended_public_key().public_key().encode(true)),
+            ];
+            // the public keys of Ed25519 are prefixed by a zero byte.
+            let public = public.strip_prefix("00").unwrap_or(public);
+            let want = [fingerprint, chain_code, secret, public];
+            assert_eq!(got, want, "{path}");
+        }
+    }
+
+    #[test]
+    fn derivation() {
+        let seed = [0x5a; 32];
+        for curve in [Curve::Secp256k1, Curve::P256, Curve::Ed25519] {
+            let master = ExtendedPrivateKey::from_seed(curve, &seed).unwrap();
+            let path: DerivationPath = "m/1'/2'".parse().unwrap();
+            let key = master.derive_path(&path).unwrap();
+            let normal = ChildNumber::normal(3).unwrap();
+            let (xprv, xpub) = (
+                key.to_base58(Version::TPRV),
+                key.extended_public_key().to_base58(Version::TPUB),
+            );
+            assert!(xprv.starts_with("tprv") && xpub.starts_with("tpub"));
+            let decoded = ExtendedPrivateKey::from_base58(curve, &xprv, Version::TPRV).unwrap();
+            assert!(decoded == key);
+            let decoded = ExtendedPublicKey::from_base58(curve, &xpub, Version::TPUB).unwrap();
+            assert!(decoded == key.extended_public_key());
+            assert_eq!(
+                ExtendedPrivateKey::from_base58(curve, &xprv, Version::XPRV).err(),
+                Some(DecodeError::InvalidTag.into())
+            );
+            if curve == Curve::Ed25519 {
+                assert_eq!(key.derive_child(normal).err(), Some(Error::Unsupported));
+                let public = key.extended_public_key();
+                assert_eq!(public.derive_child(normal).err(), Some(Error::Unsupported));
+            } else {
+                let child = key.derive_child(normal).unwrap();
+                let public = key.extended_public_key().derive_child(normal).unwrap();
+                assert!(child.extended_public_key() == public);
+                assert_eq!(child.parent_fingerprint(), key.identifier()[..4]);
+            }
+        }
+        assert_eq!(
+            ExtendedPrivateKey::from_seed(Curve::Secp256k1, &[0; 15]).err(),
+            Some(Error::InvalidParameters)
+        );
+        assert_eq!(
+            ExtendedPrivateKey::from_seed(Curve::Secp256k1, &[0; 65]).err(),
+            Some(Error::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn bad_serializations() {
+        let key = ExtendedPrivateKey::from_seed(Curve::Secp256k1, &[1; 16]).unwrap();
+        let xprv = key.to_base58(Version::XPRV);
+        let xpub = key.extended_public_key().to_base58(Version::XPUB);
+        let mut wrong_checksum = xprv.clone().into_bytes();
+        wrong_checksum[10] = if wrong_checksum[10] == b'a' {
+            b'b'
+        } else {
+            b'a'
+        };
+        let wrong_checksum = String::from_utf8(wrong_checksum).unwrap();
+        let reencode = |s: &str, f: &dyn Fn(&mut Vec<u8>)| {
+            let mut buf = bs58::decode(s).with_check(None).into_vec().unwrap();
+            f(&mut buf);
+            bs58::encode(buf).with_check().into_string()
+        };
+        let cases = [
+            (wrong_checksum, DecodeError::Malformed),
+            (
+                reencode(&xprv, &|b| b.truncate(77)),
+                DecodeError::WrongLength,
+            ),
+            // a master key with a parent fingerprint, or with an index.
+            (reencode(&xprv, &|b| b[5] = 1), DecodeError::Malformed),
+            (reencode(&xprv, &|b| b[12] = 1), DecodeError::Malformed),
+            // the secret key is not prefixed by zero, or is not less than q.
+            (reencode(&xprv, &|b| b[45] = 1), DecodeError::InvalidTag),
+            (
+                reencode(&xprv, &|b| b[46..].fill(0xff)),
+                DecodeError::NonCanonical,
+            ),
+        ];
+        for (s, err) in cases {
+            let got = ExtendedPrivateKey::from_base58(Curve::Secp256k1, &s, Version::XPRV);
+            assert_eq!(got.err(), Some(err.into()));
+        }
+        // a public key that is not on the curve.
+        let s = reencode(&xpub, &|b| b[46..].fill(0));
+        let got = ExtendedPublicKey::from_base58(Curve::Secp256k1, &s, Version::XPUB);
+        assert!(got.is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! This is documentation for the `path` module.
+//!
+//! The path module defines the indices of child keys, and the derivation
+//! paths, such as m/44'/0'/0'/0/0, that list them starting from the master
+//! key.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{DecodeError, Error};
+
+/// ChildNumber is the index of a child key. The indices not less than 2^31
+/// denote hardened children, which are written with an apostrophe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChildNumber(pub u32);
+
+impl ChildNumber {
+    pub const HARDENED: u32 = 1 << 31;
+    /// Returns the normal child of the index, which must be less than 2^31.
+    pub fn normal(index: u32) -> Result<Self, Error> {
+        if index >= Self::HARDENED {
+            return Err(Error::InvalidParameters);
+        }
+        Ok(ChildNumber(index))
+    }
+    /// Returns the hardened child of the index, which must be less than 2^31.
+    pub fn hardened(index: u32) -> Result<Self, Error> {
+        Self::normal(index).map(|c| ChildNumber(c.0 | Self::HARDENED))
+    }
+    pub fn is_hardened(self) -> bool {
+        self.0 >= Self::HARDENED
+    }
+    /// Returns the index without the hardened bit.
+    pub fn index(self) -> u32 {
+        self.0 & !Self::HARDENED
+    }
+}
+
+impl Display for ChildNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mark = if self.is_hardened() { "'" } else { "" };
+        write!(f, "{}{}", self.index(), mark)
+    }
+}
+
+impl FromStr for ChildNumber {
+    type Err = Error;
+    /// Parses an index in decimal, followed by an apostrophe, 'h', or 'H'
+    /// for hardened children.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, hardened) = match s.strip_suffix(['\'', 'h', 'H']) {
+            Some(digits) => (digits, true),
+            None => (s, false),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(DecodeError::Malformed.into());
+        }
+        let index = digits.parse().map_err(|_| DecodeError::Malformed)?;
+        if hardened {
+            Self::hardened(index)
+        } else {
+            Self::normal(index)
+        }
+    }
+}
+
+/// DerivationPath is the list of children that leads from the master key to
+/// a key of the hierarchy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DerivationPath(Vec<ChildNumber>);
+
+impl DerivationPath {
+    /// Returns the path extended with a child.
+    pub fn child(&self, c: ChildNumber) -> Self {
+        let mut path = self.clone();
+        path.0.push(c);
+        path
+    }
+}
+
+impl AsRef<[ChildNumber]> for DerivationPath {
+    fn as_ref(&self) -> &[ChildNumber] {
+        &self.0
+    }
+}
+
+impl From<Vec<ChildNumber>> for DerivationPath {
+    fn from(path: Vec<ChildNumber>) -> Self {
+        DerivationPath(path)
+    }
+}
+
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "m")?;
+        self.0.iter().try_for_each(|c| write!(f, "/{}", c))
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+    /// Parses a path that starts with "m" and lists the children separated
+    /// by slashes, e.g., "m/44'/0'/0'/0/0".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(DecodeError::Malformed.into());
+        }
+        parts
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(DerivationPath)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChildNumber, DerivationPath};
+    use crate::{DecodeError, Error};
+
+    #[test]
+    fn parse() {
+        let path: DerivationPath = "m/44'/0H/0h/0/2147483647".parse().unwrap();
+        let want = [
+            ChildNumber::hardened(44).unwrap(),
+            ChildNumber::hardened(0).unwrap(),
+            ChildNumber(0x8000_0000),
+            ChildNumber::normal(0).unwrap(),
+            ChildNumber(0x7fff_ffff),
+        ];
+        assert_eq!(path.as_ref(), want);
+        assert_eq!(path.to_string(), "m/44'/0'/0'/0/2147483647");
+        assert_eq!("m".parse(), Ok(DerivationPath::default()));
+        assert_eq!(
+            DerivationPath::default().child(ChildNumber(1)).to_string(),
+            "m/1"
+        );
+
+        for s in [
+            "", "/0", "m/", "0/1", "m//1", "m/1''", "m/-1", "m/+1", "m/x", "M/1",
+        ] {
+            assert_eq!(
+                s.parse::<DerivationPath>(),
+                Err(DecodeError::Malformed.into()),
+                "{s}"
+            );
+        }
+        for s in ["m/2147483648", "m/2147483648'"] {
+            assert_eq!(s.parse::<DerivationPath>(), Err(Error::InvalidParameters));
+        }
+        assert_eq!(
+            "m/4294967296".parse::<DerivationPath>(),
+            Err(DecodeError::Malformed.into())
+        );
+    }
+}
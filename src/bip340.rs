@@ -0,0 +1,378 @@
+//! This is documentation for the `bip340` module.
+//!
+//! The bip340 module implements the Schnorr signatures of BIP-340 over
+//! secp256k1, as used by Bitcoin since Taproot, and the key tweaking of
+//! BIP-341.
+//!
+//! Public keys are x-only: a key is the x-coordinate of a point, and it
+//! stands for the point with even y-coordinate. Accordingly, the signer
+//! negates its secret key and its nonce whenever their points have odd
+//! y-coordinates. The nonce is derived from the secret key, the message, and
+//! auxiliary random bytes, which protect against side-channel attacks.
+//!
+//! ```
+//!  use redox_ecc::bip340::{public_key, sign, verify};
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, SECP256K1};
+//!  use redox_ecc::ops::FromFactory;
+//!
+//!  let d = SECP256K1.get().get_scalar_field().from(0x1234);
+//!  let public = public_key(&d).unwrap();
+//!  let sig = sign(&d, b"message", &[0u8; 32]).unwrap();
+//!  assert!(verify(&public, b"message", &sig).is_ok());
+//!  assert!(verify(&public, b"other message", &sig).is_err());
+//! ```
+
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::batch::{verify_equations, Equation};
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode};
+use crate::instances::{GetCurve, SECP256K1};
+use crate::ops::{Deserialize, Serialize};
+use crate::scalarfield::{Scalar, Zq};
+use crate::weierstrass::Point;
+use crate::{DecodeError, Error};
+
+/// Size in bytes of public keys, and of each half of a signature.
+const SIZE: usize = 32;
+
+/// Hashes the data with SHA-256 prefixed twice by the hash of the tag, so
+/// the hashes computed for different purposes are independent.
+pub fn tagged_hash(tag: &str, data: &[&[u8]]) -> Vec<u8> {
+    let t = Sha256::digest(tag.as_bytes());
+    let mut h = Sha256::new().chain_update(t).chain_update(t);
+    for d in data {
+        h.update(d);
+    }
+    h.finalize().to_vec()
+}
+
+fn has_even_y(p: &Point) -> bool {
+    p.encode(true)[0] == 0x02
+}
+
+fn x_only(p: &Point) -> Vec<u8> {
+    p.encode(true)[1..].to_vec()
+}
+
+/// XOnlyPublicKey is a point of secp256k1 with even y-coordinate, which is
+/// encoded by its x-coordinate alone.
+#[derive(Clone, PartialEq, Eq)]
+pub struct XOnlyPublicKey(Point);
+
+impl XOnlyPublicKey {
+    /// Decodes a key of 32 bytes, which is lifted to the point with even
+    /// y-coordinate. The x-coordinate must be less than p.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, Error> {
+        if buf.len() != SIZE {
+            return Err(DecodeError::WrongLength.into());
+        }
+        let p = SECP256K1.get().decode(&[&[0x02], buf].concat())?;
+        Ok(XOnlyPublicKey(p))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        x_only(&self.0)
+    }
+    pub fn point(&self) -> &Point {
+        &self.0
+    }
+    /// Tweaks the internal key of a Taproot output as in BIP-341, committing
+    /// to the Merkle root of its script tree, if any. Returns the output key,
+    /// and whether the tweaked point had an odd y-coordinate, which the
+    /// control blocks of script-path spends carry.
+    pub fn tweak(&self, merkle_root: Option<&[u8]>) -> Result<(Self, bool), Error> {
+        let ec = SECP256K1.get();
+        let t = tap_tweak(&ec.get_scalar_field(), &self.to_bytes(), merkle_root)?;
+        let q = self.0.clone() + ec.get_generator() * &t;
+        if q.is_zero() {
+            return Err(Error::InvalidPoint);
+        }
+        let is_odd = !has_even_y(&q);
+        let q = if is_odd { -q } else { q };
+        Ok((XOnlyPublicKey(q), is_odd))
+    }
+}
+
+/// Computes the tweak of BIP-341 for a public key and the Merkle root of a
+/// script tree, which is either empty or 32 bytes long.
+fn tap_tweak(fq: &Zq, public: &[u8], merkle_root: Option<&[u8]>) -> Result<Scalar, Error> {
+    let h = merkle_root.unwrap_or_default();
+    if !h.is_empty() && h.len() != SIZE {
+        return Err(DecodeError::WrongLength.into());
+    }
+    // a tweak not less than q happens with negligible probability.
+    fq.from_bytes_be(&tagged_hash("TapTweak", &[public, h]))
+        .map_err(|_| Error::InvalidParameters)
+}
+
+/// Returns the secret key negated, if needed, so that its point has even
+/// y-coordinate, together with the x-only public key.
+fn normalize(d: &Scalar) -> Result<(Scalar, XOnlyPublicKey), Error> {
+    let ec = SECP256K1.get();
+    if d.get_field() != ec.get_scalar_field() {
+        return Err(Error::GroupMismatch);
+    }
+    if d.is_zero() {
+        return Err(Error::InvalidParameters);
+    }
+    let p = ec.get_generator() * d;
+    if has_even_y(&p) {
+        Ok((d.clone(), XOnlyPublicKey(p)))
+    } else {
+        Ok((-d, XOnlyPublicKey(-p)))
+    }
+}
+
+/// Derives the x-only public key of the secret key `d`.
+pub fn public_key(d: &Scalar) -> Result<XOnlyPublicKey, Error> {
+    normalize(d).map(|(_, p)| p)
+}
+
+/// Tweaks the secret key `d` as in BIP-341, so that it corresponds to the
+/// output key returned by [`XOnlyPublicKey::tweak`] for the public key of
+/// `d`, and can sign for key-path spends.
+pub fn tweak_secret_key(d: &Scalar, merkle_root: Option<&[u8]>) -> Result<Scalar, Error> {
+    let (d, p) = normalize(d)?;
+    let t = tap_tweak(&d.get_field(), &p.to_bytes(), merkle_root)?;
+    let d = d + t;
+    if d.is_zero() {
+        return Err(Error::InvalidParameters);
+    }
+    Ok(d)
+}
+
+fn challenge(fq: &Zq, r: &[u8], public: &[u8], msg: &[u8]) -> Scalar {
+    fq.from_bytes_wide_be(&tagged_hash("BIP0340/challenge", &[r, public, msg]))
+}
+
+/// Signs a message of any length with the secret key `d`. The auxiliary
+/// randomness should be fresh random bytes; with a fixed value, e.g., all
+/// zeros, the signatures are still secure but deterministic.
+pub fn sign(d: &Scalar, msg: &[u8], aux_rand: &[u8; SIZE]) -> Result<Vec<u8>, Error> {
+    let (d, public) = normalize(d)?;
+    let ec = SECP256K1.get();
+    let fq = ec.get_scalar_field();
+    let pk = public.to_bytes();
+    let mask = tagged_hash("BIP0340/aux", &[aux_rand]);
+    let t: Vec<u8> = d
+        .to_bytes_be()
+        .iter()
+        .zip(mask)
+        .map(|(a, b)| a ^ b)
+        .collect();
+    let k = fq.from_bytes_wide_be(&tagged_hash("BIP0340/nonce", &[&t, &pk, msg]));
+    if k.is_zero() {
+        return Err(Error::InvalidParameters);
+    }
+    let r = ec.get_generator() * &k;
+    let k = if has_even_y(&r) { k } else { -k };
+    let rx = x_only(&r);
+    let e = challenge(&fq, &rx, &pk, msg);
+    let sig = [rx, (k + e * &d).to_bytes_be()].concat();
+    // as recommended, the signature is verified to detect computation errors.
+    verify(&public, msg, &sig)?;
+    Ok(sig)
+}
+
+/// Splits a signature into r, as bytes, and s, which must be less than q.
+fn split<'a>(fq: &Zq, sig: &'a [u8]) -> Result<(&'a [u8], Scalar), Error> {
+    if sig.len() != 2 * SIZE {
+        return Err(DecodeError::WrongLength.into());
+    }
+    let (r, s) = sig.split_at(SIZE);
+    let s = fq.from_bytes_be(s).map_err(|_| Error::InvalidSignature)?;
+    Ok((r, s))
+}
+
+/// Verifies the signature of a message.
+pub fn verify(public: &XOnlyPublicKey, msg: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let ec = SECP256K1.get();
+    let fq = ec.get_scalar_field();
+    let (r, s) = split(&fq, sig)?;
+    let e = challenge(&fq, r, &public.to_bytes(), msg);
+    let big_r = ec.get_generator() * &s - &public.0 * &e;
+    // x(R) < p, so r not less than p is rejected too.
+    if big_r.is_zero() || !has_even_y(&big_r) || x_only(&big_r) != r {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// BatchItem is a signature to be verified in a batch, together with the
+/// message and the public key.
+#[derive(Clone, Copy)]
+pub struct BatchItem<'a> {
+    pub public: &'a XOnlyPublicKey,
+    pub msg: &'a [u8],
+    pub sig: &'a [u8],
+}
+
+/// Returns the verification equation sG = R + eP of a signature, where R is
+/// lifted from r.
+fn equation(item: &BatchItem) -> Result<Equation<Point>, Error> {
+    let fq = SECP256K1.get().get_scalar_field();
+    let (r, s) = split(&fq, item.sig)?;
+    let big_r = XOnlyPublicKey::from_bytes(r).map_err(|_| Error::InvalidSignature)?;
+    let k = challenge(&fq, r, &item.public.to_bytes(), item.msg);
+    Ok(Equation {
+        s,
+        r: big_r.0,
+        k,
+        a: item.public.0.clone(),
+    })
+}
+
+/// Verifies a batch of signatures as described in BIP-340. Since the group
+/// has prime order, the result agrees with [`verify`].
+///
+/// If some signatures are not valid, the error lists their indices.
+pub fn verify_batch<R: RngCore + CryptoRng>(items: &[BatchItem], rng: &mut R) -> Result<(), Error> {
+    let mut invalid = Vec::new();
+    let mut indices = Vec::new();
+    let mut eqs = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        match equation(item) {
+            Ok(eq) => {
+                indices.push(i);
+                eqs.push(eq);
+            }
+            Err(_) => invalid.push(i),
+        }
+    }
+    if !eqs.is_empty() && !verify_equations(&SECP256K1.get(), &eqs, rng)? {
+        let failed = indices.into_iter().map(|i| (i, &items[i]));
+        invalid.extend(
+            failed
+                .filter(|(_, item)| verify(item.public, item.msg, item.sig).is_err())
+                .map(|(i, _)| i),
+        );
+    }
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    invalid.sort_unstable();
+    Err(Error::InvalidBatch(invalid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        public_key, sign, tagged_hash, tweak_secret_key, verify, verify_batch, BatchItem,
+        XOnlyPublicKey,
+    };
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::instances::{GetCurve, P256, SECP256K1};
+    use crate::ops::FromFactory;
+    use crate::{DecodeError, Error};
+
+    #[test]
+    fn taproot_tweak() {
+        // taken from the wallet test vectors of BIP-341.
+        let vectors = [
+            (
+                "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d",
+                "",
+                "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+            ),
+            (
+                "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+                "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+                "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+            ),
+        ];
+        for (internal, root, output) in vectors {
+            let key = XOnlyPublicKey::from_bytes(&hex::decode(internal).unwrap()).unwrap();
+            let root = hex::decode(root).unwrap();
+            let root = Some(&root[..]).filter(|r| !r.is_empty());
+            let (q, _) = key.tweak(root).unwrap();
+            assert_eq!(hex::encode(q.to_bytes()), output);
+        }
+
+        // the tweaked secret key signs for the output key.
+        let fq = SECP256K1.get().get_scalar_field();
+        let d = fq.from(0x1234);
+        for d in [-&d, d] {
+            for root in [None, Some(&[7u8; 32][..])] {
+                let (q, is_odd) = public_key(&d).unwrap().tweak(root).unwrap();
+                let t = tweak_secret_key(&d, root).unwrap();
+                assert!(public_key(&t).unwrap() == q);
+                let p = SECP256K1.get().get_generator() * &t;
+                assert!(
+                    p == if is_odd {
+                        -q.point()
+                    } else {
+                        q.point().clone()
+                    }
+                );
+                let sig = sign(&t, b"spend", &[1u8; 32]).unwrap();
+                assert!(verify(&q, b"spend", &sig).is_ok());
+            }
+        }
+        let key = public_key(&fq.from(1)).unwrap();
+        assert_eq!(
+            key.tweak(Some(&[0u8; 31])).err(),
+            Some(DecodeError::WrongLength.into())
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let fq = SECP256K1.get().get_scalar_field();
+        let d = fq.from(0x1234);
+        // d and -d share the x-only public key.
+        assert!(public_key(&d).unwrap() == public_key(&-&d).unwrap());
+        let key = public_key(&d).unwrap();
+        assert!(XOnlyPublicKey::from_bytes(&key.to_bytes()).unwrap() == key);
+        assert_eq!(
+            XOnlyPublicKey::from_bytes(&[0u8; 33]).err(),
+            Some(DecodeError::WrongLength.into())
+        );
+        assert_eq!(
+            public_key(&fq.from(0)).err(),
+            Some(Error::InvalidParameters)
+        );
+        let d = P256.get().get_scalar_field().from(1);
+        assert_eq!(public_key(&d).err(), Some(Error::GroupMismatch));
+        let h = tagged_hash("BIP0340/challenge", &[b"ab", b"c"]);
+        assert_eq!(h, tagged_hash("BIP0340/challenge", &[b"abc"]));
+        assert!(h != tagged_hash("BIP0340/nonce", &[b"abc"]));
+    }
+
+    type Signed = (XOnlyPublicKey, Vec<u8>, Vec<u8>);
+
+    fn items(signed: &[Signed]) -> Vec<BatchItem<'_>> {
+        let it = signed
+            .iter()
+            .map(|(public, msg, sig)| BatchItem { public, msg, sig });
+        it.collect()
+    }
+
+    #[test]
+    fn batches() {
+        let mut rng = rand::thread_rng();
+        let fq = SECP256K1.get().get_scalar_field();
+        let mut signed = Vec::new();
+        for i in 1..10u8 {
+            let d = fq.from(i as u32 * 7919);
+            let msg = vec![i; i as usize];
+            let sig = sign(&d, &msg, &[i; 32]).unwrap();
+            signed.push((public_key(&d).unwrap(), msg, sig));
+        }
+        assert_eq!(verify_batch(&items(&signed), &mut rng), Ok(()));
+        assert_eq!(verify_batch(&[], &mut rng), Ok(()));
+
+        // a wrong message, a wrong signature, an R that cannot be lifted, and
+        // a malformed signature.
+        let mut bad = signed.clone();
+        bad[1].1.push(0);
+        bad[3].2[63] ^= 1;
+        bad[5].2[..32].copy_from_slice(&[0xff; 32]);
+        bad[8].2.pop();
+        assert_eq!(
+            verify_batch(&items(&bad), &mut rng),
+            Err(Error::InvalidBatch(vec![1, 3, 5, 8]))
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! This is documentation for the `element` module.
+//!
+//! The element module implements the group law of decaf448 on top of the
+//! points of edwards448.
+
+use impl_ops::impl_op_ex;
+use num_traits::identities::Zero;
+
+use std::ops;
+
+use crate::decaf448::Group;
+use crate::impl_quotient_element;
+
+impl_quotient_element!(
+    group: Group,
+    name: "decaf448",
+    curve: "edwards448",
+    points: "two",
+    size: 56,
+    // the identity is represented by the points of order 1 and 2.
+    is_zero: |p| p.c.x.is_zero(),
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-5.3.3
+    eq: |x1, y1, x2, y2| x1 * y2 == y1 * x2,
+);
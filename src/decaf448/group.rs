@@ -0,0 +1,323 @@
+//! This is documentation for the `group` module.
+//!
+//! The group module implements the encoding, the decoding, and the one-way
+//! map of decaf448 following RFC 9496.
+
+use num_bigint::{BigInt, Sign};
+use sha3::Shake256;
+
+use std::sync::{Arc, OnceLock};
+
+use crate::decaf448::Element;
+use crate::edwards::{Curve, Point, ProyCoordinates};
+use crate::ellipticcurve::{Decode, DecodePolicy, EllipticCurve};
+use crate::expander::expand_message_xof;
+use crate::field::{CMov, Field};
+use crate::impl_quotient_group;
+use crate::instances::{GetCurve, EDWARDS448};
+use crate::ops::{Deserialize, FromFactory, Serialize};
+use crate::primefield::FpElt;
+use crate::DecodeError;
+
+/// Group is the decaf448 group, whose elements are represented by points of
+/// the edwards448 curve. Its order is the prime
+/// r = 2^446-13818066809895115352007386748515426880336692474882178609894547503885.
+///
+/// Cloning a group is cheap, since clones share the same parameters.
+#[derive(Clone)]
+pub struct Group(Arc<Inner>);
+
+struct Inner {
+    ec: Curve,
+    d: FpElt,
+    exp: BigInt,
+    sqrt_minus_d: FpElt,
+    invsqrt_minus_d: FpElt,
+    one_minus_d: FpElt,
+    one_minus_two_d: FpElt,
+}
+
+static GROUP: OnceLock<Group> = OnceLock::new();
+
+impl Group {
+    /// Returns the decaf448 group. All the calls share the same parameters,
+    /// so comparing the groups obtained is cheap.
+    pub fn new() -> Self {
+        GROUP.get_or_init(|| Group::build(EDWARDS448.get())).clone()
+    }
+    /// Maps 112 uniformly random bytes to an element, such that the output
+    /// is uniformly distributed. It is the one-way map of RFC 9496.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-5.3.4
+    pub fn from_uniform_bytes(&self, bytes: &[u8; 112]) -> Element {
+        let p1 = self.map(&self.decode_field(&bytes[..56]));
+        let p2 = self.map(&self.decode_field(&bytes[56..]));
+        self.element(p1 + p2)
+    }
+    /// Hashes a message into an element using expand_message_xof with
+    /// SHAKE256 and the domain separation tag `dst`, followed by the one-way
+    /// map. This is the hash_to_decaf448 function used by RFC 9497.
+    pub fn hash_to_group(&self, msg: &[u8], dst: &[u8]) -> Element {
+        let bytes = expand_message_xof::<Shake256>(msg, dst, 112, 224).unwrap();
+        self.from_uniform_bytes(bytes.as_slice().try_into().unwrap())
+    }
+    pub(super) fn element(&self, p: Point) -> Element {
+        let g = self.clone();
+        Element { g, p }
+    }
+    /// Encodes the point representing an element.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-5.3.2
+    pub(super) fn encode_point(&self, p: &Point) -> Vec<u8> {
+        let (x0, z0, t0) = (&p.c.x, &p.c.z, &p.c.t);
+        let u1 = (x0 + t0) * (x0 - t0);
+        let v = &u1 * &self.0.one_minus_d * (x0 ^ 2u32);
+        let (_, invsqrt) = self.sqrt_ratio_m1(&self.get_field().one(), &v);
+        let ratio = abs(&(&invsqrt * &u1 * &self.0.sqrt_minus_d));
+        let u2 = &self.0.invsqrt_minus_d * &ratio * z0 - t0;
+        let s = abs(&(&self.0.one_minus_d * &invsqrt * x0 * u2));
+        s.to_bytes_le()
+    }
+    /// Returns whether u/v is a square, and the non-negative square root of
+    /// u/v if so.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-5.2
+    fn sqrt_ratio_m1(&self, u: &FpElt, v: &FpElt) -> (bool, FpElt) {
+        let r = u * &(&(u * v) ^ &self.0.exp);
+        let check = v * &(&r ^ 2u32);
+        (check == *u, abs(&r))
+    }
+    /// Decodes a field element, reducing it modulo p.
+    fn decode_field(&self, buf: &[u8]) -> FpElt {
+        self.get_field().elt(BigInt::from_bytes_le(Sign::Plus, buf))
+    }
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-5.3.4
+    fn map(&self, t: &FpElt) -> Point {
+        let one = self.get_field().one();
+        let r = -(t ^ 2u32);
+        let u0 = &self.0.d * &(&r - &one);
+        let u1 = (&u0 + &one) * (&u0 - &r);
+        let (was_square, v) = self.sqrt_ratio_m1(&self.0.one_minus_two_d, &((&r + &one) * u1));
+        let v_prime = FpElt::cmov(&(t * &v), &v, was_square);
+        let sgn = FpElt::cmov(&-&one, &one, was_square);
+        let s = &v_prime * &(&r + &one);
+        let w0 = abs(&s) + &abs(&s);
+        let ss = &s ^ 2u32;
+        let w1 = &ss + &one;
+        let w2 = &ss - &one;
+        let w3 = v_prime * &s * (r - &one) * &self.0.one_minus_two_d + &sgn;
+        self.0.ec.new_proy_point(ProyCoordinates {
+            x: &w0 * &w3,
+            y: &w2 * &w1,
+            t: w0 * w2,
+            z: w1 * w3,
+        })
+    }
+    fn build(ec: Curve) -> Group {
+        let f = ec.get_field();
+        let d = ec.coefficients().1.clone();
+        let one = f.one();
+        let sqrt_minus_d: FpElt = f.from(SQRT_MINUS_D);
+        Group(Arc::new(Inner {
+            exp: (f.get_modulus() - 3u32) >> 2usize,
+            invsqrt_minus_d: 1u32 / &sqrt_minus_d,
+            sqrt_minus_d,
+            one_minus_d: &one - &d,
+            one_minus_two_d: &one - &(&d + &d),
+            d,
+            ec,
+        }))
+    }
+}
+
+impl_quotient_group!(
+    name: "decaf448",
+    curve: "edwards448",
+    order: 2,
+    /// Returns the generator of RFC 9496, which is represented by [2]B,
+    /// where B is the generator of edwards448.
+    generator: |ec| {
+        let b = ec.get_generator();
+        &b + &b
+    },
+);
+
+impl Decode for Group {
+    type Deser = Element;
+    /// Decodes an element following RFC 9496. Every element has a unique
+    /// encoding, and decoded elements are always in the prime-order group,
+    /// so the policy has no effect.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-5.3.1
+    fn decode_with(&self, buf: &[u8], _: &DecodePolicy) -> Result<Self::Deser, DecodeError> {
+        let f = self.get_field();
+        let s = f.from_bytes_le(buf)?;
+        if is_negative(&s) {
+            return Err(DecodeError::NonCanonical);
+        }
+        let one = f.one();
+        let ss = &s ^ 2u32;
+        let u1 = &one + &ss;
+        let u1_sqr = &u1 ^ 2u32;
+        let four_d: FpElt = &self.0.d * &f.from(4);
+        let u2 = &u1_sqr - &(four_d * &ss);
+        let (was_square, invsqrt) = self.sqrt_ratio_m1(&one, &(&u2 * &u1_sqr));
+        if !was_square {
+            return Err(DecodeError::NotOnCurve);
+        }
+        let u3 = abs(&((&s + &s) * &invsqrt * &u1 * &self.0.sqrt_minus_d));
+        let x = u3 * &invsqrt * &u2 * &self.0.invsqrt_minus_d;
+        let y = (one - &ss) * &invsqrt * &u1;
+        let t = &x * &y;
+        let p = self.0.ec.new_proy_point(ProyCoordinates {
+            x,
+            y,
+            t,
+            z: f.one(),
+        });
+        Ok(self.element(p))
+    }
+}
+
+const SQRT_MINUS_D: &str = "98944233647732219769177004876929019128417576295529901074099889598043702116001257856802131563896515373927712232092845883226922417596214";
+
+#[cfg(test)]
+mod tests {
+    use crate::decaf448::Group;
+    use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode};
+    use crate::expander::expand_message_xof;
+    use crate::field::Field;
+    use crate::ops::{FromFactory, Serialize};
+    use crate::primefield::FpElt;
+    use crate::DecodeError;
+    use num_bigint::BigInt;
+    use sha3::Shake256;
+
+    // Encodings of the multiples [i]B of the generator, from RFC 9496
+    // Appendix B.1.
+    const MULTIPLES: [&str; 16] = [
+        "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        "6666666666666666666666666666666666666666666666666666666633333333333333333333333333333333333333333333333333333333",
+        "c898eb4f87f97c564c6fd61fc7e49689314a1f818ec85eeb3bd5514ac816d38778f69ef347a89fca817e66defdedce178c7cc709b2116e75",
+        "a0c09bf2ba7208fda0f4bfe3d0f5b29a543012306d43831b5adc6fe7f8596fa308763db15468323b11cf6e4aeb8c18fe44678f44545a69bc",
+        "b46f1836aa287c0a5a5653f0ec5ef9e903f436e21c1570c29ad9e5f596da97eeaf17150ae30bcb3174d04bc2d712c8c7789d7cb4fda138f4",
+        "1c5bbecf4741dfaae79db72dface00eaaac502c2060934b6eaaeca6a20bd3da9e0be8777f7d02033d1b15884232281a41fc7f80eed04af5e",
+        "86ff0182d40f7f9edb7862515821bd67bfd6165a3c44de95d7df79b8779ccf6460e3c68b70c16aaa280f2d7b3f22d745b97a89906cfc476c",
+        "502bcb6842eb06f0e49032bae87c554c031d6d4d2d7694efbf9c468d48220c50f8ca28843364d70cee92d6fe246e61448f9db9808b3b2408",
+        "0c9810f1e2ebd389caa789374d78007974ef4d17227316f40e578b336827da3f6b482a4794eb6a3975b971b5e1388f52e91ea2f1bcb0f912",
+        "20d41d85a18d5657a29640321563bbd04c2ffbd0a37a7ba43a4f7d263ce26faf4e1f74f9f4b590c69229ae571fe37fa639b5b8eb48bd9a55",
+        "e6b4b8f408c7010d0601e7eda0c309a1a42720d6d06b5759fdc4e1efe22d076d6c44d42f508d67be462914d28b8edce32e7094305164af17",
+        "be88bbb86c59c13d8e9d09ab98105f69c2d1dd134dbcd3b0863658f53159db64c0e139d180f3c89b8296d0ae324419c06fa87fc7daaf34c1",
+        "a456f9369769e8f08902124a0314c7a06537a06e32411f4f93415950a17badfa7442b6217434a3a05ef45be5f10bd7b2ef8ea00c431edec5",
+        "186e452c4466aa4383b4c00210d52e7922dbf9771e8b47e229a9b7b73c8d10fd7ef0b6e41530f91f24a3ed9ab71fa38b98b2fe4746d51d68",
+        "4ae7fdcae9453f195a8ead5cbe1a7b9699673b52c40ab27927464887be53237f7f3a21b938d40d0ec9e15b1d5130b13ffed81373a53e2b43",
+        "841981c3bfeec3f60cfeca75d9d8dc17f46cf0106f2422b59aec580a58f342272e3a5e575a055ddb051390c54c24c6ecb1e0aceb075f6056",
+    ];
+
+    #[test]
+    fn constants() {
+        let g = Group::new();
+        let f = g.get_field();
+        let d = &g.0.d;
+        assert!(&g.0.sqrt_minus_d ^ 2u32 == -d);
+        assert!(&g.0.sqrt_minus_d * &g.0.invsqrt_minus_d == f.one());
+    }
+
+    #[test]
+    fn rfc9496_vectors() {
+        let g = Group::new();
+        let gen = g.get_generator();
+        let mut p = g.identity();
+        for (i, enc) in MULTIPLES.iter().enumerate() {
+            let enc = hex::decode(enc).unwrap();
+            assert_eq!(p.encode(true), enc, "encoding of [{}]B", i);
+            let q = g.decode(&enc).unwrap();
+            assert!(q == p && g.is_on_curve(&q));
+            assert!(q == gen.clone() * g.new_scalar(BigInt::from(i)));
+            p = p + &gen;
+        }
+    }
+
+    #[test]
+    fn bad_encodings() {
+        let g = Group::new();
+        let f = g.get_field();
+        let size = f.size_bytes();
+        let mut p = f.get_modulus().to_biguint().unwrap().to_bytes_le();
+        p.resize(size, 0);
+        assert_eq!(g.decode(&p).err(), Some(DecodeError::NonCanonical));
+        assert_eq!(
+            g.decode(&vec![0xff; size]).err(),
+            Some(DecodeError::NonCanonical)
+        );
+        assert_eq!(
+            g.decode(&f.one().to_bytes_le()).err(),
+            Some(DecodeError::NonCanonical)
+        );
+        assert_eq!(g.decode(&[0u8; 57]).err(), Some(DecodeError::WrongLength));
+
+        // Every even s either fails to decode or is the encoding of an element.
+        let mut rejected = 0;
+        for s in (0..64u32).map(|i| -> FpElt { f.from(2 * i) }) {
+            let enc = s.to_bytes_le();
+            match g.decode(&enc) {
+                Ok(e) => assert_eq!(e.encode(true), enc),
+                Err(err) => {
+                    assert_eq!(err, DecodeError::NotOnCurve);
+                    rejected += 1;
+                }
+            }
+        }
+        assert!(rejected > 0);
+    }
+
+    #[test]
+    fn one_way_map() {
+        let g = Group::new();
+        // Test vector from RFC 9496 Appendix B.3.
+        let input = "cbb8c991fd2f0b7e1913462d6463e4fd2ce4ccdd28274dc2ca1f4165d5ee6cdc\
+                     cea57be3416e166fd06718a31af45a2f8e987e301be59ae6673e963001dbbda8\
+                     0df47014a21a26d6c7eb4ebe0312aa6fffb8d1b26bc62ca40ed51f8057a635a0\
+                     2c2b8c83f48fa6a2d70f58a1185902c0";
+        let output = "0c709c9607dbb01c94513358745b7c23953d03b33e39c7234e268d1d6e24f340\
+                      14ccbc2216b965dd231d5327e591dc3c0e8844ccfd568848";
+        let input: [u8; 112] = hex::decode(input).unwrap().try_into().unwrap();
+        let p = g.from_uniform_bytes(&input);
+        assert!(g.is_on_curve(&p));
+        assert_eq!(hex::encode(p.encode(true)), output);
+
+        // the halves of the input are reduced modulo p.
+        let f = g.get_field();
+        let mut modulus = f.get_modulus().to_biguint().unwrap().to_bytes_le();
+        modulus.resize(56, 0);
+        let mut input = [0u8; 112];
+        input[56..].copy_from_slice(&modulus);
+        let q = g.from_uniform_bytes(&input);
+        assert!(q == g.from_uniform_bytes(&[0u8; 112]));
+
+        let (msg, dst) = (b"abc", b"HashToGroup-OPRFV1-\x00-decaf448-SHAKE256");
+        let bytes = expand_message_xof::<Shake256>(msg, dst, 112, 224).unwrap();
+        let p = g.from_uniform_bytes(&bytes.try_into().unwrap());
+        assert!(g.hash_to_group(msg, dst) == p);
+    }
+
+    #[test]
+    fn prime_order_group() {
+        let g = Group::new();
+        let ec = &g.0.ec;
+        let gen = g.get_generator();
+        let r = g.get_scalar_field().get_modulus();
+        assert!((gen.clone() * g.new_scalar(&r - 1u32) + &gen).is_zero());
+        assert!(gen.is_torsion_free() && !gen.is_small_order());
+        assert!(gen.torsion_component() == g.identity());
+
+        // Points that differ by the point of order 2 represent the same element.
+        let f = g.get_field();
+        let t2 = ec.new_point(f.zero(), -f.one());
+        let p = gen.clone() * g.new_scalar(BigInt::from(3));
+        let q = g.element(&p.p + &t2);
+        assert!(p == q && p.encode(true) == q.encode(true));
+        assert!(g.element(t2).is_zero());
+
+        // The points of order 4 do not represent any element.
+        assert!(g.try_new_point(f.one(), f.zero()).is_err());
+        let (x, y) = (ec.get_generator().c.x, ec.get_generator().c.y);
+        assert!(g.try_new_point(x, y).unwrap() == gen.clone() * g.new_scalar(r / 2u32 + 1u32));
+    }
+}
@@ -0,0 +1,23 @@
+//! This is documentation for the `decaf448` module.
+//!
+//! The decaf448 module implements the prime-order group decaf448 specified
+//! in RFC 9496. Its elements are represented by points of edwards448, but
+//! two points that differ by a point of order 2 represent the same element,
+//! so that there is no cofactor to take care of.
+//!
+//! ```
+//!  use redox_ecc::decaf448::Group;
+//!  use redox_ecc::ellipticcurve::{Decode, EllipticCurve, Encode};
+//!
+//!  let g = Group::new();
+//!  let p = g.get_generator() * g.new_scalar(2.into());
+//!  let bytes = p.encode(true);
+//!  assert!(g.decode(&bytes).unwrap() == p);
+//! ```
+
+mod element;
+mod group;
+
+pub use crate::decaf448::element::Element;
+pub use crate::decaf448::group::Group;
+pub use crate::scalarfield::Scalar;
@@ -0,0 +1,354 @@
+//! This is documentation for the `ecdh` module.
+//!
+//! The ecdh module implements the secret value derivation primitives of SEC1
+//! (Section 3.3): the elliptic curve Diffie-Hellman primitive (ECSVDP-DH),
+//! and the cofactor Diffie-Hellman primitive (ECSVDP-DHC), which tolerates
+//! public keys outside the prime-order subgroup.
+//!
+//! ```
+//!  use redox_ecc::ecdh::{ecsvdp_dh, ecsvdp_dhc};
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::ops::FromFactory;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!
+//!  let ec = P256.get();
+//!  let fq = ec.get_scalar_field();
+//!  let (a, b) = (fq.from(5), fq.from(7));
+//!  let (pa, pb) = (ec.get_generator() * &a, ec.get_generator() * &b);
+//!  let z = ecsvdp_dh(&ec, &a, &pb).unwrap();
+//!  assert_eq!(z, ecsvdp_dh(&ec, &b, &pa).unwrap());
+//!  assert_eq!(z, ecsvdp_dhc(&ec, &a, &pb, true).unwrap());
+//! ```
+
+use crate::ellipticcurve::{EcPoint, EcScalar, EllipticCurve};
+use crate::field::Field;
+use crate::ops::Serialize;
+use crate::{edwards, montgomery, weierstrass, Error};
+
+/// XCoordinate extracts the shared secret value from a point, i.e., its
+/// affine x-coordinate encoded with the byte length of the field.
+pub trait XCoordinate {
+    fn x_coordinate(&self) -> Vec<u8>;
+}
+
+impl XCoordinate for weierstrass::Point {
+    /// Encodes the x-coordinate in big-endian order as in SEC1.
+    fn x_coordinate(&self) -> Vec<u8> {
+        let mut p = self.clone();
+        p.normalize();
+        p.c.x.to_bytes_be()
+    }
+}
+
+impl XCoordinate for montgomery::Point {
+    /// Encodes the u-coordinate in little-endian order as in RFC 7748.
+    fn x_coordinate(&self) -> Vec<u8> {
+        let mut p = self.clone();
+        p.normalize();
+        p.c.x.to_bytes_le()
+    }
+}
+
+impl XCoordinate for edwards::Point {
+    /// Encodes the x-coordinate in little-endian order as in RFC 8032.
+    fn x_coordinate(&self) -> Vec<u8> {
+        let mut p = self.clone();
+        p.normalize();
+        p.c.x.to_bytes_le()
+    }
+}
+
+/// Checks that the point is a point on the curve other than the identity.
+fn check_point<E: EllipticCurve>(ec: &E, q: &E::Point) -> Result<(), Error> {
+    ec.identity().try_add(q)?;
+    if !ec.is_on_curve(q) {
+        return Err(Error::NotOnCurve);
+    }
+    if q.is_zero() {
+        return Err(Error::InvalidPoint);
+    }
+    Ok(())
+}
+
+/// Validates a public key as in SEC1 (Section 3.2.2.1), i.e., the point is
+/// on the curve, it is not the identity, and its order is the prime r.
+pub fn validate_public_key<E: EllipticCurve>(ec: &E, q: &E::Point) -> Result<(), Error> {
+    check_point(ec, q)?;
+    if !q.is_torsion_free() {
+        return Err(Error::InvalidPoint);
+    }
+    Ok(())
+}
+
+/// Returns the x-coordinate of the shared point, unless it is the identity.
+fn shared_secret<P: XCoordinate + EcPoint<S>, S: EcScalar>(p: P) -> Result<Vec<u8>, Error> {
+    if p.is_zero() {
+        return Err(Error::InvalidPoint);
+    }
+    Ok(p.x_coordinate())
+}
+
+/// Derives the secret value shared by the secret key `d` and the public key
+/// `q` as in ECSVDP-DH of SEC1 (Section 3.3.1). The public key is validated
+/// first, so it must lie in the subgroup of prime order.
+pub fn ecsvdp_dh<E>(ec: &E, d: &E::Scalar, q: &E::Point) -> Result<Vec<u8>, Error>
+where
+    E: EllipticCurve,
+    E::Point: XCoordinate,
+{
+    validate_public_key(ec, q)?;
+    shared_secret(q.try_mul(d)?)
+}
+
+/// Derives the secret value shared by the secret key `d` and the public key
+/// `q` as in ECSVDP-DHC of SEC1 (Section 3.3.2), which multiplies by the
+/// cofactor h to clear any component of small order of the public key. In
+/// compatibility mode, the secret key is multiplied by 1/h mod r, so that
+/// the result equals the one of ECSVDP-DH for valid public keys.
+pub fn ecsvdp_dhc<E>(
+    ec: &E,
+    d: &E::Scalar,
+    q: &E::Point,
+    compatible: bool,
+) -> Result<Vec<u8>, Error>
+where
+    E: EllipticCurve,
+    E::Point: XCoordinate,
+{
+    check_point(ec, q)?;
+    let h = ec.get_cofactor();
+    let p = if compatible {
+        let fq = ec.get_scalar_field();
+        let h_inv = fq.one() / &fq.elt(h.clone());
+        q.try_mul(&(h_inv * d))?
+    } else {
+        q.try_mul(d)?
+    };
+    shared_secret(p.mul_int(&h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ecsvdp_dh, ecsvdp_dhc, validate_public_key};
+    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
+    use crate::field::Field;
+    use crate::instances::{GetCurve, WeCurveID, CURVE25519, P256, P384, P521};
+    use crate::keys::SecretKey;
+    use crate::ops::FromFactory;
+    use crate::{DecodeError, Error};
+
+    struct Vector {
+        qcavs_x: &'static str,
+        qcavs_y: &'static str,
+        d_iut: &'static str,
+        qiut_x: &'static str,
+        qiut_y: &'static str,
+        z_iut: &'static str,
+    }
+
+    // the first vectors of each curve (COUNT = 0 to 3 for P-256, and COUNT = 0
+    // and 1 for P-384 and P-521) from the ECC CDH primitive test vectors of
+    // NIST CAVP (ecccdhtestvectors.zip).
+    fn vectors() -> Vec<(WeCurveID, Vector)> {
+        vec![
+            (
+                P256,
+                Vector {
+                    qcavs_x: "700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287",
+                    qcavs_y: "db71e509e3fd9b060ddb20ba5c51dcc5948d46fbf640dfe0441782cab85fa4ac",
+                    d_iut: "7d7dc5f71eb29ddaf80d6214632eeae03d9058af1fb6d22ed80badb62bc1a534",
+                    qiut_x: "ead218590119e8876b29146ff89ca61770c4edbbf97d38ce385ed281d8a6b230",
+                    qiut_y: "28af61281fd35e2fa7002523acc85a429cb06ee6648325389f59edfce1405141",
+                    z_iut: "46fc62106420ff012e54a434fbdd2d25ccc5852060561e68040dd7778997bd7b",
+                },
+            ),
+            (
+                P256,
+                Vector {
+                    qcavs_x: "809f04289c64348c01515eb03d5ce7ac1a8cb9498f5caa50197e58d43a86a7ae",
+                    qcavs_y: "b29d84e811197f25eba8f5194092cb6ff440e26d4421011372461f579271cda3",
+                    d_iut: "38f65d6dce47676044d58ce5139582d568f64bb16098d179dbab07741dd5caf5",
+                    qiut_x: "119f2f047902782ab0c9e27a54aff5eb9b964829ca99c06b02ddba95b0a3f6d0",
+                    qiut_y: "8f52b726664cac366fc98ac7a012b2682cbd962e5acb544671d41b9445704d1d",
+                    z_iut: "057d636096cb80b67a8c038c890e887d1adfa4195e9b3ce241c8a778c59cda67",
+                },
+            ),
+            (
+                P256,
+                Vector {
+                    qcavs_x: "a2339c12d4a03c33546de533268b4ad667debf458b464d77443636440ee7fec3",
+                    qcavs_y: "ef48a3ab26e20220bcda2c1851076839dae88eae962869a497bf73cb66faf536",
+                    d_iut: "1accfaf1b97712b85a6f54b148985a1bdc4c9bec0bd258cad4b3d603f49f32c8",
+                    qiut_x: "d9f2b79c172845bfdb560bbb01447ca5ecc0470a09513b6126902c6b4f8d1051",
+                    qiut_y: "f815ef5ec32128d3487834764678702e64e164ff7315185e23aff5facd96d7bc",
+                    z_iut: "2d457b78b4614132477618a5b077965ec90730a8c81a1c75d6d4ec68005d67ec",
+                },
+            ),
+            (
+                P256,
+                Vector {
+                    qcavs_x: "df3989b9fa55495719b3cf46dccd28b5153f7808191dd518eff0c3cff2b705ed",
+                    qcavs_y: "422294ff46003429d739a33206c8752552c8ba54a270defc06e221e0feaf6ac4",
+                    d_iut: "207c43a79bfee03db6f4b944f53d2fb76cc49ef1c9c4d34d51b6c65c4db6932d",
+                    qiut_x: "24277c33f450462dcb3d4801d57b9ced05188f16c28eda873258048cd1607e0d",
+                    qiut_y: "c4789753e2b1f63b32ff014ec42cd6a69fac81dfe6d0d6fd4af372ae27c46f88",
+                    z_iut: "96441259534b80f6aee3d287a6bb17b5094dd4277d9e294f8fe73e48bf2a0024",
+                },
+            ),
+            (
+                P384,
+                Vector {
+                    qcavs_x: "a7c76b970c3b5fe8b05d2838ae04ab47697b9eaf52e764592efda27fe7513272734466b400091adbf2d68c58e0c50066",
+                    qcavs_y: "ac68f19f2e1cb879aed43a9969b91a0839c4c38a49749b661efedf243451915ed0905a32b060992b468c64766fc8437a",
+                    d_iut: "3cc3122a68f0d95027ad38c067916ba0eb8c38894d22e1b15618b6818a661774ad463b205da88cf699ab4d43c9cf98a1",
+                    qiut_x: "9803807f2f6d2fd966cdd0290bd410c0190352fbec7ff6247de1302df86f25d34fe4a97bef60cff548355c015dbb3e5f",
+                    qiut_y: "ba26ca69ec2f5b5d9dad20cc9da711383a9dbe34ea3fa5a2af75b46502629ad54dd8b7d73a8abb06a3a3be47d650cc99",
+                    z_iut: "5f9d29dc5e31a163060356213669c8ce132e22f57c9a04f40ba7fcead493b457e5621e766c40a2e3d4d6a04b25e533f1",
+                },
+            ),
+            (
+                P384,
+                Vector {
+                    qcavs_x: "30f43fcf2b6b00de53f624f1543090681839717d53c7c955d1d69efaf0349b7363acb447240101cbb3af6641ce4b88e0",
+                    qcavs_y: "25e46c0c54f0162a77efcc27b6ea792002ae2ba82714299c860857a68153ab62e525ec0530d81b5aa15897981e858757",
+                    d_iut: "92860c21bde06165f8e900c687f8ef0a05d14f290b3f07d8b3a8cc6404366e5d5119cd6d03fb12dc58e89f13df9cd783",
+                    qiut_x: "ea4018f5a307c379180bf6a62fd2ceceebeeb7d4df063a66fb838aa35243419791f7e2c9d4803c9319aa0eb03c416b66",
+                    qiut_y: "68835a91484f05ef028284df6436fb88ffebabcdd69ab0133e6735a1bcfb37203d10d340a8328a7b68770ca75878a1a6",
+                    z_iut: "a23742a2c267d7425fda94b93f93bbcc24791ac51cd8fd501a238d40812f4cbfc59aac9520d758cf789c76300c69d2ff",
+                },
+            ),
+            (
+                P521,
+                Vector {
+                    qcavs_x: "000000685a48e86c79f0f0875f7bc18d25eb5fc8c0b07e5da4f4370f3a9490340854334b1e1b87fa395464c60626124a4e70d0f785601d37c09870ebf176666877a2046d",
+                    qcavs_y: "000001ba52c56fc8776d9e8f5db4f0cc27636d0b741bbe05400697942e80b739884a83bde99e0f6716939e632bc8986fa18dccd443a348b6c3e522497955a4f3c302f676",
+                    d_iut: "0000017eecc07ab4b329068fba65e56a1f8890aa935e57134ae0ffcce802735151f4eac6564f6ee9974c5e6887a1fefee5743ae2241bfeb95d5ce31ddcb6f9edb4d6fc47",
+                    qiut_x: "000000602f9d0cf9e526b29e22381c203c48a886c2b0673033366314f1ffbcba240ba42f4ef38a76174635f91e6b4ed34275eb01c8467d05ca80315bf1a7bbd945f550a5",
+                    qiut_y: "000001b7c85f26f5d4b2d7355cf6b02117659943762b6d1db5ab4f1dbc44ce7b2946eb6c7de342962893fd387d1b73d7a8672d1f236961170b7eb3579953ee5cdc88cd2d",
+                    z_iut: "005fc70477c3e63bc3954bd0df3ea0d1f41ee21746ed95fc5e1fdf90930d5e136672d72cc770742d1711c3c3a4c334a0ad9759436a4d3c5bf6e74b9578fac148c831",
+                },
+            ),
+            (
+                P521,
+                Vector {
+                    qcavs_x: "000001df277c152108349bc34d539ee0cf06b24f5d3500677b4445453ccc21409453aafb8a72a0be9ebe54d12270aa51b3ab7f316aa5e74a951c5e53f74cd95fc29aee7a",
+                    qcavs_y: "0000013d52f33a9f3c14384d1587fa8abe7aed74bc33749ad9c570b471776422c7d4505d9b0a96b3bfac041e4c6a6990ae7f700e5b4a6640229112deafa0cd8bb0d089b0",
+                    d_iut: "000000816f19c1fb10ef94d4a1d81c156ec3d1de08b66761f03f06ee4bb9dcebbbfe1eaa1ed49a6a990838d8ed318c14d74cc872f95d05d07ad50f621ceb620cd905cfb8",
+                    qiut_x: "000000d45615ed5d37fde699610a62cd43ba76bedd8f85ed31005fe00d6450fbbd101291abd96d4945a8b57bc73b3fe9f4671105309ec9b6879d0551d930dac8ba45d255",
+                    qiut_y: "000001425332844e592b440c0027972ad1526431c06732df19cd46a242172d4dd67c2c8c99dfc22e49949a56cf90c6473635ce82f25b33682fb19bc33bd910ed8ce3a7fa",
+                    z_iut: "000b3920ac830ade812c8f96805da2236e002acbbf13596a9ab254d44d0e91b6255ebf1229f366fb5a05c5884ef46032c26d42189273ca4efa4c3db6bd12a6853759",
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn cavp_vectors() {
+        for (id, v) in vectors() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let fq = ec.get_scalar_field();
+            let hex = |s: &str| format!("0x{}", s);
+            let qcavs = ec.new_point(
+                f.from(hex(v.qcavs_x).as_str()),
+                f.from(hex(v.qcavs_y).as_str()),
+            );
+            let qiut = ec.new_point(
+                f.from(hex(v.qiut_x).as_str()),
+                f.from(hex(v.qiut_y).as_str()),
+            );
+            let d = fq.from(hex(v.d_iut).as_str());
+            assert!(ec.get_generator() * &d == qiut);
+            let z = hex::decode(v.z_iut).unwrap();
+            assert_eq!(ecsvdp_dh(&ec, &d, &qcavs).unwrap(), z, "{}", id);
+            assert_eq!(ecsvdp_dhc(&ec, &d, &qcavs, false).unwrap(), z, "{}", id);
+            assert_eq!(ecsvdp_dhc(&ec, &d, &qcavs, true).unwrap(), z, "{}", id);
+        }
+    }
+
+    #[test]
+    fn invalid_peers() {
+        let hex = |s: &str| format!("0x{}", s);
+        for (id, v) in vectors() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let d = ec.get_scalar_field().from(hex(v.d_iut).as_str());
+            let (x, y) = (
+                f.from(hex(v.qcavs_x).as_str()),
+                f.from(hex(v.qcavs_y).as_str()),
+            );
+            // the peer key is not on the curve, so it is never a point.
+            let got = ec.try_new_point(x.clone(), &y + &f.one());
+            assert_eq!(got.err(), Some(Error::NotOnCurve));
+            let mut buf = ec.new_point(x, y).encode(false);
+            *buf.last_mut().unwrap() ^= 1;
+            assert_eq!(ec.decode(&buf).err(), Some(DecodeError::NotOnCurve));
+            for (other, w) in vectors().into_iter().filter(|(other, _)| *other != id) {
+                // the peer key belongs to another curve.
+                let ex = other.get();
+                let fx = ex.get_field();
+                let (x, y) = (hex(w.qcavs_x), hex(w.qcavs_y));
+                let q = ex.new_point(fx.from(x.as_str()), fx.from(y.as_str()));
+                assert_eq!(ecsvdp_dh(&ec, &d, &q).err(), Some(Error::CurveMismatch));
+                assert_eq!(
+                    ecsvdp_dhc(&ec, &d, &q, true).err(),
+                    Some(Error::CurveMismatch)
+                );
+                // its coordinates are not a point of this curve.
+                let got = ec.try_new_point(f.from(x.as_str()), f.from(y.as_str()));
+                assert_eq!(got.err(), Some(Error::NotOnCurve));
+            }
+        }
+    }
+
+    #[test]
+    fn x25519() {
+        // RFC 7748 (Section 6.1).
+        let ec = CURVE25519.get();
+        let a = hex::decode("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")
+            .unwrap();
+        let b = hex::decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+            .unwrap();
+        let z = "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742";
+        let pb = ec.decode(&b).unwrap();
+        let a = SecretKey::Montgomery(CURVE25519, a).scalar();
+        assert_eq!(hex::encode(ecsvdp_dh(&ec, &a, &pb).unwrap()), z);
+        assert_eq!(hex::encode(ecsvdp_dhc(&ec, &a, &pb, true).unwrap()), z);
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let ec = P256.get();
+        let fq = ec.get_scalar_field();
+        let d = fq.from(7);
+        assert_eq!(
+            ecsvdp_dh(&ec, &d, &ec.identity()).err(),
+            Some(Error::InvalidPoint)
+        );
+        let q = P384.get().get_generator();
+        assert_eq!(ecsvdp_dh(&ec, &d, &q).err(), Some(Error::CurveMismatch));
+        let d = P384.get().get_scalar_field().from(7);
+        let q = ec.get_generator();
+        assert_eq!(ecsvdp_dh(&ec, &d, &q).err(), Some(Error::GroupMismatch));
+
+        // a point of order two is not a valid public key, and the cofactor
+        // clears it.
+        let ec = CURVE25519.get();
+        let f = ec.get_field();
+        let t = ec.new_point(f.zero(), f.zero());
+        assert_eq!(
+            validate_public_key(&ec, &t).err(),
+            Some(Error::InvalidPoint)
+        );
+        let d = ec.get_scalar_field().from(7);
+        assert_eq!(ecsvdp_dh(&ec, &d, &t).err(), Some(Error::InvalidPoint));
+        assert_eq!(
+            ecsvdp_dhc(&ec, &d, &t, false).err(),
+            Some(Error::InvalidPoint)
+        );
+        let q = ec.get_generator() + &t;
+        assert_eq!(ecsvdp_dh(&ec, &d, &q).err(), Some(Error::InvalidPoint));
+        let z = ecsvdp_dhc(&ec, &d, &ec.get_generator(), false).unwrap();
+        assert_eq!(ecsvdp_dhc(&ec, &d, &q, false).unwrap(), z);
+    }
+}
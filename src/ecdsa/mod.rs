@@ -0,0 +1,644 @@
+//! This is documentation for the `ecdsa` module.
+//!
+//! The ecdsa module implements the Elliptic Curve Digital Signature Algorithm
+//! (FIPS 186-5 and SEC1) over Weierstrass curves. Nonces are derived
+//! deterministically from the secret key and the message as in RFC 6979, and
+//! the hash function is a type parameter.
+//!
+//! ```
+//!  use redox_ecc::ecdsa::{sign, verify};
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::ops::FromFactory;
+//!  use sha2::Sha256;
+//!
+//!  let ec = P256.get();
+//!  let d = ec.get_scalar_field().from(0x1234);
+//!  let q = ec.get_generator() * &d;
+//!  let sig = sign::<Sha256>(&ec, &d, b"message").unwrap();
+//!  assert!(verify::<Sha256>(&ec, &q, b"message", &sig).is_ok());
+//!  assert!(verify::<Sha256>(&ec, &q, b"other message", &sig).is_err());
+//! ```
+
+mod recovery;
+pub(crate) mod rfc6979;
+mod signature;
+
+pub use crate::ecdsa::recovery::{
+    recover, recover_candidates, recover_candidates_prehash, recover_prehash, RecoveryId,
+};
+pub use crate::ecdsa::signature::Signature;
+
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::identities::Zero;
+
+use crate::ecdh::{validate_public_key, XCoordinate};
+use crate::ecdsa::rfc6979::{bits2int, Nonces};
+use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
+use crate::field::Field;
+use crate::scalarfield::{Scalar, Zq};
+use crate::weierstrass::{Curve, Point};
+use crate::Error;
+
+/// Reduces an integer modulo q, e.g., the x-coordinate of a point.
+fn reduce(fq: &Zq, n: &BigInt) -> Scalar {
+    fq.elt(n.mod_floor(&fq.get_modulus()))
+}
+
+/// Converts the hash of a message into a scalar, keeping as many leftmost
+/// bits as the order of the group has.
+fn hash_to_scalar(fq: &Zq, hash: &[u8]) -> Scalar {
+    reduce(fq, &bits2int(hash, fq.get_modulus().bits()))
+}
+
+/// Returns the x-coordinate of a point reduced modulo the order q.
+fn x_mod_q(fq: &Zq, p: &Point) -> Scalar {
+    reduce(fq, &BigInt::from_bytes_be(Sign::Plus, &p.x_coordinate()))
+}
+
+/// Signs a message with the secret key `d`, which is hashed with D.
+pub fn sign<D>(ec: &Curve, d: &Scalar, msg: &[u8]) -> Result<Signature, Error>
+where
+    D: Digest + BlockSizeUser,
+{
+    sign_prehash::<D>(ec, d, &D::digest(msg))
+}
+
+/// Signs the hash of a message with the secret key `d`. The nonce is derived
+/// as in RFC 6979 using HMAC with D, which is usually the hash function that
+/// produced `hash`.
+pub fn sign_prehash<D>(ec: &Curve, d: &Scalar, hash: &[u8]) -> Result<Signature, Error>
+where
+    D: Digest + BlockSizeUser,
+{
+    sign_prehash_recoverable::<D>(ec, d, hash).map(|(sig, _)| sig)
+}
+
+/// Signs a message with the secret key `d`, which is hashed with D, and
+/// returns the identifier that recovers the public key from the signature.
+pub fn sign_recoverable<D>(
+    ec: &Curve,
+    d: &Scalar,
+    msg: &[u8],
+) -> Result<(Signature, RecoveryId), Error>
+where
+    D: Digest + BlockSizeUser,
+{
+    sign_prehash_recoverable::<D>(ec, d, &D::digest(msg))
+}
+
+/// Signs the hash of a message with the secret key `d` as in
+/// [`sign_prehash`], and returns the identifier that recovers the public key
+/// from the signature. If the signature is normalized to low s, the
+/// identifier must be negated with [`RecoveryId::negate`].
+pub fn sign_prehash_recoverable<D>(
+    ec: &Curve,
+    d: &Scalar,
+    hash: &[u8],
+) -> Result<(Signature, RecoveryId), Error>
+where
+    D: Digest + BlockSizeUser,
+{
+    let fq = ec.get_scalar_field();
+    if d.get_field() != fq {
+        return Err(Error::GroupMismatch);
+    }
+    if d.is_zero() {
+        return Err(Error::InvalidParameters);
+    }
+    let e = hash_to_scalar(&fq, hash);
+    let mut nonces = Nonces::<D>::new(&fq.get_modulus(), d.value(), hash);
+    loop {
+        let k = fq.elt(nonces.generate());
+        let p = (ec.get_generator() * &k).encode(true);
+        let x = BigInt::from_bytes_be(Sign::Plus, &p[1..]);
+        let id = RecoveryId::new(p[0] == 0x03, x >= fq.get_modulus());
+        let r = reduce(&fq, &x);
+        if r.is_zero() {
+            continue;
+        }
+        let s = (&e + &r * d) / &k;
+        if s.is_zero() {
+            continue;
+        }
+        return Ok((Signature { r, s }, id));
+    }
+}
+
+/// Verifies the signature of a message under the public key `q`. The
+/// message is hashed with D.
+pub fn verify<D: Digest>(ec: &Curve, q: &Point, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+    verify_prehash(ec, q, &D::digest(msg), sig)
+}
+
+/// Verifies the signature of the hash of a message under the public key
+/// `q`, which is validated first. Signatures with high s are accepted; see
+/// [`Signature::is_low_s`].
+pub fn verify_prehash(ec: &Curve, q: &Point, hash: &[u8], sig: &Signature) -> Result<(), Error> {
+    validate_public_key(ec, q)?;
+    let fq = ec.get_scalar_field();
+    if sig.r.get_field() != fq || sig.s.get_field() != fq {
+        return Err(Error::GroupMismatch);
+    }
+    if sig.r.is_zero() || sig.s.is_zero() {
+        return Err(Error::InvalidSignature);
+    }
+    let e = hash_to_scalar(&fq, hash);
+    let w = 1u32 / &sig.s;
+    let p = ec.get_generator() * &(e * &w) + q * &(&sig.r * &w);
+    if p.is_zero() || x_mod_q(&fq, &p) != sig.r {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sign, sign_prehash, verify, verify_prehash, Signature};
+    use crate::ellipticcurve::{Decode, EllipticCurve};
+    use crate::instances::{GetCurve, WeCurveID, P256, P384, P521, SECP256K1};
+    use crate::ops::FromFactory;
+    use crate::Error;
+    use sha2::{Digest, Sha256, Sha384, Sha512};
+
+    // RFC 6979 (Appendix A.2.5 to A.2.7).
+    const P256_KEY: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+    const P384_KEY: &str = "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5";
+    const P521_KEY: &str = "0fad06daa62ba3b25d2fb40133da757205de67f5bb0018fee8c86e1b68c7e75caa896eb32f1f47c70855836a6d16fcc1466f6d8fbec67db89ec0c08b0e996b83538";
+
+    // a sample of the ECDSA test vectors of Wycheproof with SHA-256, as
+    // distributed with the p256 and k256 crates: (valid, x, y, msg, sig).
+    const P256_WYCHEPROOF: [(bool, &str, &str, &str, &str); 24] = [
+        (
+            true,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "304402202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e1802204cd60b855d442f5b3c7b11eb6c4e0ae7525fe710fab9aa7c77a67f79e6fadd76",
+        ),
+        (
+            true,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "304502202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "30814502202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "3082004502202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "304602202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "304402202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "3085010000004502202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "308901000000000000004502202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "30847fffffff02202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "304baa02aabb304502202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "308002202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db05000000",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "30490284ffffffff2ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "304503202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18022100b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "302502202ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18020100",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "3006020101020100",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "3046022100ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551022100ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632550",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "3028022100ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632552090380fe01",
+        ),
+        (
+            false,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "313233343030",
+            "30090c0225730c03732573",
+        ),
+        (
+            true,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "33313336303436313839",
+            "30460221009505e407657d6e8bc93db5da7aa6f5081f61980c1949f56b0f2f507da5782a7a022100c60d31904e3669738ffbeccab6c3656c08e0ed5cb92b3cfa5e7f71784f9c5021",
+        ),
+        (
+            true,
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838",
+            "00c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e",
+            "3632313535323436",
+            "3046022100ad019f74c6941d20efda70b46c53db166503a0e393e932f688227688ba6a576202210093320eb7ca0710255346bdbb3102cdcf7964ef2e0988e712bc05efe16c199345",
+        ),
+        (
+            true,
+            "7393983ca30a520bbc4783dc9960746aab444ef520c0a8e771119aa4e74b0f64",
+            "00e9d7be1ab01a0bf626e709863e6a486dbaf32793afccf774e2c6cd27b1857526",
+            "313233343030",
+            "3032020d1033e67e37b32b445580bf4eff0221008b748b74000000008b748b748b748b7466e769ad4a16d3dcd87129b8e91d1b4d",
+        ),
+        (
+            true,
+            "00f19b78928720d5bee8e670fb90010fb15c37bf91b58a5157c3f3c059b2655e88",
+            "00cf701ec962fb4a11dcf273f5dc357e58468560c7cfeb942d074abd4329260509",
+            "313233343030",
+            "304402207ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffd022016e1e458f021248a5b9434ae23f474b43ee55ba37ea585fef95c90416600f1ba",
+        ),
+        (
+            true,
+            "00851c2bbad08e54ec7a9af99f49f03644d6ec6d59b207fec98de85a7d15b956ef",
+            "00cee9960283045075684b410be8d0f7494b91aa2379f60727319f10ddeb0fe9d6",
+            "313233343030",
+            "304502207cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978022100cccccccc00000000cccccccccccccccc971f2ef152794b9d8fc7d568c9e8eaa7",
+        ),
+        (
+            true,
+            "3cf03d614d8939cfd499a07873fac281618f06b8ff87e8015c3f497265004935",
+            "7b05e8b186e38d41d31c77f5769f22d58385ecc857d07a561a6324217fffffff",
+            "4d657373616765",
+            "304402201158a08d291500b4cabed3346d891eee57c176356a2624fb011f8fbbf34668300220228a8c486a736006e082325b85290c5bc91f378b75d487dda46798c18f285519",
+        ),
+    ];
+    const SECP256K1_WYCHEPROOF: [(bool, &str, &str, &str, &str); 24] = [
+        (
+            true,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3046022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc9832365022100900e75ad233fcc908509dbff5922647db37c21f4afd3203ae8dc4ae7794b0f87",
+        ),
+        (
+            true,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "308145022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "30820045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3046022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3044022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "30850100000045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3089010000000000000045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "30847fffffff022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "304baa02aabb3045022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3080022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba05000000",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "30490284ffffffff00813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3045032100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc983236502206ff18a52dcc0336f7af62400a6dd9b810732baf1ff758000d6f613a556eb31ba",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3026022100813ef79ccefa9a56f7ba805f0e478584fe5f0dd5f567bc09b5123ccbc9832365020100",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3006020101020100",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3046022100fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141022100fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "3028022100fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364142090380fe01",
+        ),
+        (
+            false,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "313233343030",
+            "30090c0225730c03732573",
+        ),
+        (
+            true,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "33313336303436313839",
+            "3045022100a40034177f36091c2b653684a0e3eb5d4bff18e4d09f664c2800e7cafda1daf802203a3ec29853704e52031c58927a800a968353adc3d973beba9172cbbeab4dd149",
+        ),
+        (
+            true,
+            "00b838ff44e5bc177bf21189d0766082fc9d843226887fc9760371100b7ee20a6f",
+            "00f0c9d75bfba7b31a6bca1974496eeb56de357071955d83c4b1badaa0b21832e9",
+            "3632313535323436",
+            "3045022100ae459640d5d1179be47a47fa538e16d94ddea5585e7a244804a51742c686443a02206c8e30e530a634fae80b3ceb062978b39edbe19777e0a24553b68886181fd897",
+        ),
+        (
+            true,
+            "0934a537466c07430e2c48feb990bb19fb78cecc9cee424ea4d130291aa237f0",
+            "00d4f92d23b462804b5b68c52558c01c9996dbf727fccabbeedb9621a400535afa",
+            "313233343030",
+            "3026020201010220783266e90f43dafe5cd9b3b0be86de22f9de83677d0f50713a468ec72fcf5d57",
+        ),
+        (
+            true,
+            "1f7f85caf2d7550e7af9b65023ebb4dce3450311692309db269969b834b611c7",
+            "0827f45b78020ecbbaf484fdd5bfaae6870f1184c21581baf6ef82bd7b530f93",
+            "313233343030",
+            "304402207ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffc022016e1e459457679df5b9434ae23f474b3e8d2a70bd6b5dbe692ba16da01f1fb0a",
+        ),
+        (
+            true,
+            "008ad445db62816260e4e687fd1884e48b9fc0636d031547d63315e792e19bfaee",
+            "1de64f99d5f1cd8b6ec9cb0f787a654ae86993ba3db1008ef43cff0684cb22bd",
+            "313233343030",
+            "3045022100c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5022066666666666666666666666666666665e445f1f5dfb6a67e4cba8c385348e6e7",
+        ),
+        (
+            true,
+            "013fd22248d64d95f73c29b48ab48631850be503fd00f8468b5f0f70e0",
+            "00f6ee7aa43bc2c6fd25b1d8269241cbdd9dbb0dac96dc96231f430705f838717d",
+            "4d657373616765",
+            "3046022100b615698c358b35920dd883eca625a6c5f7563970cdfc378f8fe0cee17092144c022100da0b84cd94a41e049ef477aeac157b2a9bfa6b7ac8de06ed3858c5eede6ddd6d",
+        ),
+    ];
+
+    fn check<D: Digest + digest::core_api::BlockSizeUser>(
+        id: WeCurveID,
+        key: &str,
+        msg: &str,
+        r: &str,
+        s: &str,
+    ) {
+        let ec = id.get();
+        let d = ec.get_scalar_field().from(format!("0x{}", key).as_str());
+        let q = ec.get_generator() * &d;
+        let sig = sign::<D>(&ec, &d, msg.as_bytes()).unwrap();
+        assert_eq!(hex::encode(sig.to_bytes()), [r, s].concat(), "{}", id);
+        assert!(verify::<D>(&ec, &q, msg.as_bytes(), &sig).is_ok());
+        assert_eq!(
+            verify::<D>(&ec, &q, b"other", &sig).err(),
+            Some(Error::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn rfc6979_vectors() {
+        check::<Sha256>(
+            P256,
+            P256_KEY,
+            "sample",
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+            "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+        );
+        check::<Sha256>(
+            P256,
+            P256_KEY,
+            "test",
+            "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+            "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+        );
+        // the hash is longer than the order, so it is truncated.
+        check::<Sha512>(
+            P256,
+            P256_KEY,
+            "sample",
+            "8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f00",
+            "2362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe",
+        );
+        check::<Sha384>(
+            P384,
+            P384_KEY,
+            "sample",
+            "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c81a648152e44acf96e36dd1e80fabe46",
+            "99ef4aeb15f178cea1fe40db2603138f130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8",
+        );
+        check::<Sha512>(
+            P521,
+            P521_KEY,
+            "sample",
+            "00c328fafcbd79dd77850370c46325d987cb525569fb63c5d3bc53950e6d4c5f174e25a1ee9017b5d450606add152b534931d7d4e8455cc91f9b15bf05ec36e377fa",
+            "00617cce7cf5064806c467f678d3b4080d6f1cc50af26ca209417308281b68af282623eaa63e5b5c0723d8b8c37ff0777b1a20f8ccb1dccc43997f1ee0e44da4a67a",
+        );
+    }
+
+    #[test]
+    fn wycheproof_vectors() {
+        for (id, vectors) in [(P256, P256_WYCHEPROOF), (SECP256K1, SECP256K1_WYCHEPROOF)] {
+            let ec = id.get();
+            for (valid, x, y, msg, sig) in vectors {
+                // coordinates may have a leading zero byte.
+                let x = x.trim_start_matches("00");
+                let y = y.trim_start_matches("00");
+                let point = hex::decode(format!("04{:0>64}{:0>64}", x, y)).unwrap();
+                let q = ec.decode(&point).unwrap();
+                let msg = hex::decode(msg).unwrap();
+                let result = Signature::from_der(&ec, &hex::decode(sig).unwrap())
+                    .and_then(|sig| verify::<Sha256>(&ec, &q, &msg, &sig));
+                assert_eq!(result.is_ok(), valid, "{} {}", id, sig);
+            }
+        }
+    }
+
+    #[test]
+    fn low_s() {
+        let ec = SECP256K1.get();
+        let fq = ec.get_scalar_field();
+        let d = fq.from(0x1234);
+        let q = ec.get_generator() * &d;
+        let hash = Sha256::digest(b"message");
+        let sig = sign_prehash::<Sha256>(&ec, &d, &hash).unwrap();
+        let (low, high) = if sig.is_low_s() {
+            (sig.clone(), Signature { s: -&sig.s, ..sig })
+        } else {
+            (sig.normalize_s(), sig)
+        };
+        assert!(!high.is_low_s() && high.normalize_s() == low);
+        assert!(verify_prehash(&ec, &q, &hash, &low).is_ok());
+        assert!(verify_prehash(&ec, &q, &hash, &high).is_ok());
+    }
+
+    #[test]
+    fn bad_inputs() {
+        let ec = P256.get();
+        let fq = ec.get_scalar_field();
+        let d = fq.from(7);
+        let q = ec.get_generator() * &d;
+        let sig = sign::<Sha256>(&ec, &d, b"message").unwrap();
+        assert_eq!(
+            verify::<Sha256>(&ec, &ec.identity(), b"message", &sig).err(),
+            Some(Error::InvalidPoint)
+        );
+        let p384 = P384.get();
+        assert_eq!(
+            verify::<Sha256>(&p384, &q, b"message", &sig).err(),
+            Some(Error::CurveMismatch)
+        );
+        let d384 = p384.get_scalar_field().from(7);
+        assert_eq!(
+            sign::<Sha256>(&ec, &d384, b"message").err(),
+            Some(Error::GroupMismatch)
+        );
+        assert_eq!(
+            sign::<Sha256>(&ec, &fq.from(0), b"message").err(),
+            Some(Error::InvalidParameters)
+        );
+        let zero = Signature {
+            r: fq.from(0),
+            s: sig.s.clone(),
+        };
+        assert_eq!(
+            verify::<Sha256>(&ec, &q, b"message", &zero).err(),
+            Some(Error::InvalidSignature)
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! This is documentation for the `recovery` module.
+//!
+//! The recovery module recovers the public key of ECDSA from a signature and
+//! the hash of the signed message (SEC1, Section 4.1.6), as done by Bitcoin
+//! and Ethereum with secp256k1.
+//!
+//! The point R = kG of a signature is lifted from r, which is the
+//! x-coordinate of R reduced modulo q. Since p and q are close, at most two
+//! x-coordinates (r and r + q) and two y-coordinates are possible, so a
+//! recovery identifier of two bits selects the correct key.
+
+use digest::Digest;
+use num_traits::identities::Zero;
+
+use crate::ecdsa::{hash_to_scalar, Signature};
+use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve};
+use crate::field::Field;
+use crate::ops::Serialize;
+use crate::weierstrass::{Curve, Point};
+use crate::Error;
+
+/// RecoveryId identifies the point R of a signature: bit 0 is the parity of
+/// its y-coordinate, and bit 1 is set when its x-coordinate is r + q instead
+/// of r.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecoveryId(u8);
+
+impl RecoveryId {
+    pub fn new(is_y_odd: bool, is_x_reduced: bool) -> Self {
+        RecoveryId(is_y_odd as u8 | (is_x_reduced as u8) << 1)
+    }
+    /// Returns the identifier encoded in a byte, which must be in the
+    /// range [0, 3]. Ethereum adds 27 to this value.
+    pub fn from_byte(b: u8) -> Result<Self, Error> {
+        if b > 3 {
+            return Err(Error::InvalidParameters);
+        }
+        Ok(RecoveryId(b))
+    }
+    pub fn to_byte(self) -> u8 {
+        self.0
+    }
+    /// Determines whether the y-coordinate of R is odd.
+    pub fn is_y_odd(self) -> bool {
+        self.0 & 1 == 1
+    }
+    /// Determines whether the x-coordinate of R was reduced modulo q.
+    pub fn is_x_reduced(self) -> bool {
+        self.0 & 2 == 2
+    }
+    /// Returns the identifier for the signature with s negated, e.g., after
+    /// [`Signature::normalize_s`], which corresponds to -R.
+    pub fn negate(self) -> Self {
+        RecoveryId(self.0 ^ 1)
+    }
+}
+
+/// Recovers the public key that verifies the signature of a message, which
+/// is hashed with D.
+pub fn recover<D: Digest>(
+    ec: &Curve,
+    msg: &[u8],
+    sig: &Signature,
+    id: RecoveryId,
+) -> Result<Point, Error> {
+    recover_prehash(ec, &D::digest(msg), sig, id)
+}
+
+/// Recovers the public key that verifies the signature of the hash of a
+/// message. Returns an error if R has no point for the identifier, which
+/// happens when r + q is not less than p.
+pub fn recover_prehash(
+    ec: &Curve,
+    hash: &[u8],
+    sig: &Signature,
+    id: RecoveryId,
+) -> Result<Point, Error> {
+    let fq = ec.get_scalar_field();
+    if sig.r.get_field() != fq || sig.s.get_field() != fq {
+        return Err(Error::GroupMismatch);
+    }
+    if sig.r.is_zero() || sig.s.is_zero() {
+        return Err(Error::InvalidSignature);
+    }
+    let f = ec.get_field();
+    let mut x = sig.r.value().clone();
+    if id.is_x_reduced() {
+        x += fq.get_modulus();
+    }
+    if x >= f.get_modulus() {
+        return Err(Error::InvalidSignature);
+    }
+    let tag = 0x02 | id.is_y_odd() as u8;
+    let buf = [vec![tag], f.elt(x).to_bytes_be()].concat();
+    let policy = DecodePolicy {
+        subgroup_check: true,
+        ..DecodePolicy::default()
+    };
+    let r = ec
+        .decode_with(&buf, &policy)
+        .map_err(|_| Error::InvalidSignature)?;
+    // Q = r⁻¹(sR - eG)
+    let e = hash_to_scalar(&fq, hash);
+    let q = (r * &sig.s - ec.get_generator() * &e) * &(1u32 / &sig.r);
+    if q.is_zero() {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(q)
+}
+
+/// Recovers every public key that verifies the signature of a message, when
+/// the recovery identifier is unknown. The message is hashed with D.
+pub fn recover_candidates<D: Digest>(
+    ec: &Curve,
+    msg: &[u8],
+    sig: &Signature,
+) -> Vec<(RecoveryId, Point)> {
+    recover_candidates_prehash(ec, &D::digest(msg), sig)
+}
+
+/// Recovers every public key that verifies the signature of the hash of a
+/// message, together with its recovery identifier. Usually, there are two
+/// candidates, and rarely up to four.
+pub fn recover_candidates_prehash(
+    ec: &Curve,
+    hash: &[u8],
+    sig: &Signature,
+) -> Vec<(RecoveryId, Point)> {
+    (0..4)
+        .map(RecoveryId)
+        .filter_map(|id| Some((id, recover_prehash(ec, hash, sig, id).ok()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        recover, recover_candidates, recover_candidates_prehash, recover_prehash, RecoveryId,
+    };
+    use crate::ecdsa::{sign_recoverable, verify, verify_prehash, Signature};
+    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
+    use crate::field::Field;
+    use crate::instances::{GetCurve, P256, SECP256K1};
+    use crate::ops::{FromFactory, Serialize};
+    use crate::Error;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn secp256k1_vectors() {
+        // taken from the tests of the k256 crate.
+        let vectors = [
+            (
+                "021a7a569e91dbf60581509c7fc946d1003b60c7dee85299538db6353538d59574",
+                "ce53abb3721bafc561408ce8ff99c909f7f0b18a2f788649d6470162ab1aa0323971edc523a6d6453f3fb6128d318d9db1a5ff3386feb1047d9816e780039d52",
+                0,
+            ),
+            (
+                "036d6caac248af96f6afa7f904f550253a0f3ef3f5aa2fe6838a95b216691468e2",
+                "46c05b6368a44b8810d79859441d819b8e7cdc8bfd371e35c53196f4bcacdb5135c7facce2a97b95eacba8a586d87b7958aaf8368ab29cee481f76e871dbd9cb",
+                1,
+            ),
+        ];
+        let ec = SECP256K1.get();
+        let msg = b"example message";
+        for (pk, sig, id) in vectors {
+            let sig = Signature::from_bytes(&ec, &hex::decode(sig).unwrap()).unwrap();
+            let id = RecoveryId::from_byte(id).unwrap();
+            let q = recover::<Sha256>(&ec, msg, &sig, id).unwrap();
+            assert_eq!(hex::encode(q.encode(true)), pk);
+            let candidates = recover_candidates::<Sha256>(&ec, msg, &sig);
+            assert!(candidates.iter().any(|(i, p)| *i == id && *p == q));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for ec in [P256.get(), SECP256K1.get()] {
+            let d = ec.get_scalar_field().from(0x1234);
+            let q = ec.get_generator() * &d;
+            for msg in [&b"sample"[..], b"test", b"message"] {
+                let (sig, id) = sign_recoverable::<Sha256>(&ec, &d, msg).unwrap();
+                assert!(recover::<Sha256>(&ec, msg, &sig, id).unwrap() == q);
+                let other = recover::<Sha256>(&ec, msg, &sig, id.negate()).unwrap();
+                assert!(other != q);
+                let candidates = recover_candidates::<Sha256>(&ec, msg, &sig);
+                assert_eq!(candidates.len(), 2);
+                for (_, p) in candidates {
+                    assert!(verify::<Sha256>(&ec, &p, msg, &sig).is_ok());
+                }
+                // negating s corresponds to negating R.
+                let neg = Signature {
+                    s: -&sig.s,
+                    ..sig.clone()
+                };
+                assert!(recover::<Sha256>(&ec, msg, &neg, id.negate()).unwrap() == q);
+            }
+        }
+    }
+
+    #[test]
+    fn reduced_x() {
+        // Finding a nonce k such that x(kG) ≥ q is infeasible, so the point
+        // R is chosen first, and the public key follows from the signature.
+        let ec = SECP256K1.get();
+        let f = ec.get_field();
+        let fq = ec.get_scalar_field();
+        let n = fq.get_modulus();
+        let r = (1u32..)
+            .find_map(|t| {
+                let x = f.elt(&n + t).to_bytes_be();
+                ec.decode(&[vec![0x02], x].concat()).ok()
+            })
+            .unwrap();
+        let x = num_bigint::BigInt::from_bytes_be(num_bigint::Sign::Plus, &r.encode(true)[1..]);
+        let sig = Signature {
+            r: fq.elt(x - &n),
+            s: fq.from(0x5678),
+        };
+        let hash = Sha256::digest(b"message");
+        let e = super::hash_to_scalar(&fq, &hash);
+        let q = (&r * &sig.s - ec.get_generator() * &e) * &(1u32 / &sig.r);
+        assert!(verify_prehash(&ec, &q, &hash, &sig).is_ok());
+
+        let id = RecoveryId::new(false, true);
+        assert!(recover_prehash(&ec, &hash, &sig, id).unwrap() == q);
+        let candidates = recover_candidates_prehash(&ec, &hash, &sig);
+        assert!(candidates.iter().any(|(i, p)| *i == id && *p == q));
+
+        // r + q ≥ p, so there is no such point.
+        let sig = Signature {
+            r: fq.elt(f.get_modulus() - &n),
+            s: fq.from(1),
+        };
+        assert_eq!(
+            recover_prehash(&ec, &hash, &sig, id).err(),
+            Some(Error::InvalidSignature)
+        );
+        assert_eq!(
+            RecoveryId::from_byte(4).err(),
+            Some(Error::InvalidParameters)
+        );
+    }
+}
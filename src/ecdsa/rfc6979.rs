@@ -0,0 +1,108 @@
+//! This is documentation for the `rfc6979` module.
+//!
+//! The rfc6979 module derives the nonces of ECDSA deterministically from the
+//! secret key and the message using HMAC-DRBG, as in RFC 6979.
+
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use hmac::{Mac, SimpleHmac};
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::identities::Zero;
+
+/// Converts an octet-string into an integer keeping its leftmost `qlen`
+/// bits, i.e., the bits2int function of RFC 6979 (Section 2.3.2).
+pub(super) fn bits2int(buf: &[u8], qlen: u64) -> BigInt {
+    let n = BigInt::from_bytes_be(Sign::Plus, buf);
+    let blen = 8 * buf.len() as u64;
+    if blen > qlen {
+        n >> (blen - qlen)
+    } else {
+        n
+    }
+}
+
+/// Encodes an integer in big-endian order using the byte length of q.
+fn int2octets(n: &BigInt, rlen: usize) -> Vec<u8> {
+    let (_, bytes) = n.to_bytes_be();
+    [vec![0; rlen.saturating_sub(bytes.len())], bytes].concat()
+}
+
+/// Nonces generates the candidate nonces of RFC 6979 (Section 3.2) for a
+/// secret key and the hash of a message. The next candidate is used when a
+/// nonce produces an invalid signature.
+pub(crate) struct Nonces<D: Digest + BlockSizeUser> {
+    k: Vec<u8>,
+    v: Vec<u8>,
+    q: BigInt,
+    _hash: std::marker::PhantomData<D>,
+}
+
+impl<D: Digest + BlockSizeUser> Nonces<D> {
+    pub(crate) fn new(q: &BigInt, x: &BigInt, h1: &[u8]) -> Self {
+        let qlen = q.bits();
+        let rlen = qlen.div_ceil(8) as usize;
+        let hlen = <D as Digest>::output_size();
+        let x = int2octets(x, rlen);
+        let h1 = int2octets(&bits2int(h1, qlen).mod_floor(q), rlen);
+        let mut nonces = Nonces {
+            k: vec![0x00; hlen],
+            v: vec![0x01; hlen],
+            q: q.clone(),
+            _hash: std::marker::PhantomData,
+        };
+        for i in 0..=1 {
+            nonces.k = nonces.hmac(&[&nonces.v, &[i][..], &x, &h1]);
+            nonces.v = nonces.hmac(&[&nonces.v]);
+        }
+        nonces
+    }
+    fn hmac(&self, data: &[&[u8]]) -> Vec<u8> {
+        let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(&self.k).unwrap();
+        data.iter().for_each(|d| mac.update(d));
+        mac.finalize().into_bytes().to_vec()
+    }
+    /// Returns the next candidate nonce in the range [1, q-1].
+    pub(crate) fn generate(&mut self) -> BigInt {
+        let qlen = self.q.bits();
+        loop {
+            let mut t = Vec::new();
+            while (8 * t.len() as u64) < qlen {
+                self.v = self.hmac(&[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+            let k = bits2int(&t, qlen);
+            // prepares the next candidate.
+            self.k = self.hmac(&[&self.v, &[0x00]]);
+            self.v = self.hmac(&[&self.v]);
+            if !k.is_zero() && k < self.q {
+                return k;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Nonces;
+    use num_bigint::{BigInt, Sign};
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn rfc6979_nonces() {
+        // RFC 6979 (Appendix A.1.2), the curve is not needed.
+        let q = BigInt::parse_bytes(b"4000000000000000000020108a2e0cc0d99f8a5ef", 16).unwrap();
+        let x = BigInt::parse_bytes(b"09a4d6792295a7f730fc3f2b49cbc0f62e862272f", 16).unwrap();
+        let h1 = Sha256::digest(b"sample");
+        let k = BigInt::parse_bytes(b"23af4074c90a02b3fe61d286d5c87f425e6bdd81b", 16).unwrap();
+        assert_eq!(Nonces::<Sha256>::new(&q, &x, &h1).generate(), k);
+
+        // RFC 6979 (Appendix A.2.5), P-256 with SHA-256.
+        let hex = |s: &str| BigInt::from_bytes_be(Sign::Plus, &hex::decode(s).unwrap());
+        let q = hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551");
+        let x = hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let k = hex("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60");
+        let h1 = Sha256::digest(b"sample");
+        assert_eq!(Nonces::<Sha256>::new(&q, &x, &h1).generate(), k);
+    }
+}
@@ -0,0 +1,161 @@
+//! This is documentation for the `signature` module.
+//!
+//! The signature module defines the signatures of ECDSA and their encodings:
+//! the concatenation r‖s of fixed-length integers, and the ASN.1 structure
+//! Ecdsa-Sig-Value in DER (RFC 3279).
+
+use num_traits::identities::Zero;
+
+use crate::ellipticcurve::EllipticCurve;
+use crate::field::Field;
+use crate::keys::der::{self, Reader, SEQUENCE};
+use crate::ops::{Deserialize, Serialize};
+use crate::scalarfield::{Scalar, Zq};
+use crate::weierstrass::Curve;
+use crate::{DecodeError, Error};
+
+/// Signature is a signature of ECDSA, i.e., a pair of scalars (r, s).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub r: Scalar,
+    pub s: Scalar,
+}
+
+/// Decodes a big-endian scalar in the range [1, q-1], whose leading zeros
+/// can be omitted.
+fn decode_scalar(fq: &Zq, buf: &[u8]) -> Result<Scalar, DecodeError> {
+    let size = fq.size_bytes();
+    if buf.len() > size {
+        return Err(DecodeError::WrongLength);
+    }
+    let padded = [vec![0; size - buf.len()], buf.to_vec()].concat();
+    let k = fq.from_bytes_be(&padded)?;
+    if k.is_zero() {
+        return Err(DecodeError::Malformed);
+    }
+    Ok(k)
+}
+
+impl Signature {
+    /// Encodes the signature as r‖s, where each scalar has the byte length
+    /// of the order of the group.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.r.to_bytes_be(), self.s.to_bytes_be()].concat()
+    }
+    /// Decodes a signature encoded as r‖s. Both scalars must be in the range
+    /// [1, q-1].
+    pub fn from_bytes(ec: &Curve, buf: &[u8]) -> Result<Self, Error> {
+        let fq = ec.get_scalar_field();
+        if buf.len() != 2 * fq.size_bytes() {
+            return Err(DecodeError::WrongLength.into());
+        }
+        let (r, s) = buf.split_at(fq.size_bytes());
+        Ok(Signature {
+            r: decode_scalar(&fq, r)?,
+            s: decode_scalar(&fq, s)?,
+        })
+    }
+    /// Encodes the signature as a DER Ecdsa-Sig-Value.
+    pub fn to_der(&self) -> Vec<u8> {
+        der::sequence(&[
+            der::unsigned_integer(&self.r.to_bytes_be()),
+            der::unsigned_integer(&self.s.to_bytes_be()),
+        ])
+    }
+    /// Decodes a DER Ecdsa-Sig-Value. Parsing is strict: any encoding other
+    /// than DER, trailing data, and integers out of the range [1, q-1] are
+    /// rejected.
+    pub fn from_der(ec: &Curve, buf: &[u8]) -> Result<Self, Error> {
+        let fq = ec.get_scalar_field();
+        let mut r = Reader::new(buf);
+        let mut seq = Reader::new(r.read(SEQUENCE)?);
+        r.finish()?;
+        let sig = Signature {
+            r: decode_scalar(&fq, seq.read_unsigned_integer()?)?,
+            s: decode_scalar(&fq, seq.read_unsigned_integer()?)?,
+        };
+        seq.finish()?;
+        Ok(sig)
+    }
+    /// Determines whether s is at most (q-1)/2, as some protocols require
+    /// to prevent malleability.
+    pub fn is_low_s(&self) -> bool {
+        let half = self.s.get_field().get_modulus() >> 1;
+        *self.s.value() <= half
+    }
+    /// Returns the signature with s replaced by q-s if s is not low. Both
+    /// signatures are valid for the same message.
+    pub fn normalize_s(&self) -> Self {
+        let mut sig = self.clone();
+        if !self.is_low_s() {
+            sig.s = -&sig.s;
+        }
+        sig
+    }
+}
+
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "r: {}\ns: {}", self.r, self.s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Signature;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::instances::{GetCurve, P256, P384};
+    use crate::ops::FromFactory;
+    use crate::{DecodeError, Error};
+
+    #[test]
+    fn encodings() {
+        let ec = P256.get();
+        let fq = ec.get_scalar_field();
+        let sig = Signature {
+            r: fq.from(0x7f),
+            s: fq.from(-1),
+        };
+        let der = sig.to_der();
+        assert_eq!(der[..5], [0x30, 0x26, 0x02, 0x01, 0x7f]);
+        assert!(Signature::from_der(&ec, &der).unwrap() == sig);
+        let raw = sig.to_bytes();
+        assert_eq!(raw.len(), 64);
+        assert!(Signature::from_bytes(&ec, &raw).unwrap() == sig);
+        assert!(!sig.is_low_s());
+        assert!(sig.normalize_s().is_low_s());
+        assert!(sig.normalize_s().s == fq.from(1));
+
+        let p384 = P384.get();
+        assert_eq!(
+            Signature::from_bytes(&p384, &raw).err(),
+            Some(Error::Decode(DecodeError::WrongLength))
+        );
+        // s = q is out of range, and r = 0 is not valid.
+        let mut der = der.clone();
+        let last = der.len() - 1;
+        der[last] += 1;
+        assert_eq!(
+            Signature::from_der(&ec, &der).err(),
+            Some(Error::Decode(DecodeError::NonCanonical))
+        );
+        let der = [0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01];
+        assert_eq!(
+            Signature::from_der(&ec, &der).err(),
+            Some(Error::Decode(DecodeError::Malformed))
+        );
+        // BER encodings and trailing data are not valid.
+        let bad: [&[u8]; 4] = [
+            &[0x30, 0x81, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01],
+            &[0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x02, 0x01, 0x01],
+            &[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00],
+            &[0x30, 0x07, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00],
+        ];
+        for der in bad {
+            assert_eq!(
+                Signature::from_der(&ec, der).err(),
+                Some(Error::Decode(DecodeError::Malformed))
+            );
+        }
+    }
+}
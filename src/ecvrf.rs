@@ -0,0 +1,503 @@
+//! This is documentation for the `ecvrf` module.
+//!
+//! The ecvrf module implements the verifiable random functions of RFC 9381
+//! for the ciphersuites ECVRF-P256-SHA256-TAI, ECVRF-P256-SHA256-SSWU, and
+//! ECVRF-EDWARDS25519-SHA512-ELL2. The holder of a secret key computes the
+//! output β of an input α together with a proof π, which lets anyone holding
+//! the public key check that β is the only output for α.
+//!
+//! Public keys are encoded as the suites do, i.e., as compressed SEC1 points
+//! for P-256, and following RFC 8032 for edwards25519.
+//!
+//! ```
+//!  use redox_ecc::ecvrf::{proof_to_hash, prove, verify, Suite};
+//!  use redox_ecc::ellipticcurve::Encode;
+//!  use redox_ecc::instances::EDWARDS25519;
+//!  use redox_ecc::keys::SecretKey;
+//!
+//!  let sk = SecretKey::Edwards(EDWARDS25519, vec![7u8; 32]);
+//!  let pk = sk.public_key().encode(true);
+//!  let suite = Suite::Edwards25519Sha512Ell2;
+//!  let proof = prove(suite, &sk, b"round 1").unwrap();
+//!  let beta = proof_to_hash(suite, &proof).unwrap();
+//!  assert_eq!(verify(suite, &pk, b"round 1", &proof), Ok(beta));
+//!  assert!(verify(suite, &pk, b"round 2", &proof).is_err());
+//! ```
+
+use num_bigint::{BigInt, Sign};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::ecdsa::rfc6979::Nonces;
+use crate::eddsa::expand_seed;
+use crate::edwards::Ell2;
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode};
+use crate::field::Field;
+use crate::hashtocurve::HashToCurve;
+use crate::instances::{edwards25519_to_curve25519, GetCurve, EDWARDS25519, P256};
+use crate::keys::SecretKey;
+use crate::ops::{FromFactory, Serialize};
+use crate::scalarfield::Scalar;
+use crate::weierstrass::SSWU;
+use crate::{edwards, weierstrass, DecodeError, Error};
+
+/// Length in bytes of the challenge c, i.e., cLen.
+const C_LEN: usize = 16;
+
+/// Suite is one of the ciphersuites of ECVRF defined in RFC 9381.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Suite {
+    /// ECVRF-P256-SHA256-TAI, which hashes to the curve by try-and-increment.
+    P256Sha256Tai,
+    /// ECVRF-P256-SHA256-SSWU, which hashes to the curve with the
+    /// P256_XMD:SHA-256_SSWU_NU_ suite of RFC 9380.
+    P256Sha256Sswu,
+    /// ECVRF-EDWARDS25519-SHA512-ELL2, which hashes to the curve with the
+    /// edwards25519_XMD:SHA-512_ELL2_NU_ suite of RFC 9380.
+    Edwards25519Sha512Ell2,
+}
+
+impl Suite {
+    /// Returns the suite_string, which is the first byte of every hash.
+    fn id(self) -> u8 {
+        match self {
+            Suite::P256Sha256Tai => 0x01,
+            Suite::P256Sha256Sswu => 0x02,
+            Suite::Edwards25519Sha512Ell2 => 0x04,
+        }
+    }
+    /// Hashes the concatenation of the data with SHA-256 or SHA-512.
+    fn hash(self, data: &[&[u8]]) -> Vec<u8> {
+        match self {
+            Suite::P256Sha256Tai | Suite::P256Sha256Sswu => {
+                let mut h = Sha256::new();
+                data.iter().for_each(|d| h.update(d));
+                h.finalize().to_vec()
+            }
+            Suite::Edwards25519Sha512Ell2 => {
+                let mut h = Sha512::new();
+                data.iter().for_each(|d| h.update(d));
+                h.finalize().to_vec()
+            }
+        }
+    }
+    /// Returns the domain separation tag of hash-to-curve, i.e.,
+    /// "ECVRF_" ‖ h2c_suite_ID_string ‖ suite_string.
+    fn dst(self, h2c_suite: &str) -> Vec<u8> {
+        [b"ECVRF_", h2c_suite.as_bytes(), &[self.id()]].concat()
+    }
+}
+
+/// VrfCurve lists the operations that differ between the curves of the
+/// suites, so that the protocol is written once.
+trait VrfCurve: EllipticCurve<Scalar = Scalar> {
+    /// Decodes a point, i.e., the string_to_point function of the suite.
+    fn string_to_point(&self, buf: &[u8]) -> Result<Self::Point, DecodeError>;
+    /// Encodes a scalar using qLen bytes, i.e., the int_to_string function.
+    fn int_to_string(&self, k: &Scalar) -> Vec<u8>;
+    /// Decodes an integer, i.e., the string_to_int function.
+    fn string_to_int(&self, buf: &[u8]) -> BigInt;
+    /// Hashes the salt and the input α into a point of the subgroup, i.e.,
+    /// the ECVRF_encode_to_curve function of the suite.
+    fn encode_to_curve(
+        &self,
+        suite: Suite,
+        salt: &[u8],
+        alpha: &[u8],
+    ) -> Result<Self::Point, Error>;
+}
+
+impl VrfCurve for weierstrass::Curve {
+    fn string_to_point(&self, buf: &[u8]) -> Result<Self::Point, DecodeError> {
+        // only compressed points are allowed.
+        if buf.len() != 1 + self.get_field().size_bytes() {
+            return Err(DecodeError::WrongLength);
+        }
+        self.decode(buf)
+    }
+    fn int_to_string(&self, k: &Scalar) -> Vec<u8> {
+        k.to_bytes_be()
+    }
+    fn string_to_int(&self, buf: &[u8]) -> BigInt {
+        BigInt::from_bytes_be(Sign::Plus, buf)
+    }
+    fn encode_to_curve(
+        &self,
+        suite: Suite,
+        salt: &[u8],
+        alpha: &[u8],
+    ) -> Result<Self::Point, Error> {
+        match suite {
+            Suite::P256Sha256Tai => {
+                // P-256 has cofactor 1, so the point is not multiplied.
+                for ctr in 0..=255u8 {
+                    let hash = suite.hash(&[&[suite.id(), 0x01], salt, alpha, &[ctr, 0x00]]);
+                    if let Ok(h) = self.decode(&[&[0x02], &hash[..]].concat()) {
+                        return Ok(h);
+                    }
+                }
+                Err(Error::InvalidPoint)
+            }
+            Suite::P256Sha256Sswu => {
+                let map = SSWU::new(self.clone(), self.get_field().from(-10));
+                let dst = suite.dst("P256_XMD:SHA-256_SSWU_NU_");
+                let h2c = HashToCurve::<Sha256, _>::new(self.clone(), map, &dst, 128);
+                h2c.encode_to_curve(&[salt, alpha].concat())
+            }
+            Suite::Edwards25519Sha512Ell2 => Err(Error::CurveMismatch),
+        }
+    }
+}
+
+impl VrfCurve for edwards::Curve {
+    fn string_to_point(&self, buf: &[u8]) -> Result<Self::Point, DecodeError> {
+        if buf.len() != self.encoding_size() {
+            return Err(DecodeError::WrongLength);
+        }
+        self.decode(buf)
+    }
+    fn int_to_string(&self, k: &Scalar) -> Vec<u8> {
+        k.to_bytes_le()
+    }
+    fn string_to_int(&self, buf: &[u8]) -> BigInt {
+        BigInt::from_bytes_le(Sign::Plus, buf)
+    }
+    fn encode_to_curve(
+        &self,
+        suite: Suite,
+        salt: &[u8],
+        alpha: &[u8],
+    ) -> Result<Self::Point, Error> {
+        if suite != Suite::Edwards25519Sha512Ell2 {
+            return Err(Error::CurveMismatch);
+        }
+        let ratmap = Box::new(edwards25519_to_curve25519());
+        let map = Ell2::new(self.clone(), self.get_field().from(2), Some(ratmap));
+        let dst = suite.dst("edwards25519_XMD:SHA-512_ELL2_NU_");
+        let h2c = HashToCurve::<Sha512, _>::new(self.clone(), map, &dst, 128);
+        h2c.encode_to_curve(&[salt, alpha].concat())
+    }
+}
+
+/// Returns the challenge, i.e., the first cLen bytes of the hash of the
+/// points.
+fn challenge(suite: Suite, points: [&[u8]; 5]) -> Vec<u8> {
+    let prefix = [suite.id(), 0x02];
+    let mut data = vec![&prefix[..]];
+    data.extend(points);
+    data.push(&[0x00]);
+    let mut c = suite.hash(&data);
+    c.truncate(C_LEN);
+    c
+}
+
+/// Computes a proof given the secret scalar and the function that derives
+/// the nonce from the encoding of H.
+fn prove_with<E: VrfCurve>(
+    suite: Suite,
+    ec: &E,
+    x: &Scalar,
+    alpha: &[u8],
+    nonce: impl FnOnce(&[u8]) -> Scalar,
+) -> Result<Vec<u8>, Error> {
+    let g = ec.get_generator();
+    let pk = g.try_mul(x)?.encode(true);
+    let h = ec.encode_to_curve(suite, &pk, alpha)?;
+    let h_string = h.encode(true);
+    let gamma = h.try_mul(x)?.encode(true);
+    let k = nonce(&h_string);
+    let u = g.try_mul(&k)?.encode(true);
+    let v = h.try_mul(&k)?.encode(true);
+    let c_string = challenge(suite, [&pk, &h_string, &gamma, &u, &v]);
+    let c = ec.new_scalar(ec.string_to_int(&c_string));
+    let s = k + c * x;
+    Ok([gamma, c_string, ec.int_to_string(&s)].concat())
+}
+
+/// Computes the proof π of the output of α under the secret key, which must
+/// be a key of P-256 or edwards25519 according to the suite.
+pub fn prove(suite: Suite, sk: &SecretKey, alpha: &[u8]) -> Result<Vec<u8>, Error> {
+    match (suite, sk) {
+        (Suite::P256Sha256Tai | Suite::P256Sha256Sswu, SecretKey::Weierstrass(id, x))
+            if *id == P256 =>
+        {
+            let ec = id.get();
+            let q = ec.get_scalar_field().get_modulus();
+            prove_with(suite, &ec, x, alpha, |h_string| {
+                let h1 = Sha256::digest(h_string);
+                ec.new_scalar(Nonces::<Sha256>::new(&q, x.value(), &h1).generate())
+            })
+        }
+        (Suite::Edwards25519Sha512Ell2, SecretKey::Edwards(id, seed)) if *id == EDWARDS25519 => {
+            let ec = id.get();
+            if seed.len() != ec.encoding_size() {
+                return Err(DecodeError::WrongLength.into());
+            }
+            let (x, prefix) = expand_seed(*id, seed);
+            prove_with(suite, &ec, &x, alpha, |h_string| {
+                let k = suite.hash(&[&prefix, h_string]);
+                ec.get_scalar_field().from_bytes_wide_le(&k)
+            })
+        }
+        _ => Err(Error::CurveMismatch),
+    }
+}
+
+/// Decodes a proof into Γ, the challenge string, and s.
+fn decode_proof<E: VrfCurve>(ec: &E, proof: &[u8]) -> Result<(E::Point, Vec<u8>, Scalar), Error> {
+    let q = ec.get_scalar_field().get_modulus();
+    let q_len = ec.get_scalar_field().size_bytes();
+    let pt_len = proof
+        .len()
+        .checked_sub(C_LEN + q_len)
+        .ok_or(DecodeError::WrongLength)?;
+    let (gamma, rest) = proof.split_at(pt_len);
+    let (c_string, s_string) = rest.split_at(C_LEN);
+    let gamma = ec.string_to_point(gamma)?;
+    let s = ec.string_to_int(s_string);
+    if s >= q {
+        return Err(Error::InvalidProof);
+    }
+    Ok((gamma, c_string.to_vec(), ec.new_scalar(s)))
+}
+
+/// Returns β = Hash(suite_string ‖ 0x03 ‖ point_to_string(h·Γ) ‖ 0x00).
+fn gamma_to_hash<E: VrfCurve>(suite: Suite, ec: &E, gamma: &E::Point) -> Result<Vec<u8>, Error> {
+    let h = ec.new_scalar(ec.get_cofactor());
+    let gamma = gamma.try_mul(&h)?.encode(true);
+    Ok(suite.hash(&[&[suite.id(), 0x03], &gamma, &[0x00]]))
+}
+
+fn verify_with<E: VrfCurve>(
+    suite: Suite,
+    ec: &E,
+    public: &[u8],
+    alpha: &[u8],
+    proof: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let y = ec.string_to_point(public)?;
+    if y.is_small_order() {
+        return Err(Error::InvalidPoint);
+    }
+    let (gamma, c_string, s) = decode_proof(ec, proof)?;
+    let c = ec.new_scalar(ec.string_to_int(&c_string));
+    let h = ec.encode_to_curve(suite, public, alpha)?;
+    let g = ec.get_generator();
+    let u = g.try_mul(&s)?.try_add(&y.try_mul(&-c.clone())?)?;
+    let v = h.try_mul(&s)?.try_add(&gamma.try_mul(&-c)?)?;
+    // the challenge hashes point_to_string(Y), not the input string.
+    let points: [&[u8]; 5] = [
+        &y.encode(true),
+        &h.encode(true),
+        &gamma.encode(true),
+        &u.encode(true),
+        &v.encode(true),
+    ];
+    if challenge(suite, points) != c_string {
+        return Err(Error::InvalidProof);
+    }
+    gamma_to_hash(suite, ec, &gamma)
+}
+
+/// Verifies the proof π of α under the public key, and returns the output β
+/// if it is valid. Public keys of small order are rejected, i.e., the key is
+/// always validated.
+pub fn verify(suite: Suite, public: &[u8], alpha: &[u8], proof: &[u8]) -> Result<Vec<u8>, Error> {
+    match suite {
+        Suite::P256Sha256Tai | Suite::P256Sha256Sswu => {
+            verify_with(suite, &P256.get(), public, alpha, proof)
+        }
+        Suite::Edwards25519Sha512Ell2 => {
+            verify_with(suite, &EDWARDS25519.get(), public, alpha, proof)
+        }
+    }
+}
+
+/// Returns the output β of a proof without verifying it, which must only be
+/// done for proofs known to be valid.
+pub fn proof_to_hash(suite: Suite, proof: &[u8]) -> Result<Vec<u8>, Error> {
+    match suite {
+        Suite::P256Sha256Tai | Suite::P256Sha256Sswu => {
+            let ec = P256.get();
+            gamma_to_hash(suite, &ec, &decode_proof(&ec, proof)?.0)
+        }
+        Suite::Edwards25519Sha512Ell2 => {
+            let ec = EDWARDS25519.get();
+            gamma_to_hash(suite, &ec, &decode_proof(&ec, proof)?.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{proof_to_hash, prove, verify, Suite};
+    use crate::ellipticcurve::{EllipticCurve, Encode};
+    use crate::instances::{GetCurve, EDWARDS25519, P256};
+    use crate::keys::SecretKey;
+    use crate::ops::Deserialize;
+    use crate::{DecodeError, Error};
+
+    // Examples 10 to 18 of RFC 9381 (Appendix B): (suite, SK, alpha, pi, beta).
+    const RFC9381_VECTORS: [(Suite, &str, &str, &str, &str); 9] = [
+        (
+            Suite::P256Sha256Tai,
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            "73616d706c65",
+            "035b5c726e8c0e2c488a107c600578ee75cb702343c153cb1eb8dec77f4b5071b4a53f0a46f018bc2c56e58d383f2305e0975972c26feea0eb122fe7893c15af376b33edf7de17c6ea056d4d82de6bc02f",
+            "a3ad7b0ef73d8fc6655053ea22f9bede8c743f08bbed3d38821f0e16474b505e",
+        ),
+        (
+            Suite::P256Sha256Tai,
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            "74657374",
+            "034dac60aba508ba0c01aa9be80377ebd7562c4a52d74722e0abae7dc3080ddb56c19e067b15a8a8174905b13617804534214f935b94c2287f797e393eb0816969d864f37625b443f30f1a5a33f2b3c854",
+            "a284f94ceec2ff4b3794629da7cbafa49121972671b466cab4ce170aa365f26d",
+        ),
+        (
+            Suite::P256Sha256Tai,
+            "2ca1411a41b17b24cc8c3b089cfd033f1920202a6c0de8abb97df1498d50d2c8",
+            "4578616d706c65207573696e67204543445341206b65792066726f6d20417070656e646978204c2e342e32206f6620414e53492e58392d36322d32303035",
+            "03d03398bf53aa23831d7d1b2937e005fb0062cbefa06796579f2a1fc7e7b8c667d091c00b0f5c3619d10ecea44363b5a599cadc5b2957e223fec62e81f7b4825fc799a771a3d7334b9186bdbee87316b1",
+            "90871e06da5caa39a3c61578ebb844de8635e27ac0b13e829997d0d95dd98c19",
+        ),
+        (
+            Suite::P256Sha256Sswu,
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            "73616d706c65",
+            "0331d984ca8fece9cbb9a144c0d53df3c4c7a33080c1e02ddb1a96a365394c7888782fffde7b842c38c20c08de6ec6c2e7027a97000f2c9fa4425d5c03e639fb48fde58114d755985498d7eb234cf4aed9",
+            "21e66dc9747430f17ed9efeda054cf4a264b097b9e8956a1787526ed00dc664b",
+        ),
+        (
+            Suite::P256Sha256Sswu,
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            "74657374",
+            "03f814c0455d32dbc75ad3aea08c7e2db31748e12802db23640203aebf1fa8db2743aad348a3006dc1caad7da28687320740bf7dd78fe13c298867321ce3b36b79ec3093b7083ac5e4daf3465f9f43c627",
+            "8e7185d2b420e4f4681f44ce313a26d05613323837da09a69f00491a83ad25dd",
+        ),
+        (
+            Suite::P256Sha256Sswu,
+            "2ca1411a41b17b24cc8c3b089cfd033f1920202a6c0de8abb97df1498d50d2c8",
+            "4578616d706c65207573696e67204543445341206b65792066726f6d20417070656e646978204c2e342e32206f6620414e53492e58392d36322d32303035",
+            "039f8d9cdc162c89be2871cbcb1435144739431db7fab437ab7bc4e2651a9e99d5488405a11a6c7fc8defddd9e1573a563b7333aab4effe73ae9803274174c659269fd39b53e133dcd9e0d24f01288de9a",
+            "4fbadf33b42a5f42f23a6f89952d2e634a6e3810f15878b46ef1bb85a04fe95a",
+        ),
+        (
+            Suite::Edwards25519Sha512Ell2,
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "",
+            "7d9c633ffeee27349264cf5c667579fc583b4bda63ab71d001f89c10003ab46f14adf9a3cd8b8412d9038531e865c341cafa73589b023d14311c331a9ad15ff2fb37831e00f0acaa6d73bc9997b06501",
+            "9d574bf9b8302ec0fc1e21c3ec5368269527b87b462ce36dab2d14ccf80c53cccf6758f058c5b1c856b116388152bbe509ee3b9ecfe63d93c3b4346c1fbc6c54",
+        ),
+        (
+            Suite::Edwards25519Sha512Ell2,
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "72",
+            "47b327393ff2dd81336f8a2ef10339112401253b3c714eeda879f12c509072ef055b48372bb82efbdce8e10c8cb9a2f9d60e93908f93df1623ad78a86a028d6bc064dbfc75a6a57379ef855dc6733801",
+            "38561d6b77b71d30eb97a062168ae12b667ce5c28caccdf76bc88e093e4635987cd96814ce55b4689b3dd2947f80e59aac7b7675f8083865b46c89b2ce9cc735",
+        ),
+        (
+            Suite::Edwards25519Sha512Ell2,
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "af82",
+            "926e895d308f5e328e7aa159c06eddbe56d06846abf5d98c2512235eaa57fdce35b46edfc655bc828d44ad09d1150f31374e7ef73027e14760d42e77341fe05467bb286cc2c9d7fde29120a0b2320d04",
+            "121b7f9b9aaaa29099fc04a94ba52784d44eac976dd1a3cca458733be5cd090a7b5fbd148444f17f8daf1fb55cb04b1ae85a626e30a54b4b0f8abf4a43314a58",
+        ),
+    ];
+
+    fn secret_key(suite: Suite, sk: &str) -> SecretKey {
+        let sk = hex::decode(sk).unwrap();
+        if suite == Suite::Edwards25519Sha512Ell2 {
+            return SecretKey::Edwards(EDWARDS25519, sk);
+        }
+        let fq = P256.get().get_scalar_field();
+        SecretKey::Weierstrass(P256, fq.from_bytes_be(&sk).unwrap())
+    }
+
+    #[test]
+    fn rfc9381_vectors() {
+        for (suite, sk, alpha, pi, beta) in RFC9381_VECTORS {
+            let sk = secret_key(suite, sk);
+            let pk = sk.public_key().encode(true);
+            let alpha = hex::decode(alpha).unwrap();
+            let proof = prove(suite, &sk, &alpha).unwrap();
+            assert_eq!(hex::encode(&proof), pi, "{suite:?}");
+            let beta = hex::decode(beta).unwrap();
+            assert_eq!(proof_to_hash(suite, &proof), Ok(beta.clone()));
+            assert_eq!(verify(suite, &pk, &alpha, &proof), Ok(beta));
+        }
+    }
+
+    #[test]
+    fn invalid_proofs() {
+        for (suite, sk, alpha, pi, _) in RFC9381_VECTORS {
+            let pk = secret_key(suite, sk).public_key().encode(true);
+            let alpha = hex::decode(alpha).unwrap();
+            let proof = hex::decode(pi).unwrap();
+            let (pt_len, c_len) = (pk.len(), 16);
+
+            // another input, another suite, and a modified challenge.
+            let other = [&alpha[..], b"x"].concat();
+            assert_eq!(verify(suite, &pk, &other, &proof), Err(Error::InvalidProof));
+            if suite != Suite::Edwards25519Sha512Ell2 {
+                let other = [Suite::P256Sha256Tai, Suite::P256Sha256Sswu];
+                let other = other.into_iter().find(|s| *s != suite).unwrap();
+                assert_eq!(verify(other, &pk, &alpha, &proof), Err(Error::InvalidProof));
+            }
+            let mut bad = proof.clone();
+            bad[pt_len] ^= 1;
+            assert_eq!(verify(suite, &pk, &alpha, &bad), Err(Error::InvalidProof));
+
+            // s is not reduced, and the proof is truncated.
+            let mut bad = proof.clone();
+            bad[pt_len + c_len..].fill(0xff);
+            assert_eq!(verify(suite, &pk, &alpha, &bad), Err(Error::InvalidProof));
+            assert_eq!(proof_to_hash(suite, &bad), Err(Error::InvalidProof));
+            let short = &proof[1..];
+            assert!(matches!(
+                verify(suite, &pk, &alpha, short),
+                Err(Error::Decode(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn keys() {
+        let suite = Suite::Edwards25519Sha512Ell2;
+        let (_, sk, alpha, pi, _) = RFC9381_VECTORS[6];
+        let proof = hex::decode(pi).unwrap();
+        let alpha = hex::decode(alpha).unwrap();
+
+        // the identity, a point of order 2, and a point of order 8.
+        for small in [
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a",
+        ] {
+            let small = hex::decode(small).unwrap();
+            assert_eq!(
+                verify(suite, &small, &alpha, &proof),
+                Err(Error::InvalidPoint)
+            );
+        }
+        assert_eq!(
+            verify(suite, &[0u8; 33], &alpha, &proof),
+            Err(DecodeError::WrongLength.into())
+        );
+
+        // the key must be of the curve of the suite.
+        let sk = secret_key(suite, sk);
+        assert_eq!(
+            prove(Suite::P256Sha256Tai, &sk, b""),
+            Err(Error::CurveMismatch)
+        );
+        let sk = secret_key(Suite::P256Sha256Sswu, RFC9381_VECTORS[0].1);
+        assert_eq!(prove(suite, &sk, b""), Err(Error::CurveMismatch));
+        let uncompressed = sk.public_key().encode(false);
+        let (_, _, alpha, pi, _) = RFC9381_VECTORS[0];
+        let proof = hex::decode(pi).unwrap();
+        let alpha = hex::decode(alpha).unwrap();
+        assert_eq!(
+            verify(Suite::P256Sha256Tai, &uncompressed, &alpha, &proof),
+            Err(DecodeError::WrongLength.into())
+        );
+    }
+}
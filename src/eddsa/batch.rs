@@ -0,0 +1,187 @@
+//! This is documentation for the `batch` module.
+//!
+//! The batch module verifies many signatures of EdDSA at once, which is
+//! faster than verifying them one at a time. If the batch fails, each
+//! signature is verified on its own to find the invalid ones.
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::batch::verify_equations;
+use crate::eddsa::{check, equation, Criteria, Variant};
+use crate::instances::{GetCurve, EDWARDS25519, EDWARDS448};
+use crate::Error;
+
+/// BatchItem is a signature to be verified in a batch, together with the
+/// message, the public key, and the variant and context used to sign.
+#[derive(Clone, Copy)]
+pub struct BatchItem<'a> {
+    pub variant: Variant,
+    pub public: &'a [u8],
+    pub ctx: &'a [u8],
+    pub msg: &'a [u8],
+    pub sig: &'a [u8],
+}
+
+/// Verifies a batch of signatures, which can mix variants and curves. The
+/// cofactored equation is used, so the result agrees with [`super::verify`]
+/// using the same criteria; hence, the criteria must be either
+/// [`Criteria::Cofactored`] or [`Criteria::Zip215`].
+///
+/// If some signatures are not valid, the error lists their indices.
+pub fn verify_batch<R: RngCore + CryptoRng>(
+    items: &[BatchItem],
+    criteria: Criteria,
+    rng: &mut R,
+) -> Result<(), Error> {
+    if criteria == Criteria::Strict {
+        return Err(Error::InvalidParameters);
+    }
+    let mut invalid = Vec::new();
+    for id in [EDWARDS25519, EDWARDS448] {
+        let ec = id.get();
+        let mut indices = Vec::new();
+        let mut eqs = Vec::new();
+        for (i, item) in items.iter().enumerate() {
+            if item.variant.curve() != id {
+                continue;
+            }
+            let eq = equation(
+                item.variant,
+                item.public,
+                item.ctx,
+                item.msg,
+                item.sig,
+                criteria,
+            );
+            match eq {
+                Ok(eq) => {
+                    indices.push(i);
+                    eqs.push(eq);
+                }
+                Err(_) => invalid.push(i),
+            }
+        }
+        if !eqs.is_empty() && !verify_equations(&ec, &eqs, rng)? {
+            let failed = indices.iter().zip(&eqs);
+            invalid.extend(
+                failed
+                    .filter(|(_, eq)| !check(&ec, eq, criteria))
+                    .map(|(i, _)| i),
+            );
+        }
+    }
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    invalid.sort_unstable();
+    Err(Error::InvalidBatch(invalid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_batch, BatchItem};
+    use crate::eddsa::{challenge, encode_scalar, verify, Criteria, SigningKey, Variant};
+    use crate::ellipticcurve::{EllipticCurve, Encode};
+    use crate::field::Field;
+    use crate::instances::{GetCurve, EDWARDS25519, EDWARDS448};
+    use crate::ops::FromFactory;
+    use crate::Error;
+
+    type Signed = (Variant, Vec<u8>, &'static [u8], Vec<u8>, Vec<u8>);
+
+    fn items(signed: &[Signed]) -> Vec<BatchItem<'_>> {
+        let it = signed
+            .iter()
+            .map(|(variant, public, ctx, msg, sig)| BatchItem {
+                variant: *variant,
+                public,
+                ctx,
+                msg,
+                sig,
+            });
+        it.collect()
+    }
+
+    #[test]
+    fn batches() {
+        let mut rng = rand::thread_rng();
+        let variants = [
+            (Variant::Ed25519, &b""[..]),
+            (Variant::Ed25519ctx, b"foo"),
+            (Variant::Ed25519ph, b""),
+            (Variant::Ed448, b"bar"),
+            (Variant::Ed448ph, b""),
+        ];
+        let mut signed = Vec::new();
+        for i in 0..10u8 {
+            let (variant, ctx) = variants[i as usize % variants.len()];
+            let id = variant.curve();
+            let seed = vec![i; id.get().encoding_size()];
+            let key = SigningKey::from_seed(id, &seed).unwrap();
+            let msg = vec![i; i as usize];
+            let sig = key.sign(variant, ctx, &msg).unwrap();
+            signed.push((variant, key.public_key().to_vec(), ctx, msg, sig));
+        }
+        for criteria in [Criteria::Cofactored, Criteria::Zip215] {
+            assert_eq!(verify_batch(&items(&signed), criteria, &mut rng), Ok(()));
+            assert_eq!(verify_batch(&[], criteria, &mut rng), Ok(()));
+        }
+        assert_eq!(
+            verify_batch(&items(&signed), Criteria::Strict, &mut rng),
+            Err(Error::InvalidParameters)
+        );
+
+        // a wrong message, a wrong signature, and a malformed signature.
+        let mut bad = signed.clone();
+        bad[1].3.push(0);
+        bad[3].4[0] ^= 1;
+        bad[8].4.pop();
+        assert_eq!(
+            verify_batch(&items(&bad), Criteria::Cofactored, &mut rng),
+            Err(Error::InvalidBatch(vec![1, 3, 8]))
+        );
+    }
+
+    #[test]
+    fn agrees_with_single_verification() {
+        // the public key has a component of order 2, so the signatures with
+        // odd k are only valid under the cofactored equation.
+        let mut rng = rand::thread_rng();
+        for (id, variant) in [
+            (EDWARDS25519, Variant::Ed25519),
+            (EDWARDS448, Variant::Ed448),
+        ] {
+            let ec = id.get();
+            let fq = ec.get_scalar_field();
+            let f = ec.get_field();
+            let g = ec.get_generator();
+            let s = fq.from(0x1234);
+            let t = ec.new_point(f.zero(), -f.one());
+            let public = (&g * &s + t).encode(true);
+            let mut signed = Vec::new();
+            for i in 0u8..8 {
+                let r = fq.from(i + 1);
+                let big_r = (&g * &r).encode(true);
+                let msg = vec![i];
+                let k = challenge(variant, &variant.dom(b"").unwrap(), &big_r, &public, &msg);
+                let sig = [big_r, encode_scalar(&(r + k * &s), ec.encoding_size())].concat();
+                signed.push((msg, sig));
+            }
+            let strict = |(msg, sig): &(Vec<u8>, Vec<u8>)| {
+                verify(variant, &public, b"", msg, sig, Criteria::Strict).is_ok()
+            };
+            assert!(!signed.iter().all(strict));
+            let items: Vec<_> = signed
+                .iter()
+                .map(|(msg, sig)| BatchItem {
+                    variant,
+                    public: &public,
+                    ctx: b"",
+                    msg,
+                    sig,
+                })
+                .collect();
+            assert_eq!(verify_batch(&items, Criteria::Cofactored, &mut rng), Ok(()));
+        }
+    }
+}
@@ -5,44 +5,69 @@
 use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
 use num_traits::identities::Zero;
 
-use std::io::{Error, ErrorKind};
 use std::str::FromStr;
+use std::sync::Arc;
 
 use crate::do_if_eq;
 use crate::edwards::point::{Point, ProyCoordinates};
-use crate::edwards::scalar::Scalar;
-use crate::ellipticcurve::{Decode, EllipticCurve};
+use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve};
 use crate::field::{Field, Sgn0, Sqrt};
 use crate::ops::FromFactory;
 use crate::primefield::{Fp, FpElt};
+use crate::scalarfield::{Scalar, Zq};
+use crate::{DecodeError, Error};
 
 /// This is an elliptic curve defined in the twisted Edwards model and defined by the equation:
 /// ax^2+y^2=1+dx^2y^2.
 ///
-#[derive(Clone, PartialEq)]
-pub struct Curve {
+/// Cloning a curve is cheap, since clones share the same curve parameters.
+#[derive(Clone)]
+pub struct Curve(pub(super) Arc<Inner>);
+
+#[derive(PartialEq)]
+pub(super) struct Inner {
     pub(super) f: Fp,
     pub(super) a: FpElt,
     pub(super) d: FpElt,
-    pub(super) r: BigUint,
+    pub(super) q: Zq,
     pub(super) gx: FpElt,
     pub(super) gy: FpElt,
     pub(super) h: BigUint,
 }
 
 impl Curve {
+    /// Creates a point and validates it lies on the curve.
+    /// Use it for points coming from outside of the group law, e.g. outputs of rational maps.
     pub(crate) fn new_proy_point(&self, c: ProyCoordinates) -> Point {
+        let pt = self.new_proy_point_unchecked(c);
+        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+    }
+    /// Creates a point without checking the curve equation. The check is
+    /// only performed on debug builds.
+    pub(super) fn new_proy_point_unchecked(&self, c: ProyCoordinates) -> Point {
         let e = self.clone();
         let pt = Point { e, c };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        debug_assert!(self.is_on_curve(&pt), "{}", ERR_ECC_NEW);
+        pt
+    }
+}
+
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
     }
 }
 
 impl EllipticCurve for Curve {
     type F = Fp;
     type Scalar = Scalar;
+    type ScalarField = Zq;
     type Point = Point;
-    fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point {
+    fn try_new_point(
+        &self,
+        x: <Self::F as Field>::Elt,
+        y: <Self::F as Field>::Elt,
+    ) -> Result<Self::Point, Error> {
         let e = self.clone();
         let f = e.get_field();
         let pt = Point {
@@ -54,15 +79,19 @@ impl EllipticCurve for Curve {
             },
             e,
         };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        if self.is_on_curve(&pt) {
+            Ok(pt)
+        } else {
+            Err(Error::NotOnCurve)
+        }
     }
 
     fn new_scalar(&self, k: BigInt) -> Self::Scalar {
-        Scalar::new(k, &self.r)
+        self.0.q.elt(k)
     }
     fn identity(&self) -> Self::Point {
-        let f = &self.f;
-        self.new_proy_point(ProyCoordinates {
+        let f = &self.0.f;
+        self.new_proy_point_unchecked(ProyCoordinates {
             x: f.zero(),
             y: f.one(),
             t: f.zero(),
@@ -75,8 +104,8 @@ impl EllipticCurve for Curve {
         let y2 = &p.y ^ 2u32;
         let t2 = &p.t ^ 2u32;
         let z2 = &p.z ^ 2u32;
-        let l1 = x2 * &self.a + y2;
-        let r1 = t2 * &self.d + z2;
+        let l1 = x2 * &self.0.a + y2;
+        let r1 = t2 * &self.0.d + z2;
         let l2 = &p.x * &p.y;
         let r2 = &p.t * &p.z;
         let e1 = l1 - r1;
@@ -84,65 +113,98 @@ impl EllipticCurve for Curve {
         e1.is_zero() && e2.is_zero()
     }
     fn get_order(&self) -> BigUint {
-        self.r.clone()
+        self.0.q.get_modulus().to_biguint().unwrap()
     }
     fn get_cofactor(&self) -> BigInt {
-        self.h.to_bigint().unwrap()
+        self.0.h.to_bigint().unwrap()
     }
     fn get_field(&self) -> Self::F {
-        self.f.clone()
+        self.0.f.clone()
+    }
+    fn get_scalar_field(&self) -> Self::ScalarField {
+        self.0.q.clone()
     }
     fn get_generator(&self) -> Self::Point {
-        self.new_proy_point(ProyCoordinates {
-            x: self.gx.clone(),
-            y: self.gy.clone(),
-            t: &self.gx * &self.gy,
-            z: self.f.one(),
+        self.new_proy_point_unchecked(ProyCoordinates {
+            x: self.0.gx.clone(),
+            y: self.0.gy.clone(),
+            t: &self.0.gx * &self.0.gy,
+            z: self.0.f.one(),
         })
     }
 }
 
 impl Decode for Curve {
     type Deser = Point;
+    /// Decodes either the RFC 8032 encoding of a point or its affine
+    /// coordinates (x, y), which are distinguished by their length.
     // based on https://tools.ietf.org/html/rfc8032#section-5.2.3
-    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, Error> {
-        let modulus = self.get_field().get_modulus();
-        let size = (modulus.bits() as usize + 1 + 7) / 8;
-        // step 1
-        if buf.len() != size {
-            return Err(Error::new(ErrorKind::Other, "Wrong input buffer size."));
+    fn decode_with(&self, buf: &[u8], policy: &DecodePolicy) -> Result<Self::Deser, DecodeError> {
+        let size = self.encoding_size();
+        let coord_size = self.0.f.size_bytes();
+        let p = if buf.len() == size {
+            self.decode_compressed(buf, policy)?
+        } else if buf.len() == 2 * coord_size {
+            let x = self.decode_coordinate(&buf[..coord_size], policy)?;
+            let y = self.decode_coordinate(&buf[coord_size..], policy)?;
+            self.try_new_point(x, y)
+                .map_err(|_| DecodeError::NotOnCurve)?
+        } else {
+            return Err(DecodeError::WrongLength);
+        };
+        if policy.subgroup_check && !p.is_torsion_free() {
+            return Err(DecodeError::NotInSubgroup);
         }
-        let last_byte = size - 1;
+        Ok(p)
+    }
+}
+
+impl Curve {
+    /// Returns the length of the RFC 8032 encoding of a point.
+    pub(crate) fn encoding_size(&self) -> usize {
+        (self.0.f.get_modulus().bits() as usize + 1).div_ceil(8)
+    }
+    /// Returns the coefficients (a, d) of the curve equation.
+    pub(crate) fn coefficients(&self) -> (&FpElt, &FpElt) {
+        (&self.0.a, &self.0.d)
+    }
+    fn decode_coordinate(&self, buf: &[u8], policy: &DecodePolicy) -> Result<FpElt, DecodeError> {
+        let n = BigInt::from_bytes_le(Sign::Plus, buf);
+        if !policy.non_canonical && n >= self.0.f.get_modulus() {
+            return Err(DecodeError::NonCanonical);
+        }
+        Ok(self.0.f.elt(n))
+    }
+    fn decode_compressed(&self, buf: &[u8], policy: &DecodePolicy) -> Result<Point, DecodeError> {
+        // step 1
+        let last_byte = buf.len() - 1;
         let x_0 = (buf[last_byte] >> 7) & 0x01;
         let mut y_bytes = buf.to_vec();
         y_bytes[last_byte] &= &127; // clear msb
-        let y_zz = BigInt::from_bytes_le(Sign::Plus, &y_bytes);
-        if y_zz >= modulus {
-            return Err(Error::new(ErrorKind::Other, "Invalid y value chosen"));
-        }
-        let y = self.f.elt(y_zz);
+        let y = self.decode_coordinate(&y_bytes, policy)?;
 
         // step 2
         let yy = &y * &y;
-        let minus_one = -self.f.one();
+        let minus_one = -self.0.f.one();
         let u = &yy + &minus_one;
-        let v = (&self.d * &yy) - &self.a;
+        let v = (&self.0.d * &yy) - &self.0.a;
         let u_inv_v = u / v;
+        if !u_inv_v.is_square() {
+            return Err(DecodeError::NotOnCurve);
+        }
         let x_sqrt = u_inv_v.sqrt();
 
         // step 4 (step 3 is unnecessary)
-        if x_sqrt == self.f.zero() && x_0 == 0x01 {
-            return Err(Error::new(
-                ErrorKind::Other,
-                "Failed decoding on square root",
-            ));
+        if !policy.non_canonical && x_sqrt.is_zero() && x_0 == 0x01 {
+            return Err(DecodeError::NonCanonical);
         }
         let tag = ((x_sqrt.sgn0() >> 1) & 0x01) as u8;
         let mut x = x_sqrt;
         if tag != x_0 {
             x = -x;
         }
-        Ok(self.new_point(x, y))
+        self.try_new_point(x, y)
+            .map_err(|_| DecodeError::NotOnCurve)
     }
 }
 
@@ -151,7 +213,7 @@ impl std::fmt::Display for Curve {
         write!(
             f,
             "Twisted Edwards Curve ax^2+y^2=1+dx^2y^2\na: {}\nd: {}",
-            self.a, self.d,
+            self.0.a, self.0.d,
         )
     }
 }
@@ -171,15 +233,15 @@ pub struct Params {
 impl<'a> std::convert::From<&'a Params> for Curve {
     fn from(params: &'a Params) -> Curve {
         let f = Fp::new(BigUint::from_str(params.p).unwrap());
-        Curve {
+        Curve(Arc::new(Inner {
             a: f.from(params.a),
             d: f.from(params.d),
-            r: BigUint::from_str(params.r).unwrap(),
+            q: Zq::new(BigUint::from_str(params.r).unwrap()),
             h: BigUint::from_str(params.h).unwrap(),
             gx: f.from(params.gx),
             gy: f.from(params.gy),
             f,
-        }
+        }))
     }
 }
 
@@ -188,9 +250,127 @@ const ERR_ECC_NEW: &str = "not valid point";
 // tests for ser/deser
 #[cfg(test)]
 mod tests {
-    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
-    use crate::field::Field;
+    use crate::edwards::Curve;
+    use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve, Encode};
+    use crate::field::{Field, Sqrt};
     use crate::instances::{GetCurve, EDWARDS25519, EDWARDS448};
+    use crate::ops::{FromFactory, Serialize};
+    use crate::primefield::FpElt;
+    use crate::DecodeError;
+    use sha2::{Digest, Sha512};
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use sha3::Shake256;
+
+    // Derives the public key from a secret key as in RFC 8032 Section 5.1.5
+    // and Section 5.2.5.
+    fn public_key(ec: &Curve, hash: &[u8], clamp: fn(&mut [u8])) -> Vec<u8> {
+        let mut s = hash[..ec.encoding_size()].to_vec();
+        clamp(&mut s);
+        let s = ec.get_scalar_field().from_bytes_wide_le(&s);
+        (ec.get_generator() * s).encode(true)
+    }
+
+    #[test]
+    fn rfc8032_vectors() {
+        let ec = EDWARDS25519.get();
+        for (sk, pk) in [
+            (
+                "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            ),
+            (
+                "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            ),
+            (
+                "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+                "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            ),
+        ] {
+            let hash = Sha512::digest(hex::decode(sk).unwrap());
+            let pk = hex::decode(pk).unwrap();
+            let got = public_key(&ec, &hash, |s| {
+                s[0] &= 248;
+                s[31] &= 127;
+                s[31] |= 64;
+            });
+            assert_eq!(got, pk);
+            assert!(ec.decode(&pk).unwrap().encode(true) == pk);
+        }
+
+        let ec = EDWARDS448.get();
+        for (sk, pk) in [
+            (
+                "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+                "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+            ),
+            (
+                "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+                "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+            ),
+        ] {
+            let mut hash = [0u8; 114];
+            let mut shake = Shake256::default();
+            shake.update(&hex::decode(sk).unwrap());
+            shake.finalize_xof().read(&mut hash);
+            let pk = hex::decode(pk).unwrap();
+            let got = public_key(&ec, &hash, |s| {
+                s[0] &= 252;
+                s[56] = 0;
+                s[55] |= 128;
+            });
+            assert_eq!(got, pk);
+            assert!(ec.decode(&pk).unwrap().encode(true) == pk);
+        }
+    }
+
+    #[test]
+    fn non_canonical_encodings() {
+        let strict = DecodePolicy::default();
+        let permissive = DecodePolicy {
+            non_canonical: true,
+            ..Default::default()
+        };
+        for &id in [EDWARDS25519, EDWARDS448].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let size = ec.encoding_size();
+            let last = size - 1;
+            let one = ec.identity();
+            let minus_one = ec.new_point(f.zero(), -f.one());
+
+            // y = p+1 is a non-canonical encoding of y = 1.
+            let mut ser = (f.get_modulus() + 1u32).to_biguint().unwrap().to_bytes_le();
+            ser.resize(size, 0);
+            assert_eq!(
+                ec.decode_with(&ser, &strict).err(),
+                Some(DecodeError::NonCanonical)
+            );
+            assert!(ec.decode_with(&ser, &permissive).unwrap() == one);
+
+            // the sign bit must be clear when x = 0.
+            for p in [&one, &minus_one] {
+                let mut ser = p.encode(true);
+                ser[last] |= 0x80;
+                assert_eq!(
+                    ec.decode_with(&ser, &strict).err(),
+                    Some(DecodeError::NonCanonical)
+                );
+                assert!(ec.decode_with(&ser, &permissive).unwrap() == *p);
+            }
+
+            // non-canonical affine coordinates.
+            let mut ser = ec.identity().encode(false);
+            let n = f.size_bytes();
+            let p1 = (f.get_modulus() + 1u32).to_biguint().unwrap().to_bytes_le();
+            ser[n..n + p1.len()].copy_from_slice(&p1);
+            assert_eq!(
+                ec.decode_with(&ser, &strict).err(),
+                Some(DecodeError::NonCanonical)
+            );
+            assert!(ec.decode_with(&ser, &permissive).unwrap() == one);
+        }
+    }
 
     #[test]
     fn point_serialization() {
@@ -198,8 +378,8 @@ mod tests {
             let ec = id.get();
             let modulus = ec.get_field().get_modulus();
             let gen = ec.get_generator();
-            let ser = gen.encode(false); // compression does not exist
-            assert_eq!(ser.len(), (modulus.bits() as usize + 1 + 7) / 8);
+            let ser = gen.encode(true);
+            assert_eq!(ser.len(), (modulus.bits() as usize + 1).div_ceil(8));
             let deser = ec.decode(&ser).unwrap();
             assert!(
                 ec.is_on_curve(&deser),
@@ -207,6 +387,97 @@ mod tests {
                 id
             );
             assert!(gen == deser, "decompressed point equality check for {}", id);
+            let ser = gen.encode(false);
+            assert_eq!(ser.len(), 2 * ec.get_field().size_bytes());
+            assert!(ser[..ser.len() / 2] == ec.0.gx.to_bytes_le()[..]);
+            assert!(gen == ec.decode(&ser).unwrap());
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        for &id in [EDWARDS25519, EDWARDS448].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let ser = ec.get_generator().encode(true);
+            let size = ser.len();
+            let last = size - 1;
+            assert_eq!(
+                ec.decode(&ser[..last]).err(),
+                Some(DecodeError::WrongLength)
+            );
+
+            // y = p is not the canonical representative of 0.
+            let mut ser = f.get_modulus().to_biguint().unwrap().to_bytes_le();
+            ser.resize(size, 0);
+            assert_eq!(ec.decode(&ser).err(), Some(DecodeError::NonCanonical));
+
+            // y = 1 implies x = 0, so the sign bit must not be set.
+            let mut ser = vec![0u8; size];
+            ser[0] = 1;
+            assert!(ec.decode(&ser).is_ok());
+            ser[last] |= 0x80;
+            assert_eq!(ec.decode(&ser).err(), Some(DecodeError::NonCanonical));
+
+            // finds a y such that (y^2-1)/(dy^2-a) is a non-square.
+            let y = (2..)
+                .map(|i: i64| -> FpElt { f.from(i) })
+                .find(|y| {
+                    let yy = y * y;
+                    let u = &yy - f.one();
+                    let v = &ec.0.d * &yy - &ec.0.a;
+                    !(u / v).is_square()
+                })
+                .unwrap();
+            let mut ser = y.to_bytes_le();
+            ser.resize(size, 0);
+            assert_eq!(ec.decode(&ser).err(), Some(DecodeError::NotOnCurve));
+        }
+    }
+
+    #[test]
+    fn subgroup_checks() {
+        let policy = DecodePolicy {
+            subgroup_check: true,
+            ..Default::default()
+        };
+        for &id in [EDWARDS25519, EDWARDS448].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let size = ec.get_generator().encode(true).len();
+            let gen = ec.get_generator();
+            let id = ec.identity();
+            assert!(gen.is_torsion_free() && !gen.is_small_order());
+            assert!(id.is_torsion_free() && id.is_small_order());
+            assert!(gen.torsion_component().is_zero());
+            assert!(ec.decode_with(&gen.encode(true), &policy).unwrap() == gen);
+
+            // (0,-1) has order two.
+            let t = ec.new_point(f.zero(), -f.one());
+            assert!(!t.is_torsion_free() && t.is_small_order());
+            let p = &gen + &t;
+            assert!(!p.is_torsion_free() && !p.is_small_order());
+            assert!(p.torsion_component() == t);
+            assert!(ec.decode(&p.encode(true)).unwrap() == p);
+            assert_eq!(
+                ec.decode_with(&p.encode(true), &policy).err(),
+                Some(DecodeError::NotInSubgroup)
+            );
+            assert_eq!(
+                ec.decode_with(&t.encode(true), &policy).err(),
+                Some(DecodeError::NotInSubgroup)
+            );
+
+            // any point is the sum of its components.
+            let p = (2u32..)
+                .find_map(|i| {
+                    let mut ser = f.from(i).to_bytes_le();
+                    ser.resize(size, 0);
+                    ec.decode(&ser).ok()
+                })
+                .unwrap();
+            let t = p.torsion_component();
+            assert!(t.is_small_order() && (&p - &t).is_torsion_free());
         }
     }
 }
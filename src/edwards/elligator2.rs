@@ -4,6 +4,7 @@ use crate::field::Field;
 use crate::montgomery::Curve as MtCurve;
 use crate::montgomery::Ell2 as MtEll2;
 use crate::primefield::FpElt;
+use crate::Error;
 
 pub struct Ell2 {
     ratmap: Box<dyn RationalMap<E0 = TeCurve, E1 = MtCurve> + 'static>,
@@ -16,23 +17,34 @@ impl Ell2 {
         z: FpElt,
         ratmap: Option<Box<dyn RationalMap<E0 = TeCurve, E1 = MtCurve>>>,
     ) -> Ell2 {
+        match Ell2::try_new(e, z, ratmap) {
+            Ok(m) => m,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    /// Returns an error if the rational map is missing, if its domain is not
+    /// the curve, or if its codomain does not satisfy the requirements of the map.
+    pub fn try_new(
+        e: TeCurve,
+        z: FpElt,
+        ratmap: Option<Box<dyn RationalMap<E0 = TeCurve, E1 = MtCurve>>>,
+    ) -> Result<Ell2, Error> {
         let (map_to_curve, ratmap) = match ratmap {
-            None => {
-                // If no ratmap is provided, it must use the cannonical birational map.
-                todo!()
-            }
+            // If no ratmap is provided, it must use the cannonical birational map,
+            // which is not supported yet.
+            None => return Err(Error::Unsupported),
             Some(r) => {
                 if r.domain() != e {
-                    panic!("Domain of rational map is incompatible with curve")
+                    return Err(Error::CurveMismatch);
                 }
                 let mt_curve = r.codomain();
-                (Box::new(MtEll2::new(mt_curve, z)), r)
+                (Box::new(MtEll2::try_new(mt_curve, z)?), r)
             }
         };
-        Ell2 {
+        Ok(Ell2 {
             map_to_curve,
             ratmap,
-        }
+        })
     }
 }
 
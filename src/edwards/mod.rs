@@ -4,9 +4,8 @@
 mod curve;
 mod elligator2;
 mod point;
-mod scalar;
 
 pub use crate::edwards::curve::{Curve, Params};
 pub use crate::edwards::elligator2::Ell2;
 pub use crate::edwards::point::{Point, ProyCoordinates};
-pub use crate::edwards::scalar::Scalar;
+pub use crate::scalarfield::Scalar;
@@ -1,17 +1,19 @@
 use impl_ops::impl_op_ex;
-use num_bigint::ToBigInt;
+use num_bigint::BigInt;
+use num_integer::Integer;
 use num_traits::identities::{One, Zero};
 
 use std::ops;
 
 use crate::do_if_eq;
 use crate::edwards::curve::Curve;
-use crate::edwards::scalar::Scalar;
 use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
 use crate::field::{Field, Sgn0};
 use crate::ops::ScMulRef;
 use crate::ops::Serialize;
 use crate::primefield::FpElt;
+use crate::scalarfield::{mul_int, Scalar};
+use crate::Error;
 
 #[derive(Clone)]
 pub struct ProyCoordinates {
@@ -29,22 +31,59 @@ pub struct Point {
 impl ScMulRef<Scalar> for Point {}
 impl EcPoint<Scalar> for Point {
     fn is_zero(&self) -> bool {
-        self.c.x.is_zero() && !self.c.y.is_zero() && self.c.t.is_zero() && !self.c.z.is_zero()
+        self.c.x.is_zero() && self.c.t.is_zero() && !self.c.z.is_zero() && self.c.y == self.c.z
+    }
+    fn try_add(&self, p: &Self) -> Result<Self, Error> {
+        if self.e == p.e {
+            Ok(self.core_add(p))
+        } else {
+            Err(Error::CurveMismatch)
+        }
+    }
+    fn try_mul(&self, k: &Scalar) -> Result<Self, Error> {
+        if self.e.0.q == k.get_field() {
+            Ok(self.core_mul(k))
+        } else {
+            Err(Error::GroupMismatch)
+        }
+    }
+    fn mul_int(&self, k: &BigInt) -> Self {
+        mul_int(self, k, self.e.identity(), Point::core_doubling)
+    }
+    fn is_torsion_free(&self) -> bool {
+        self.e.0.h.is_one() || self.mul_int(&self.e.0.q.get_modulus()).is_zero()
+    }
+    fn is_small_order(&self) -> bool {
+        self.mul_int(&self.e.get_cofactor()).is_zero()
+    }
+    fn torsion_component(&self) -> Self {
+        let r = self.e.0.q.get_modulus();
+        let h = self.e.get_cofactor();
+        // [r*u]P with u = 1/r mod h removes the component of order r.
+        let u = r.extended_gcd(&h).x.mod_floor(&h);
+        self.mul_int(&r).mul_int(&u)
     }
 }
 impl Encode for Point {
+    /// Encodes the point following RFC 8032 if `compress` is set, i.e., the
+    /// y-coordinate and the sign of the x-coordinate. Otherwise, it returns
+    /// the affine coordinates (x, y), each one in little-endian order.
     // based on https://tools.ietf.org/html/rfc8032#section-5.2.2
-    fn encode(&self, _: bool) -> Vec<u8> {
+    fn encode(&self, compress: bool) -> Vec<u8> {
         let mut p_normal = self.clone();
         p_normal.normalize();
         let coords = p_normal.c;
         let x = &coords.x;
         let y = &coords.y;
+        if !compress {
+            let mut enc = x.to_bytes_le();
+            enc.append(&mut y.to_bytes_le());
+            return enc;
+        }
         // negative == odd
         let x_0 = (((x.sgn0() >> 1) & 0x01) << 7) as u8;
         let mut enc = y.to_bytes_le();
-        let p = self.e.f.get_modulus();
-        let size = (p.bits() as usize + 1 + 7) / 8;
+        let size = self.e.encoding_size();
         enc.resize(size, 0u8);
         let last = enc.len() - 1;
         enc[last] |= x_0;
@@ -61,7 +100,7 @@ impl Point {
         self.c.z.set_one();
     }
     fn core_neg(&self) -> Point {
-        self.e.new_proy_point(ProyCoordinates {
+        self.e.new_proy_point_unchecked(ProyCoordinates {
             x: -&self.c.x,
             y: self.c.y.clone(),
             t: -&self.c.t,
@@ -71,7 +110,7 @@ impl Point {
     fn core_add(&self, p: &Point) -> Point {
         let (x1, y1, t1, z1) = (&self.c.x, &self.c.y, &self.c.t, &self.c.z);
         let (x2, y2, t2, z2) = (&p.c.x, &p.c.y, &p.c.t, &p.c.z);
-        let (a_ec, d_ec) = (&self.e.a, &self.e.d);
+        let (a_ec, d_ec) = (&self.e.0.a, &self.e.0.d);
         let aa = x1 * x2; // A = X1 * X2
         let bb = y1 * y2; // B = Y1 * Y2
         let cc = d_ec * t1 * t2; // C = d*T1 * T2
@@ -84,7 +123,32 @@ impl Point {
         let y3 = &gg * &hh; // Y3 = G * H
         let t3 = ee * hh; // T3 = E * H
         let z3 = ff * gg; // Z3 = F * G
-        self.e.new_proy_point(ProyCoordinates {
+        self.e.new_proy_point_unchecked(ProyCoordinates {
+            x: x3,
+            y: y3,
+            t: t3,
+            z: z3,
+        })
+    }
+    // Reference: "Twisted Edwards curves revisited" by Hisil-Wong-Carter-Dawson
+    // (dbl-2008-hwcd), which does not use T1.
+    fn core_doubling(&self) -> Point {
+        let (x1, y1, z1) = (&self.c.x, &self.c.y, &self.c.z);
+        let aa = x1 * x1; // A = X1^2
+        let bb = y1 * y1; // B = Y1^2
+        let zz = z1 * z1;
+        let cc = &zz + &zz; // C = 2*Z1^2
+        let dd = &aa * &self.e.0.a; // D = a*A
+        let xy = x1 + y1;
+        let ee = &xy * &xy - &aa - &bb; // E = (X1 + Y1)^2 - A - B
+        let gg = &dd + &bb; // G = D + B
+        let ff = &gg - &cc; // F = G - C
+        let hh = dd - bb; // H = D - B
+        let x3 = &ee * &ff; // X3 = E * F
+        let y3 = &gg * &hh; // Y3 = G * H
+        let t3 = ee * hh; // T3 = E * H
+        let z3 = ff * gg; // Z3 = F * G
+        self.e.new_proy_point_unchecked(ProyCoordinates {
             x: x3,
             y: y3,
             t: t3,
@@ -94,7 +158,7 @@ impl Point {
     fn core_mul(&self, k: &Scalar) -> Point {
         let mut q = self.e.identity();
         for ki in k.iter_lr() {
-            q = &q + &q;
+            q = q.core_doubling();
             if ki {
                 q = q + self;
             }
@@ -123,13 +187,34 @@ impl_op_ex!(+|a: &Point , b: &Point | -> Point  {
 impl_op_ex!(-|a: &Point, b: &Point| -> Point { a + (-b) });
 impl_op_ex!(-|a: &Point| -> Point { a.core_neg() });
 impl_op_ex!(*|a: &Point, b: &Scalar| -> Point {
-    let r = a.e.r.to_bigint().unwrap();
-    do_if_eq!(r == b.r, a.core_mul(b), ERR_MUL_OP)
+    do_if_eq!(a.e.0.q == b.get_field(), a.core_mul(b), ERR_MUL_OP)
 });
 
 const ERR_MUL_OP: &str = "Scalar don't match with point";
 const ERR_ADD_OP: &str = "points of different curves";
 
+impl<'b> std::ops::Mul<&'b Point> for &Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: &'b Point) -> Self::Output {
+        other * self
+    }
+}
+impl<'b> std::ops::Mul<&'b Point> for Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: &'b Point) -> Self::Output {
+        other * &self
+    }
+}
+impl std::ops::Mul<Point> for Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: Point) -> Self::Output {
+        other * &self
+    }
+}
+
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -6,10 +6,11 @@ use num_bigint::{BigInt, BigUint};
 
 use std::fmt::Display;
 
-use crate::field::Field;
-use crate::ops::{AddRef, DivRef, MulRef, NegRef, ScMulRef, Serialize, SubRef};
+use crate::field::{Field, FieldElement};
+use crate::ops::{AddRef, NegRef, ScMulRef, SubRef};
+use crate::{DecodeError, Error};
 /// EcScalar models the behaviour of a scalar to multiply points.
-pub trait EcScalar: Display + AddRef + SubRef + MulRef + DivRef + NegRef + Serialize {}
+pub trait EcScalar: FieldElement + NegRef {}
 
 /// EcPoint models the behaviour of a point on an elliptic curve.
 pub trait EcPoint<T>: Display + AddRef + SubRef + NegRef + ScMulRef<T> + Encode + Eq
@@ -17,6 +18,21 @@ where
     T: EcScalar,
 {
     fn is_zero(&self) -> bool;
+    /// Adds two points, or fails if they belong to different curves.
+    fn try_add(&self, _: &Self) -> Result<Self, Error>;
+    /// Multiplies a point by a scalar, or fails if the scalar does not match
+    /// with the order of the group.
+    fn try_mul(&self, _: &T) -> Result<Self, Error>;
+    /// Multiplies a point by a non-negative integer, which, unlike a scalar,
+    /// is not reduced modulo r, e.g., the order or the cofactor of the group.
+    fn mul_int(&self, _: &BigInt) -> Self;
+    /// Determines whether the point belongs to the subgroup of prime order r.
+    fn is_torsion_free(&self) -> bool;
+    /// Determines whether the order of the point divides the cofactor h.
+    fn is_small_order(&self) -> bool;
+    /// Returns the component of the point in the subgroup of order h, i.e.,
+    /// the point T such that P = P' + T for some P' of order r.
+    fn torsion_component(&self) -> Self;
 }
 
 /// Encode provides functionality for encoding elliptic curve points as
@@ -25,26 +41,64 @@ pub trait Encode {
     fn encode(&self, compress: bool) -> Vec<u8>;
 }
 
+/// DecodePolicy determines which encodings are accepted when decoding points.
+/// The default policy only checks that points lie on the curve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DecodePolicy {
+    /// Rejects points that are not in the subgroup of prime order.
+    pub subgroup_check: bool,
+    /// Accepts non-canonical encodings of Edwards points, i.e., those with
+    /// y ≥ p, or with x = 0 and the sign bit set. RFC 8032 rejects them.
+    pub non_canonical: bool,
+    /// Accepts the hybrid format of SEC1 (0x06 and 0x07), which is found in
+    /// legacy data.
+    pub accept_hybrid: bool,
+    /// Rejects the SEC1 encoding of the point at infinity (0x00).
+    pub reject_identity: bool,
+    /// Rejects compressed points of SEC1 (0x02 and 0x03), e.g., when peers
+    /// use a convention for the sign of y other than its parity.
+    pub reject_compressed: bool,
+}
+
 /// Decode provides functionality for decoding octet-strings into
 /// elliptic curve points
 pub trait Decode {
     type Deser;
-    fn decode(&self, _: &[u8]) -> Result<Self::Deser, std::io::Error>;
+    /// Decodes a point using the default policy.
+    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, DecodeError> {
+        self.decode_with(buf, &DecodePolicy::default())
+    }
+    /// Decodes a point accepting only the encodings allowed by the policy.
+    fn decode_with(&self, _: &[u8], _: &DecodePolicy) -> Result<Self::Deser, DecodeError>;
 }
 
 /// Curve trait allows to implement elliptic curve operations.
 pub trait EllipticCurve: Decode {
     type F: Field;
     type Scalar: EcScalar;
+    type ScalarField: Field<Elt = Self::Scalar>;
     type Point: EcPoint<Self::Scalar>;
     fn identity(&self) -> Self::Point;
-    fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point;
+    /// Creates a point from its affine coordinates, and panics if it is not on the curve.
+    fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point {
+        match self.try_new_point(x, y) {
+            Ok(p) => p,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    /// Creates a point from its affine coordinates, or fails if it is not on the curve.
+    fn try_new_point(
+        &self,
+        x: <Self::F as Field>::Elt,
+        y: <Self::F as Field>::Elt,
+    ) -> Result<Self::Point, Error>;
     fn new_scalar(&self, _: BigInt) -> Self::Scalar;
     fn get_generator(&self) -> Self::Point;
     fn is_on_curve(&self, _: &Self::Point) -> bool;
     fn get_order(&self) -> BigUint;
     fn get_cofactor(&self) -> BigInt;
     fn get_field(&self) -> Self::F;
+    fn get_scalar_field(&self) -> Self::ScalarField;
 }
 
 /// Rational map between two elliptic curves.
@@ -0,0 +1,106 @@
+//! This is documentation for the `error` module.
+//!
+//! The error module defines the errors reported by the fallible functions of this crate.
+
+use std::fmt::Display;
+
+/// Error lists the failures reported by the fallible operations of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The operands are elements of different fields.
+    FieldMismatch,
+    /// The points belong to different curves.
+    CurveMismatch,
+    /// The scalar does not match with the order of the group.
+    GroupMismatch,
+    /// The coordinates do not satisfy the curve equation.
+    NotOnCurve,
+    /// The string does not represent an element of the field.
+    InvalidString,
+    /// The parameters do not satisfy the requirements of the map.
+    InvalidParameters,
+    /// The operation is not supported for the given input.
+    Unsupported,
+    /// The point is not valid for the operation, e.g., a public key that is
+    /// the identity or lies outside the prime-order subgroup.
+    InvalidPoint,
+    /// The signature is not valid for the message and the public key.
+    InvalidSignature,
+    /// The proof is not valid for the input and the public key.
+    InvalidProof,
+    /// Some signatures of a batch are not valid; their indices are listed.
+    InvalidBatch(Vec<usize>),
+    /// The octet-string cannot be decoded.
+    Decode(DecodeError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::FieldMismatch => write!(f, "elements of different fields"),
+            Error::CurveMismatch => write!(f, "points of different curves"),
+            Error::GroupMismatch => write!(f, "Scalar don't match with point"),
+            Error::NotOnCurve => write!(f, "not valid point"),
+            Error::InvalidString => write!(f, "invalid string for a field element"),
+            Error::InvalidParameters => write!(f, "wrong input parameters"),
+            Error::Unsupported => write!(f, "unsupported operation"),
+            Error::InvalidPoint => write!(f, "invalid point for the operation"),
+            Error::InvalidSignature => write!(f, "invalid signature"),
+            Error::InvalidProof => write!(f, "invalid proof"),
+            Error::InvalidBatch(v) => write!(f, "invalid signatures at {:?}", v),
+            Error::Decode(e) => write!(f, "decoding error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        Error::Decode(e)
+    }
+}
+
+/// DecodeError describes why an octet-string could not be decoded into a
+/// field element, a scalar, or a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The length of the input does not match with any valid encoding.
+    WrongLength,
+    /// The tag byte does not correspond to any supported or allowed format,
+    /// or it disagrees with the encoded point.
+    InvalidTag,
+    /// A value is not reduced, i.e., it is not the canonical representative
+    /// of the element.
+    NonCanonical,
+    /// There is no point with the given coordinates, e.g., because the
+    /// recovered coordinate is a non-square.
+    NotOnCurve,
+    /// The point is not in the prime-order subgroup.
+    NotInSubgroup,
+    /// The structure of the input is not valid, e.g., a key whose DER or
+    /// PEM encoding is malformed.
+    Malformed,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::WrongLength => write!(f, "wrong input length"),
+            DecodeError::InvalidTag => write!(f, "invalid tag"),
+            DecodeError::NonCanonical => write!(f, "non-canonical encoding"),
+            DecodeError::NotOnCurve => write!(f, "point is not on the curve"),
+            DecodeError::NotInSubgroup => write!(f, "point is not in the prime-order subgroup"),
+            DecodeError::Malformed => write!(f, "malformed input"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
@@ -0,0 +1,219 @@
+//! This is documentation for the `expander` module.
+//!
+//! The expander module implements the functions of RFC 9380 that expand a
+//! message into a uniformly random octet-string of any length, which are the
+//! first step of hashing to a field or to a group.
+
+use digest::core_api::BlockSizeUser;
+use digest::{Digest, ExtendableOutput, Update, XofReader};
+
+use crate::Error;
+
+/// Expands a message into `len` uniformly random bytes using a hash function
+/// with a fixed-length output and a domain separation tag `dst`, i.e., the
+/// `expand_message_xmd` function of RFC 9380. Tags longer than 255 bytes are
+/// hashed first.
+///
+/// It fails if `len` is larger than 255 times the output size of the hash
+/// function, or larger than 65535.
+// based on https://www.rfc-editor.org/rfc/rfc9380.html#section-5.3.1
+pub fn expand_message_xmd<D>(msg: &[u8], dst: &[u8], len: usize) -> Result<Vec<u8>, Error>
+where
+    D: Digest + BlockSizeUser,
+{
+    let b_in_bytes = <D as Digest>::output_size();
+    let ell = len.div_ceil(b_in_bytes);
+    if len == 0 || ell > 255 || len > 65535 {
+        return Err(Error::InvalidParameters);
+    }
+    let long_dst;
+    let dst = if dst.len() > 255 {
+        long_dst = D::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        &long_dst[..]
+    } else {
+        dst
+    };
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let z_pad = vec![0u8; D::block_size()];
+    let b_0 = D::new()
+        .chain_update(z_pad)
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut b_i = D::new()
+        .chain_update(&b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let chained: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(x, y)| x ^ y).collect();
+        b_i = D::new()
+            .chain_update(chained)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len);
+    Ok(uniform_bytes)
+}
+
+/// Expands a message into `len` uniformly random bytes using an extendable
+/// output function and a domain separation tag `dst`, i.e., the
+/// `expand_message_xof` function of RFC 9380. Tags longer than 255 bytes are
+/// hashed first into ceil(2k/8) bytes, where `k` is the target security
+/// level in bits.
+///
+/// It fails if `len` is larger than 65535.
+// based on https://www.rfc-editor.org/rfc/rfc9380.html#section-5.3.2
+pub fn expand_message_xof<X>(msg: &[u8], dst: &[u8], len: usize, k: usize) -> Result<Vec<u8>, Error>
+where
+    X: Default + ExtendableOutput + Update,
+{
+    if len == 0 || len > 65535 {
+        return Err(Error::InvalidParameters);
+    }
+    let xof = |data: &[&[u8]], len: usize| {
+        let mut h = X::default();
+        data.iter().for_each(|d| h.update(d));
+        let mut out = vec![0u8; len];
+        h.finalize_xof().read(&mut out);
+        out
+    };
+    let long_dst;
+    let dst = if dst.len() > 255 {
+        long_dst = xof(&[b"H2C-OVERSIZE-DST-", dst], (2 * k).div_ceil(8));
+        &long_dst[..]
+    } else {
+        dst
+    };
+    let len_bytes = (len as u16).to_be_bytes();
+    Ok(xof(&[msg, &len_bytes, dst, &[dst.len() as u8]], len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_message_xmd, expand_message_xof};
+    use crate::Error;
+    use sha2::{Sha256, Sha512};
+    use sha3::Shake128;
+
+    // Test vectors from RFC 9380 Appendix K.
+    #[test]
+    fn xmd_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        for (msg, len, want) in [
+            (
+                "",
+                0x20,
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                "abc",
+                0x20,
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+            (
+                "abc",
+                0x80,
+                "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a\
+                 647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e07985635\
+                 bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00\
+                 058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40",
+            ),
+        ] {
+            let got = expand_message_xmd::<Sha256>(msg.as_bytes(), dst, len).unwrap();
+            assert_eq!(hex::encode(got), want);
+        }
+
+        let long_dst = [
+            &b"QUUX-V01-CS02-with-expander-SHA256-128-long-DST-"[..],
+            &[b'1'; 208][..],
+        ]
+        .concat();
+        let got = expand_message_xmd::<Sha256>(b"", &long_dst, 0x20).unwrap();
+        assert_eq!(
+            hex::encode(got),
+            "e8dc0c8b686b7ef2074086fbdd2f30e3f8bfbd3bdf177f73f04b97ce618a3ed3"
+        );
+
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        for (msg, want) in [
+            (
+                "",
+                "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba",
+            ),
+            (
+                "abc",
+                "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc",
+            ),
+        ] {
+            let got = expand_message_xmd::<Sha512>(msg.as_bytes(), dst, 0x20).unwrap();
+            assert_eq!(hex::encode(got), want);
+        }
+    }
+
+    #[test]
+    fn xmd_lengths() {
+        let dst = b"DST";
+        assert_eq!(
+            expand_message_xmd::<Sha256>(b"", dst, 0).err(),
+            Some(Error::InvalidParameters)
+        );
+        assert!(expand_message_xmd::<Sha256>(b"", dst, 255 * 32).is_ok());
+        assert_eq!(
+            expand_message_xmd::<Sha256>(b"", dst, 255 * 32 + 1).err(),
+            Some(Error::InvalidParameters)
+        );
+    }
+
+    // Test vectors from RFC 9380 Appendix K.6.
+    #[test]
+    fn xof_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHAKE128";
+        for (msg, len, want) in [
+            (
+                "",
+                0x20,
+                "86518c9cd86581486e9485aa74ab35ba150d1c75c88e26b7043e44e2acd735a2",
+            ),
+            (
+                "abcdef0123456789",
+                0x20,
+                "912c58deac4821c3509dbefa094df54b34b8f5d01a191d1d3108a2c89077acca",
+            ),
+            (
+                "abc",
+                0x80,
+                "c952f0c8e529ca8824acc6a4cab0e782fc3648c563ddb00da7399f2ae35654f4\
+                 860ec671db2356ba7baa55a34a9d7f79197b60ddae6e64768a37d699a7832349\
+                 6db3878c8d64d909d0f8a7de4927dcab0d3dbbc26cb20a49eceb0530b431cdf4\
+                 7bc8c0fa3e0d88f53b318b6739fbed7d7634974f1b5c386d6230c76260d5337a",
+            ),
+        ] {
+            let got = expand_message_xof::<Shake128>(msg.as_bytes(), dst, len, 128).unwrap();
+            assert_eq!(hex::encode(got), want);
+        }
+
+        let long_dst = [
+            &b"QUUX-V01-CS02-with-expander-SHAKE128-long-DST-"[..],
+            &[b'1'; 210][..],
+        ]
+        .concat();
+        let got = expand_message_xof::<Shake128>(b"abc", &long_dst, 0x20, 128).unwrap();
+        assert_eq!(
+            hex::encode(got),
+            "690c8d82c7213b4282c6cb41c00e31ea1d3e2005f93ad19bbf6da40f15790c5c"
+        );
+        assert_eq!(
+            expand_message_xof::<Shake128>(b"", dst, 65536, 128).err(),
+            Some(Error::InvalidParameters)
+        );
+    }
+}
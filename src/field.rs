@@ -7,7 +7,7 @@ use num_traits::identities::{One, Zero};
 use std::fmt::Display;
 use std::ops::BitXor;
 
-use crate::ops::{AddRef, DivRef, FromFactory, MulRef, Serialize, SubRef};
+use crate::ops::{AddRef, DivRef, FromFactory, MulRef, Serialize, SubRef, TryFromFactory};
 
 /// Sqrt trait adds square-root calculation and quadratic-residue testing.
 pub trait Sqrt {
@@ -41,6 +41,10 @@ pub trait FromFactoryStr<Out>: for<'a> FromFactory<&'a str, Output = Out> {}
 
 impl<T, U> FromFactoryStr<U> for T where T: for<'a> FromFactory<&'a str, Output = U> {}
 
+pub trait TryFromFactoryStr<Out>: for<'a> TryFromFactory<&'a str, Output = Out> {}
+
+impl<T, U> TryFromFactoryStr<U> for T where T: for<'a> TryFromFactory<&'a str, Output = U> {}
+
 pub trait FromFactoryPrimitive<Out>
 where
     Self: FromFactory<u8, Output = Out>
@@ -69,7 +73,9 @@ impl<T, U> FromFactoryPrimitive<U> for T where
 /// Field is a fabric to instante a finite field.
 pub trait Field
 where
-    Self: FromFactoryPrimitive<<Self as Field>::Elt> + FromFactoryStr<<Self as Field>::Elt>,
+    Self: FromFactoryPrimitive<<Self as Field>::Elt>
+        + FromFactoryStr<<Self as Field>::Elt>
+        + TryFromFactoryStr<<Self as Field>::Elt>,
 {
     /// `Elt` determines the type of field elements.
     type Elt: FieldElement;
@@ -0,0 +1,216 @@
+//! This is documentation for the `hashtocurve` module.
+//!
+//! The hashtocurve module implements the hash_to_field, encode_to_curve, and
+//! hash_to_curve functions of RFC 9380, which hash messages into elements of
+//! a field, and into points of an elliptic curve using a [`MapToCurve`].
+//!
+//! ```
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::hashtocurve::HashToCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::ops::FromFactory;
+//!  use redox_ecc::weierstrass::SSWU;
+//!  use sha2::Sha256;
+//!
+//!  let ec = P256.get();
+//!  let map = SSWU::new(ec.clone(), ec.get_field().from(-10));
+//!  let h2c = HashToCurve::<Sha256, _>::new(ec, map, b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_", 128);
+//!  let p = h2c.hash_to_curve(b"abc").unwrap();
+//!  let q = h2c.hash_to_curve(b"abc").unwrap();
+//!  assert!(p == q);
+//! ```
+
+use std::marker::PhantomData;
+
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use num_bigint::{BigInt, Sign};
+
+use crate::ellipticcurve::{EllipticCurve, MapToCurve};
+use crate::expander::expand_message_xmd;
+use crate::field::Field;
+use crate::Error;
+
+/// Hashes a message into `count` elements of the field `f`, reducing strings
+/// of L = ceil((ceil(log2(p)) + k) / 8) bytes each, where `k` is the target
+/// security level in bits, i.e., the hash_to_field function of RFC 9380
+/// using `expand_message_xmd`.
+///
+/// Since it only relies on the modulus, it also hashes into scalar fields.
+// based on https://www.rfc-editor.org/rfc/rfc9380.html#section-5.2
+pub fn hash_to_field<D, F>(
+    f: &F,
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+    k: usize,
+) -> Result<Vec<F::Elt>, Error>
+where
+    D: Digest + BlockSizeUser,
+    F: Field,
+{
+    let len = (f.get_modulus().bits() as usize + k).div_ceil(8);
+    let uniform_bytes = expand_message_xmd::<D>(msg, dst, count * len)?;
+    let elts = uniform_bytes
+        .chunks(len)
+        .map(|tv| f.elt(BigInt::from_bytes_be(Sign::Plus, tv)));
+    Ok(elts.collect())
+}
+
+/// HashToCurve hashes messages into points of an elliptic curve, as the
+/// suites of RFC 9380 do. It is made of the hash function `D` used by
+/// `expand_message_xmd`, a map to the curve, the domain separation tag, and
+/// the target security level in bits.
+///
+/// The cofactor is cleared by multiplying by h, which matches the h_eff of
+/// the suites for all built-in curves but BLS12-381.
+pub struct HashToCurve<D, M: MapToCurve> {
+    ec: M::E,
+    map: M,
+    dst: Vec<u8>,
+    k: usize,
+    _hash: PhantomData<D>,
+}
+
+impl<D, M> HashToCurve<D, M>
+where
+    D: Digest + BlockSizeUser,
+    M: MapToCurve,
+{
+    pub fn new(ec: M::E, map: M, dst: &[u8], k: usize) -> Self {
+        HashToCurve {
+            ec,
+            map,
+            dst: dst.to_vec(),
+            k,
+            _hash: PhantomData,
+        }
+    }
+    /// Hashes a message into `count` elements of the base field.
+    pub fn hash_to_field(
+        &self,
+        msg: &[u8],
+        count: usize,
+    ) -> Result<Vec<<<M::E as EllipticCurve>::F as Field>::Elt>, Error> {
+        hash_to_field::<D, _>(&self.ec.get_field(), msg, &self.dst, count, self.k)
+    }
+    fn clear_cofactor(&self, p: <M::E as EllipticCurve>::Point) -> <M::E as EllipticCurve>::Point {
+        p * self.ec.new_scalar(self.ec.get_cofactor())
+    }
+    /// Hashes a message into a point using a single evaluation of the map,
+    /// i.e., the nonuniform encoding (`_NU_`) of RFC 9380.
+    pub fn encode_to_curve(&self, msg: &[u8]) -> Result<<M::E as EllipticCurve>::Point, Error> {
+        let u = self.hash_to_field(msg, 1)?;
+        Ok(self.clear_cofactor(self.map.map(&u[0])))
+    }
+    /// Hashes a message into a point using the sum of two evaluations of the
+    /// map, i.e., the random oracle encoding (`_RO_`) of RFC 9380.
+    pub fn hash_to_curve(&self, msg: &[u8]) -> Result<<M::E as EllipticCurve>::Point, Error> {
+        let u = self.hash_to_field(msg, 2)?;
+        let q = self.map.map(&u[0]) + self.map.map(&u[1]);
+        Ok(self.clear_cofactor(q))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashToCurve;
+    use crate::edwards::Ell2;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::instances::{edwards25519_to_curve25519, GetCurve, EDWARDS25519, P256};
+    use crate::ops::FromFactory;
+    use crate::weierstrass::SSWU;
+    use sha2::{Sha256, Sha512};
+
+    // Test vectors from RFC 9380 Appendix J.1.1: (msg, u0, u1, P.x, P.y).
+    #[test]
+    fn p256_sswu_ro() {
+        let ec = P256.get();
+        let f = ec.get_field();
+        let map = SSWU::new(ec.clone(), f.from(-10));
+        let dst = b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_";
+        let h2c = HashToCurve::<Sha256, _>::new(ec.clone(), map, dst, 128);
+        for (msg, u0, u1, x, y) in [
+            (
+                "",
+                "ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009",
+                "8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a",
+                "2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4",
+                "8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415",
+            ),
+            (
+                "abc",
+                "afe47f2ea2b10465cc26ac403194dfb68b7f5ee865cda61e9f3e07a537220af1",
+                "379a27833b0bfe6f7bdca08e1e83c760bf9a338ab335542704edcd69ce9e46e0",
+                "0bb8b87485551aa43ed54f009230450b492fead5f1cc91658775dac4a3388a0f",
+                "5c41b3d0731a27a7b14bc0bf0ccded2d8751f83493404c84a88e71ffd424212e",
+            ),
+            (
+                "abcdef0123456789",
+                "0fad9d125a9477d55cf9357105b0eb3a5c4259809bf87180aa01d651f53d312c",
+                "b68597377392cd3419d8fcc7d7660948c8403b19ea78bbca4b133c9d2196c0fb",
+                "65038ac8f2b1def042a5df0b33b1f4eca6bff7cb0f9c6c1526811864e544ed80",
+                "cad44d40a656e7aff4002a8de287abc8ae0482b5ae825822bb870d6df9b56ca3",
+            ),
+        ] {
+            let hex = |s: &str| f.from(format!("0x{s}").as_str());
+            let u = h2c.hash_to_field(msg.as_bytes(), 2).unwrap();
+            assert!(u == [hex(u0), hex(u1)], "{msg}");
+            let want = ec.new_point(hex(x), hex(y));
+            assert!(h2c.hash_to_curve(msg.as_bytes()).unwrap() == want, "{msg}");
+        }
+    }
+
+    // Test vectors from RFC 9380 Appendix J.5: (msg, P.x, P.y) for the
+    // random oracle and the nonuniform encodings.
+    #[test]
+    fn edwards25519_ell2() {
+        let ec = EDWARDS25519.get();
+        let f = ec.get_field();
+        let ell2 = || {
+            let ratmap = Box::new(edwards25519_to_curve25519());
+            Ell2::new(ec.clone(), f.from(2), Some(ratmap))
+        };
+        let ro = HashToCurve::<Sha512, _>::new(
+            ec.clone(),
+            ell2(),
+            b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_RO_",
+            128,
+        );
+        let nu = HashToCurve::<Sha512, _>::new(
+            ec.clone(),
+            ell2(),
+            b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_NU_",
+            128,
+        );
+        for (msg, x, y, nu_x, nu_y) in [
+            (
+                "",
+                "3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6",
+                "09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21",
+                "1ff2b70ecf862799e11b7ae744e3489aa058ce805dd323a936375a84695e76da",
+                "222e314d04a4d5725e9f2aff9fb2a6b69ef375a1214eb19021ceab2d687f0f9b",
+            ),
+            (
+                "abc",
+                "608040b42285cc0d72cbb3985c6b04c935370c7361f4b7fbdb1ae7f8c1a8ecad",
+                "1a8395b88338f22e435bbd301183e7f20a5f9de643f11882fb237f88268a5531",
+                "5f13cc69c891d86927eb37bd4afc6672360007c63f68a33ab423a3aa040fd2a8",
+                "67732d50f9a26f73111dd1ed5dba225614e538599db58ba30aaea1f5c827fa42",
+            ),
+            (
+                "abcdef0123456789",
+                "6d7fabf47a2dc03fe7d47f7dddd21082c5fb8f86743cd020f3fb147d57161472",
+                "53060a3d140e7fbcda641ed3cf42c88a75411e648a1add71217f70ea8ec561a6",
+                "1dd2fefce934ecfd7aae6ec998de088d7dd03316aa1847198aecf699ba6613f1",
+                "2f8a6c24dd1adde73909cada6a4a137577b0f179d336685c4a955a0a8e1a86fb",
+            ),
+        ] {
+            let hex = |s: &str| f.from(format!("0x{s}").as_str());
+            let want = ec.new_point(hex(x), hex(y));
+            assert!(ro.hash_to_curve(msg.as_bytes()).unwrap() == want, "{msg}");
+            let want = ec.new_point(hex(nu_x), hex(nu_y));
+            assert!(nu.encode_to_curve(msg.as_bytes()).unwrap() == want, "{msg}");
+        }
+    }
+}
@@ -1,14 +1,26 @@
+use std::sync::OnceLock;
+
 use crate::edwards::{Curve, Params};
 use crate::instances::GetCurve;
 
-#[derive(PartialEq, Eq, Copy, Clone)]
-pub struct EdCurveID(&'static Params);
+/// Identifies one of the built-in curves. All curves obtained from the same
+/// identifier share their parameters, so comparing them is cheap.
+#[derive(Copy, Clone)]
+pub struct EdCurveID(&'static Params, &'static OnceLock<Curve>);
+
+impl Eq for EdCurveID {}
+
+impl PartialEq for EdCurveID {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
 
 impl GetCurve for EdCurveID {
     type E = Curve;
     #[inline]
     fn get(&self) -> Self::E {
-        Curve::from(self.0)
+        self.1.get_or_init(|| Curve::from(self.0)).clone()
     }
 }
 
@@ -19,9 +31,12 @@ impl std::fmt::Display for EdCurveID {
 }
 
 /// EDWARDS25519 is the edwards25519 elliptic curve as specified in RFC-7748.
-pub static EDWARDS25519: EdCurveID = EdCurveID(EDWARDS25519_PARAMS);
+pub static EDWARDS25519: EdCurveID = EdCurveID(EDWARDS25519_PARAMS, &EDWARDS25519_CURVE);
 /// EDWARDS448 is the edwards448 elliptic curve as specified in RFC-7748.
-pub static EDWARDS448: EdCurveID = EdCurveID(EDWARDS448_PARAMS);
+pub static EDWARDS448: EdCurveID = EdCurveID(EDWARDS448_PARAMS, &EDWARDS448_CURVE);
+
+static EDWARDS25519_CURVE: OnceLock<Curve> = OnceLock::new();
+static EDWARDS448_CURVE: OnceLock<Curve> = OnceLock::new();
 
 static EDWARDS25519_PARAMS: &Params = &Params {
     name: "edwards25519",
@@ -1,14 +1,26 @@
+use std::sync::OnceLock;
+
 use crate::instances::GetCurve;
 use crate::montgomery::{Curve, Params};
 
-#[derive(PartialEq, Eq, Copy, Clone)]
-pub struct MtCurveID(&'static Params);
+/// Identifies one of the built-in curves. All curves obtained from the same
+/// identifier share their parameters, so comparing them is cheap.
+#[derive(Copy, Clone)]
+pub struct MtCurveID(&'static Params, &'static OnceLock<Curve>);
+
+impl Eq for MtCurveID {}
+
+impl PartialEq for MtCurveID {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
 
 impl GetCurve for MtCurveID {
     type E = Curve;
     #[inline]
     fn get(&self) -> Self::E {
-        Curve::from(self.0)
+        self.1.get_or_init(|| Curve::from(self.0)).clone()
     }
 }
 
@@ -19,9 +31,12 @@ impl std::fmt::Display for MtCurveID {
 }
 
 /// CURVE25519 is the curve25519 elliptic curve as specified in RFC-7748.
-pub static CURVE25519: MtCurveID = MtCurveID(CURVE25519_PARAMS);
+pub static CURVE25519: MtCurveID = MtCurveID(CURVE25519_PARAMS, &CURVE25519_CURVE);
 /// CURVE448 is the curve448 elliptic curve as specified in RFC-7748.
-pub static CURVE448: MtCurveID = MtCurveID(CURVE448_PARAMS);
+pub static CURVE448: MtCurveID = MtCurveID(CURVE448_PARAMS, &CURVE448_CURVE);
+
+static CURVE25519_CURVE: OnceLock<Curve> = OnceLock::new();
+static CURVE448_CURVE: OnceLock<Curve> = OnceLock::new();
 
 static CURVE25519_PARAMS: &Params = &Params {
     name: "curve25519",
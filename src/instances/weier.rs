@@ -1,14 +1,26 @@
+use std::sync::OnceLock;
+
 use crate::instances::GetCurve;
 use crate::weierstrass::{Curve, Params};
 
-#[derive(PartialEq, Eq, Copy, Clone)]
-pub struct WeCurveID(&'static Params);
+/// Identifies one of the built-in curves. All curves obtained from the same
+/// identifier share their parameters, so comparing them is cheap.
+#[derive(Copy, Clone)]
+pub struct WeCurveID(&'static Params, &'static OnceLock<Curve>);
+
+impl Eq for WeCurveID {}
+
+impl PartialEq for WeCurveID {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
 
 impl GetCurve for WeCurveID {
     type E = Curve;
     #[inline]
     fn get(&self) -> Self::E {
-        Curve::from(self.0)
+        self.1.get_or_init(|| Curve::from(self.0)).clone()
     }
 }
 
@@ -19,19 +31,28 @@ impl std::fmt::Display for WeCurveID {
 }
 
 /// P256 is the NIST P-256 elliptic curve.
-pub static P256: WeCurveID = WeCurveID(P256_PARAMS);
+pub static P256: WeCurveID = WeCurveID(P256_PARAMS, &P256_CURVE);
 /// P384 is the NIST P-384 elliptic curve.
-pub static P384: WeCurveID = WeCurveID(P384_PARAMS);
+pub static P384: WeCurveID = WeCurveID(P384_PARAMS, &P384_CURVE);
 /// P521 is the NIST P-521 elliptic curve.
-pub static P521: WeCurveID = WeCurveID(P521_PARAMS);
+pub static P521: WeCurveID = WeCurveID(P521_PARAMS, &P521_CURVE);
 /// SECP256K1 is a 256-bit elliptic curve known as secp256k1.
-pub static SECP256K1: WeCurveID = WeCurveID(SECP256K1_PARAMS);
+pub static SECP256K1: WeCurveID = WeCurveID(SECP256K1_PARAMS, &SECP256K1_CURVE);
 /// SECP256K1_3ISO is a degree-3 curve isogenous to secp256k1.
-pub static SECP256K1_3ISO: WeCurveID = WeCurveID(SECP256K1_3ISO_PARAMS);
+pub static SECP256K1_3ISO: WeCurveID = WeCurveID(SECP256K1_3ISO_PARAMS, &SECP256K1_3ISO_CURVE);
 /// BLS12381_G1 is a pairing-friendly curve known as BLS12381 defined over GF(p).
-pub static BLS12381G1: WeCurveID = WeCurveID(BLS12381G1_PARAMS);
+pub static BLS12381G1: WeCurveID = WeCurveID(BLS12381G1_PARAMS, &BLS12381G1_CURVE);
 /// BLS12381_G1_11ISO is a degree-11 curve isogenous to BLS12381 defined over GF(p).
-pub static BLS12381G1_11ISO: WeCurveID = WeCurveID(BLS12381G1_11ISO_PARAMS);
+pub static BLS12381G1_11ISO: WeCurveID =
+    WeCurveID(BLS12381G1_11ISO_PARAMS, &BLS12381G1_11ISO_CURVE);
+
+static P256_CURVE: OnceLock<Curve> = OnceLock::new();
+static P384_CURVE: OnceLock<Curve> = OnceLock::new();
+static P521_CURVE: OnceLock<Curve> = OnceLock::new();
+static SECP256K1_CURVE: OnceLock<Curve> = OnceLock::new();
+static SECP256K1_3ISO_CURVE: OnceLock<Curve> = OnceLock::new();
+static BLS12381G1_CURVE: OnceLock<Curve> = OnceLock::new();
+static BLS12381G1_11ISO_CURVE: OnceLock<Curve> = OnceLock::new();
 
 static P256_PARAMS: &Params = &Params {
     name: "P256",
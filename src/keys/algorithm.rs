@@ -0,0 +1,136 @@
+//! This is documentation for the `algorithm` module.
+//!
+//! The algorithm module maps the built-in curves to the object identifiers
+//! that name them in the AlgorithmIdentifier of X.509 and PKCS#8.
+
+use crate::ellipticcurve::{Decode, DecodePolicy, EllipticCurve, Encode};
+use crate::field::Field;
+use crate::instances::{
+    EdCurveID, GetCurve, MtCurveID, WeCurveID, CURVE25519, CURVE448, EDWARDS25519, EDWARDS448,
+    P256, P384, P521, SECP256K1,
+};
+use crate::keys::der::{self, Reader, OID};
+use crate::keys::PublicKey;
+use crate::{DecodeError, Error};
+
+// id-ecPublicKey (RFC 5480).
+pub(super) const ID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+// named curves (RFC 5480 and SEC 2).
+const SECP256R1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+const SECP384R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+const SECP521R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x23];
+const SECP256K1_OID: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+// id-X25519, id-X448, id-Ed25519, and id-Ed448 (RFC 8410).
+const ID_X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+const ID_X448: &[u8] = &[0x2b, 0x65, 0x6f];
+const ID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+const ID_ED448: &[u8] = &[0x2b, 0x65, 0x71];
+
+fn named_curves() -> [(WeCurveID, &'static [u8]); 4] {
+    [
+        (P256, SECP256R1),
+        (P384, SECP384R1),
+        (P521, SECP521R1),
+        (SECP256K1, SECP256K1_OID),
+    ]
+}
+
+fn edwards_curves() -> [(EdCurveID, &'static [u8]); 2] {
+    [(EDWARDS25519, ID_ED25519), (EDWARDS448, ID_ED448)]
+}
+
+fn montgomery_curves() -> [(MtCurveID, &'static [u8]); 2] {
+    [(CURVE25519, ID_X25519), (CURVE448, ID_X448)]
+}
+
+/// Algorithm identifies the curve of a key.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Algorithm {
+    Weierstrass(WeCurveID),
+    Edwards(EdCurveID),
+    Montgomery(MtCurveID),
+}
+
+impl Algorithm {
+    /// Returns the object identifier of the algorithm, and the one of the
+    /// named curve for elliptic curve keys. It fails for curves that have
+    /// no object identifier, e.g., BLS12381G1.
+    pub(super) fn oids(&self) -> Result<(&'static [u8], Option<&'static [u8]>), Error> {
+        fn find<I: PartialEq>(id: &I, list: &[(I, &'static [u8])]) -> Result<&'static [u8], Error> {
+            let found = list.iter().find(|(c, _)| c == id);
+            found.map(|(_, oid)| *oid).ok_or(Error::Unsupported)
+        }
+        match self {
+            Algorithm::Weierstrass(id) => Ok((ID_EC_PUBLIC_KEY, Some(find(id, &named_curves())?))),
+            Algorithm::Edwards(id) => Ok((find(id, &edwards_curves())?, None)),
+            Algorithm::Montgomery(id) => Ok((find(id, &montgomery_curves())?, None)),
+        }
+    }
+    /// Encodes the AlgorithmIdentifier.
+    pub(super) fn to_der(self) -> Result<Vec<u8>, Error> {
+        let (oid, params) = self.oids()?;
+        let mut elements = vec![der::tlv(OID, oid)];
+        elements.extend(params.map(|p| der::tlv(OID, p)));
+        Ok(der::sequence(&elements))
+    }
+    /// Decodes the contents of an AlgorithmIdentifier. The parameters must be
+    /// a named curve for elliptic curve keys, and must be absent otherwise.
+    pub(super) fn from_der(buf: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(buf);
+        let oid = r.read(OID)?;
+        let params = if r.is_empty() {
+            None
+        } else {
+            Some(r.read(OID)?)
+        };
+        r.finish()?;
+        Self::from_oids(oid, params)
+    }
+    /// Returns the algorithm with the given object identifier and, for
+    /// elliptic curve keys, the given named curve.
+    pub(super) fn from_oids(oid: &[u8], params: Option<&[u8]>) -> Result<Self, Error> {
+        let alg = match (oid, params) {
+            (ID_EC_PUBLIC_KEY, Some(p)) => named_curves()
+                .into_iter()
+                .find(|(_, oid)| *oid == p)
+                .map(|(id, _)| Algorithm::Weierstrass(id)),
+            (_, Some(_)) => None,
+            (_, None) => {
+                let ed = edwards_curves().into_iter().find(|(_, o)| *o == oid);
+                let mt = montgomery_curves().into_iter().find(|(_, o)| *o == oid);
+                ed.map(|(id, _)| Algorithm::Edwards(id))
+                    .or(mt.map(|(id, _)| Algorithm::Montgomery(id)))
+            }
+        };
+        alg.ok_or(Error::Unsupported)
+    }
+    /// Decodes a public key with the encoding used by X.509, i.e., a SEC1
+    /// point for elliptic curve keys, or the encodings of RFC 8032 and
+    /// RFC 7748 for Edwards and Montgomery curves, respectively.
+    pub(super) fn public_key(&self, buf: &[u8]) -> Result<PublicKey, Error> {
+        match self {
+            Algorithm::Weierstrass(id) => {
+                let policy = DecodePolicy {
+                    reject_identity: true,
+                    ..DecodePolicy::default()
+                };
+                let p = id.get().decode_with(buf, &policy)?;
+                Ok(PublicKey::Weierstrass(*id, p))
+            }
+            Algorithm::Edwards(id) => {
+                let ec = id.get();
+                if buf.len() != ec.get_generator().encode(true).len() {
+                    return Err(DecodeError::WrongLength.into());
+                }
+                Ok(PublicKey::Edwards(*id, ec.decode(buf)?))
+            }
+            Algorithm::Montgomery(id) => {
+                let ec = id.get();
+                if buf.len() != ec.get_field().size_bytes() {
+                    return Err(DecodeError::WrongLength.into());
+                }
+                Ok(PublicKey::Montgomery(*id, ec.decode(buf)?))
+            }
+        }
+    }
+}
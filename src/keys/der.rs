@@ -0,0 +1,215 @@
+//! This is documentation for the `der` module.
+//!
+//! The der module implements the subset of the Distinguished Encoding Rules
+//! of ASN.1 (X.690) that is needed to read and write keys.
+
+use crate::DecodeError;
+
+pub(crate) const INTEGER: u8 = 0x02;
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+pub(crate) const OID: u8 = 0x06;
+pub(crate) const SEQUENCE: u8 = 0x30;
+
+/// Returns the tag of a context-specific element, either constructed, e.g.,
+/// for explicit tagging, or primitive.
+pub(crate) const fn context(n: u8, constructed: bool) -> u8 {
+    0x80 | (constructed as u8) << 5 | n
+}
+
+/// Encodes a tag-length-value triplet.
+pub(crate) fn tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = value.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(value);
+    out
+}
+
+/// Encodes a sequence whose elements are already encoded.
+pub(crate) fn sequence(elements: &[Vec<u8>]) -> Vec<u8> {
+    tlv(SEQUENCE, &elements.concat())
+}
+
+/// Encodes an octet-string as a bit string with no unused bits. The tag is
+/// either BIT_STRING or the one of an implicitly tagged element.
+pub(crate) fn bit_string(tag: u8, value: &[u8]) -> Vec<u8> {
+    tlv(tag, &[&[0u8], value].concat())
+}
+
+/// Encodes a small non-negative integer, e.g., a version number.
+pub(crate) fn small_integer(n: u8) -> Vec<u8> {
+    if n < 0x80 {
+        tlv(INTEGER, &[n])
+    } else {
+        tlv(INTEGER, &[0, n])
+    }
+}
+
+/// Encodes a non-negative integer given by its big-endian magnitude.
+pub(crate) fn unsigned_integer(magnitude: &[u8]) -> Vec<u8> {
+    let skip = magnitude.iter().take_while(|&&b| b == 0).count();
+    let mut value = magnitude[skip..].to_vec();
+    if value.is_empty() || value[0] & 0x80 != 0 {
+        value.insert(0, 0);
+    }
+    tlv(INTEGER, &value)
+}
+
+/// Reader parses a sequence of DER-encoded values.
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Reader(buf)
+    }
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Returns the tag of the next element, if any.
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.0.first().copied()
+    }
+    /// Returns the value of the next element, which must have the given tag.
+    pub(crate) fn read(&mut self, tag: u8) -> Result<&'a [u8], DecodeError> {
+        let (&t, rest) = self.0.split_first().ok_or(DecodeError::Malformed)?;
+        let (&first, mut rest) = rest.split_first().ok_or(DecodeError::Malformed)?;
+        if t != tag {
+            return Err(DecodeError::Malformed);
+        }
+        let len = if first < 0x80 {
+            first as usize
+        } else {
+            // only the definite form with the minimum number of octets is valid.
+            let n = (first & 0x7f) as usize;
+            if n == 0 || n > std::mem::size_of::<usize>() || rest.len() < n || rest[0] == 0 {
+                return Err(DecodeError::Malformed);
+            }
+            let len = rest[..n].iter().fold(0, |acc, &b| (acc << 8) | b as usize);
+            if len < 0x80 {
+                return Err(DecodeError::Malformed);
+            }
+            rest = &rest[n..];
+            len
+        };
+        if rest.len() < len {
+            return Err(DecodeError::Malformed);
+        }
+        let (value, rest) = rest.split_at(len);
+        self.0 = rest;
+        Ok(value)
+    }
+    /// Returns the contents of a bit string, which must have no unused bits.
+    pub(crate) fn read_bit_string(&mut self, tag: u8) -> Result<&'a [u8], DecodeError> {
+        match self.read(tag)?.split_first() {
+            Some((0, value)) => Ok(value),
+            _ => Err(DecodeError::Malformed),
+        }
+    }
+    /// Returns a small non-negative integer, e.g., a version number.
+    pub(crate) fn read_small_integer(&mut self) -> Result<u8, DecodeError> {
+        match self.read(INTEGER)? {
+            [n] if *n < 0x80 => Ok(*n),
+            [0, n] if *n >= 0x80 => Ok(*n),
+            _ => Err(DecodeError::Malformed),
+        }
+    }
+    /// Returns the big-endian magnitude of a non-negative integer, which must
+    /// be encoded with the minimum number of octets.
+    pub(crate) fn read_unsigned_integer(&mut self) -> Result<&'a [u8], DecodeError> {
+        match self.read(INTEGER)? {
+            [0] => Ok(&[]),
+            [0, rest @ ..] if rest[0] & 0x80 != 0 => Ok(rest),
+            value @ [b, ..] if *b != 0 && b & 0x80 == 0 => Ok(value),
+            _ => Err(DecodeError::Malformed),
+        }
+    }
+    /// Checks that all the elements were read.
+    pub(crate) fn finish(self) -> Result<(), DecodeError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::Malformed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{context, small_integer, tlv, unsigned_integer, Reader, OCTET_STRING, SEQUENCE};
+    use crate::DecodeError;
+
+    #[test]
+    fn lengths() {
+        for len in [0, 1, 127, 128, 255, 256, 65535, 65536] {
+            let value = vec![0xaa; len];
+            let der = tlv(OCTET_STRING, &value);
+            let mut r = Reader::new(&der);
+            assert_eq!(r.read(OCTET_STRING).unwrap(), &value[..]);
+            assert!(r.finish().is_ok());
+        }
+        assert_eq!(tlv(OCTET_STRING, &[0; 200])[..3], [0x04, 0x81, 200]);
+        assert_eq!(tlv(OCTET_STRING, &[0; 256])[..4], [0x04, 0x82, 1, 0]);
+        for n in [0, 1, 127, 128, 255] {
+            let der = small_integer(n);
+            assert_eq!(Reader::new(&der).read_small_integer(), Ok(n));
+        }
+        assert_eq!(
+            Reader::new(&[0x02, 0x01, 0x80]).read_small_integer().ok(),
+            None
+        );
+        assert_eq!(
+            Reader::new(&[0x02, 0x02, 0x00, 0x01])
+                .read_small_integer()
+                .ok(),
+            None
+        );
+        let cases: [(&[u8], &[u8], &[u8]); 3] = [
+            (&[], &[0x02, 0x01, 0x00], &[]),
+            (&[0, 0, 0x7f], &[0x02, 0x01, 0x7f], &[0x7f]),
+            (&[0x80, 0], &[0x02, 0x03, 0x00, 0x80, 0x00], &[0x80, 0]),
+        ];
+        for (magnitude, der, value) in cases {
+            assert_eq!(unsigned_integer(magnitude), der);
+            assert_eq!(Reader::new(der).read_unsigned_integer(), Ok(value));
+        }
+        for der in [
+            &[0x02, 0x01, 0x80][..],
+            &[0x02, 0x02, 0x00, 0x01],
+            &[0x02, 0x00],
+        ] {
+            assert_eq!(
+                Reader::new(der).read_unsigned_integer().err(),
+                Some(DecodeError::Malformed)
+            );
+        }
+        assert_eq!((context(0, true), context(1, false)), (0xa0, 0x81));
+    }
+
+    #[test]
+    fn malformed() {
+        let cases: [&[u8]; 7] = [
+            &[],
+            &[0x04],
+            &[0x04, 0x02, 0x00],
+            &[0x04, 0x80],
+            &[0x04, 0x81, 0x05, 0, 0, 0, 0, 0],
+            &[0x04, 0x82, 0x00, 0x81],
+            &[0x30, 0x00],
+        ];
+        for der in cases {
+            let mut r = Reader::new(der);
+            assert_eq!(r.read(OCTET_STRING).err(), Some(DecodeError::Malformed));
+        }
+        let mut r = Reader::new(&[0x30, 0x00, 0x00]);
+        assert!(r.read(SEQUENCE).is_ok());
+        assert_eq!(r.finish().err(), Some(DecodeError::Malformed));
+    }
+}
@@ -0,0 +1,279 @@
+//! This is documentation for the `jwk` module.
+//!
+//! The jwk module implements JSON Web Keys (RFC 7517) of type EC (RFC 7518)
+//! and OKP (RFC 8037), and their thumbprints (RFC 7638).
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
+use crate::field::Field;
+use crate::instances::{
+    GetCurve, CURVE25519, CURVE448, EDWARDS25519, EDWARDS448, P256, P384, P521, SECP256K1,
+};
+use crate::keys::algorithm::Algorithm;
+use crate::keys::{PrivateKey, PublicKey, SecretKey};
+use crate::ops::Deserialize;
+use crate::{DecodeError, Error};
+
+/// Jwk is a JSON Web Key of type EC or OKP. Coordinates and secret keys are
+/// encoded in base64url without padding. With the `serde` feature, it can
+/// be converted to and from JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+    pub y: Option<String>,
+    pub d: Option<String>,
+}
+
+fn curve_names() -> [(Algorithm, &'static str); 8] {
+    [
+        (Algorithm::Weierstrass(P256), "P-256"),
+        (Algorithm::Weierstrass(P384), "P-384"),
+        (Algorithm::Weierstrass(P521), "P-521"),
+        (Algorithm::Weierstrass(SECP256K1), "secp256k1"),
+        (Algorithm::Edwards(EDWARDS25519), "Ed25519"),
+        (Algorithm::Edwards(EDWARDS448), "Ed448"),
+        (Algorithm::Montgomery(CURVE25519), "X25519"),
+        (Algorithm::Montgomery(CURVE448), "X448"),
+    ]
+}
+
+impl Jwk {
+    /// Computes the thumbprint of the key as in RFC 7638, i.e., the SHA-256
+    /// digest of the required members in lexicographic order.
+    pub fn thumbprint(&self) -> Result<Vec<u8>, Error> {
+        // the members must not need escaping to produce the canonical form.
+        let members = [&self.crv, &self.x].into_iter().chain(&self.y);
+        if members
+            .flat_map(|m| m.chars())
+            .any(|c| c == '"' || c == '\\' || c.is_control())
+        {
+            return Err(DecodeError::Malformed.into());
+        }
+        let json = match (self.kty.as_str(), &self.y) {
+            ("EC", Some(y)) => format!(
+                r#"{{"crv":"{}","kty":"EC","x":"{}","y":"{}"}}"#,
+                self.crv, self.x, y
+            ),
+            ("OKP", None) => format!(r#"{{"crv":"{}","kty":"OKP","x":"{}"}}"#, self.crv, self.x),
+            _ => return Err(Error::Unsupported),
+        };
+        Ok(Sha256::digest(json).to_vec())
+    }
+    fn algorithm(&self) -> Result<Algorithm, Error> {
+        let (alg, _) = curve_names()
+            .into_iter()
+            .find(|(_, name)| *name == self.crv)
+            .ok_or(Error::Unsupported)?;
+        let kty = match alg {
+            Algorithm::Weierstrass(_) => "EC",
+            Algorithm::Edwards(_) | Algorithm::Montgomery(_) => "OKP",
+        };
+        if self.kty != kty {
+            return Err(Error::Unsupported);
+        }
+        Ok(alg)
+    }
+}
+
+fn decode(s: &str) -> Result<Vec<u8>, Error> {
+    BASE64URL
+        .decode(s)
+        .map_err(|_| DecodeError::Malformed.into())
+}
+
+impl PublicKey {
+    /// Converts the key into a JWK. It fails for curves that have no name
+    /// registered for JWK, and for points that have no JWK encoding, i.e.,
+    /// the identity, and the points of small order of OKP keys.
+    pub fn to_jwk(&self) -> Result<Jwk, Error> {
+        let invalid = match self {
+            PublicKey::Weierstrass(_, p) => p.is_zero(),
+            PublicKey::Edwards(_, p) => p.is_small_order(),
+            PublicKey::Montgomery(_, p) => p.is_small_order(),
+        };
+        if invalid {
+            return Err(Error::InvalidPoint);
+        }
+        let alg = self.algorithm();
+        let (_, crv) = curve_names()
+            .into_iter()
+            .find(|(a, _)| *a == alg)
+            .ok_or(Error::Unsupported)?;
+        let buf = self.encode(false);
+        let (kty, x, y) = match self {
+            PublicKey::Weierstrass(..) => {
+                let size = (buf.len() - 1) / 2;
+                let (x, y) = buf[1..].split_at(size);
+                ("EC", BASE64URL.encode(x), Some(BASE64URL.encode(y)))
+            }
+            PublicKey::Edwards(..) | PublicKey::Montgomery(..) => {
+                ("OKP", BASE64URL.encode(buf), None)
+            }
+        };
+        Ok(Jwk {
+            kty: kty.to_string(),
+            crv: crv.to_string(),
+            x,
+            y,
+            d: None,
+        })
+    }
+    /// Converts a JWK into a public key. The coordinates of EC keys must
+    /// have the length of the field elements, and be a point on the curve.
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, Error> {
+        let alg = jwk.algorithm()?;
+        let x = decode(&jwk.x)?;
+        match (alg, &jwk.y) {
+            (Algorithm::Weierstrass(id), Some(y)) => {
+                let ec = id.get();
+                let f = ec.get_field();
+                let y = decode(y)?;
+                if x.len() != f.size_bytes() || y.len() != f.size_bytes() {
+                    return Err(DecodeError::WrongLength.into());
+                }
+                let p = ec.try_new_point(f.from_bytes_be(&x)?, f.from_bytes_be(&y)?)?;
+                Ok(PublicKey::Weierstrass(id, p))
+            }
+            (Algorithm::Weierstrass(_), None) => Err(DecodeError::Malformed.into()),
+            (_, Some(_)) => Err(DecodeError::Malformed.into()),
+            (_, None) => alg.public_key(&x),
+        }
+    }
+}
+
+impl PrivateKey {
+    /// Converts the key into a JWK, which includes the public key. It is
+    /// derived from the secret key if absent.
+    pub fn to_jwk(&self) -> Result<Jwk, Error> {
+        self.check()?;
+        let public = match &self.public {
+            Some(p) => p.clone(),
+            None => self.secret.public_key(),
+        };
+        let mut jwk = public.to_jwk()?;
+        jwk.d = Some(BASE64URL.encode(self.secret.to_bytes()));
+        Ok(jwk)
+    }
+    /// Converts a JWK with a secret key into a private key. The secret key
+    /// must have the length of the scalars for EC keys, and the public key
+    /// must correspond to it.
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, Error> {
+        let alg = jwk.algorithm()?;
+        let public = PublicKey::from_jwk(jwk)?;
+        let d = decode(jwk.d.as_ref().ok_or(DecodeError::Malformed)?)?;
+        if let Algorithm::Weierstrass(id) = alg {
+            if d.len() != id.get().get_scalar_field().size_bytes() {
+                return Err(DecodeError::WrongLength.into());
+            }
+        }
+        let key = PrivateKey {
+            secret: SecretKey::from_bytes(alg, &d)?,
+            public: Some(public),
+        };
+        key.check()?;
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Jwk;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::field::Field;
+    use crate::instances::{GetCurve, BLS12381G1, CURVE25519, EDWARDS448, P256};
+    use crate::keys::{PrivateKey, PublicKey};
+    use crate::{DecodeError, Error};
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+    use base64::Engine;
+
+    fn jwk(kty: &str, crv: &str, x: &str, y: Option<&str>, d: Option<&str>) -> Jwk {
+        Jwk {
+            kty: kty.to_string(),
+            crv: crv.to_string(),
+            x: x.to_string(),
+            y: y.map(String::from),
+            d: d.map(String::from),
+        }
+    }
+
+    #[test]
+    fn rfc8037_vectors() {
+        // Appendix A.1 to A.3.
+        let x = "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo";
+        let d = "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A";
+        let private = jwk("OKP", "Ed25519", x, None, Some(d));
+        let key = PrivateKey::from_jwk(&private).unwrap();
+        assert_eq!(key.to_jwk().unwrap(), private);
+        let public = key.secret.public_key().to_jwk().unwrap();
+        assert_eq!(public, jwk("OKP", "Ed25519", x, None, None));
+        let thumbprint = BASE64URL.encode(public.thumbprint().unwrap());
+        assert_eq!(thumbprint, "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k");
+    }
+
+    #[test]
+    fn rfc7517_vectors() {
+        // Appendix A.2.
+        let x = "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4";
+        let y = "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM";
+        let d = "870MB6gfuTJ4HtUnUvYMyJpr5eUZNP4Bk43bVdj3eAE";
+        let private = jwk("EC", "P-256", x, Some(y), Some(d));
+        let key = PrivateKey::from_jwk(&private).unwrap();
+        assert_eq!(key.to_jwk().unwrap(), private);
+        let public = PublicKey::from_jwk(&jwk("EC", "P-256", x, Some(y), None)).unwrap();
+        assert!(key.secret.public_key() == public);
+        assert_eq!(public.to_jwk().unwrap().thumbprint().unwrap().len(), 32);
+    }
+
+    #[test]
+    fn bad_keys() {
+        let x = "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4";
+        let y = "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM";
+        let check = |jwk: Jwk, err: Error| assert_eq!(PublicKey::from_jwk(&jwk).err(), Some(err));
+        check(jwk("OKP", "P-256", x, Some(y), None), Error::Unsupported);
+        check(jwk("EC", "P-257", x, Some(y), None), Error::Unsupported);
+        check(
+            jwk("EC", "P-256", x, None, None),
+            Error::Decode(DecodeError::Malformed),
+        );
+        check(
+            jwk("EC", "P-256", "MKBC+NIc", Some(y), None),
+            Error::Decode(DecodeError::Malformed),
+        );
+        // the coordinates must have full length, and be a point on the curve.
+        check(
+            jwk("EC", "P-256", &BASE64URL.encode([1u8; 31]), Some(y), None),
+            Error::Decode(DecodeError::WrongLength),
+        );
+        check(jwk("EC", "P-256", y, Some(x), None), Error::NotOnCurve);
+        // the public key must match the secret key.
+        let d = BASE64URL.encode([1u8; 32]);
+        let private = jwk("EC", "P-256", x, Some(y), Some(&d));
+        assert_eq!(
+            PrivateKey::from_jwk(&private).err(),
+            Some(Error::InvalidParameters)
+        );
+        let key = PublicKey::Weierstrass(BLS12381G1, BLS12381G1.get().get_generator());
+        assert_eq!(key.to_jwk().err(), Some(Error::Unsupported));
+        // the identity and the points of small order have no JWK.
+        let key = PublicKey::Weierstrass(P256, P256.get().identity());
+        assert_eq!(key.to_jwk().err(), Some(Error::InvalidPoint));
+        let key = PublicKey::Edwards(EDWARDS448, EDWARDS448.get().identity());
+        assert_eq!(key.to_jwk().err(), Some(Error::InvalidPoint));
+        let ec = CURVE25519.get();
+        let f = ec.get_field();
+        let key = PublicKey::Montgomery(CURVE25519, ec.new_point(f.zero(), f.zero()));
+        assert_eq!(key.to_jwk().err(), Some(Error::InvalidPoint));
+        let key = PublicKey::Weierstrass(P256, P256.get().get_generator());
+        let mut jwk = key.to_jwk().unwrap();
+        jwk.x.push('"');
+        assert_eq!(
+            jwk.thumbprint().err(),
+            Some(Error::Decode(DecodeError::Malformed))
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! This is documentation for the `keys` module.
+//!
+//! The keys module converts keys to and from the formats used by other
+//! tools: the SubjectPublicKeyInfo structure of X.509 for public keys, and
+//! the PKCS#8 and SEC1 structures for private keys, in their DER and PEM
+//! encodings, as well as JSON Web Keys and the key formats of OpenSSH.
+//!
+//! ```
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::keys::PublicKey;
+//!
+//!  let key = PublicKey::Weierstrass(P256, P256.get().get_generator());
+//!  let pem = key.to_spki_pem().unwrap();
+//!  assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----"));
+//!  assert!(PublicKey::from_spki_pem(&pem).unwrap() == key);
+//! ```
+
+mod algorithm;
+pub(crate) mod der;
+mod jwk;
+mod openssh;
+mod pem;
+mod pkcs8;
+mod public;
+mod sec1;
+mod secret;
+mod spki;
+
+pub use crate::keys::jwk::Jwk;
+pub use crate::keys::public::PublicKey;
+pub use crate::keys::secret::{PrivateKey, SecretKey};
@@ -0,0 +1,63 @@
+//! This is documentation for the `pem` module.
+//!
+//! The pem module implements the textual encoding of RFC 7468, which wraps
+//! DER structures in base64 between a header and a footer.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use crate::DecodeError;
+
+/// Encodes the DER structure with the given label, using lines of 64
+/// characters as OpenSSL does.
+pub(super) fn encode(label: &str, der: &[u8]) -> String {
+    encode_wrapped(label, der, 64)
+}
+
+/// Encodes the structure with the given label, using lines of the given
+/// width, e.g., 70 characters as OpenSSH does.
+pub(super) fn encode_wrapped(label: &str, der: &[u8], width: usize) -> String {
+    let b64 = BASE64.encode(der);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in b64.as_bytes().chunks(width) {
+        out.push_str(std::str::from_utf8(line).unwrap());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Decodes the first structure with the given label. Text before the header
+/// and after the footer is ignored.
+pub(super) fn decode(label: &str, pem: &str) -> Result<Vec<u8>, DecodeError> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let start = pem.find(&begin).ok_or(DecodeError::Malformed)? + begin.len();
+    let len = pem[start..].find(&end).ok_or(DecodeError::Malformed)?;
+    let b64: String = pem[start..start + len]
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    BASE64.decode(b64).map_err(|_| DecodeError::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+    use crate::DecodeError;
+
+    #[test]
+    fn round_trip() {
+        for len in [0, 1, 47, 48, 49, 100] {
+            let der: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let pem = encode("TEST", &der);
+            assert!(pem.lines().all(|l| l.len() <= 64));
+            assert_eq!(decode("TEST", &pem).unwrap(), der);
+            let text = format!("comment\r\n{}trailer", pem.replace('\n', "\r\n"));
+            assert_eq!(decode("TEST", &text).unwrap(), der);
+            assert_eq!(decode("OTHER", &pem).err(), Some(DecodeError::Malformed));
+        }
+        let pem = "-----BEGIN TEST-----\nAA$A\n-----END TEST-----\n";
+        assert_eq!(decode("TEST", pem).err(), Some(DecodeError::Malformed));
+    }
+}
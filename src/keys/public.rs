@@ -0,0 +1,52 @@
+//! This is documentation for the `public` module.
+//!
+//! The public module defines the public keys that are exchanged with other
+//! tools.
+
+use crate::ellipticcurve::Encode;
+use crate::instances::{EdCurveID, MtCurveID, WeCurveID};
+use crate::keys::algorithm::Algorithm;
+use crate::{edwards, montgomery, weierstrass};
+
+/// PublicKey is a point of one of the built-in curves together with the
+/// identifier of the curve.
+#[derive(Clone)]
+pub enum PublicKey {
+    Weierstrass(WeCurveID, weierstrass::Point),
+    Edwards(EdCurveID, edwards::Point),
+    Montgomery(MtCurveID, montgomery::Point),
+}
+
+impl PublicKey {
+    pub(super) fn algorithm(&self) -> Algorithm {
+        match self {
+            PublicKey::Weierstrass(id, _) => Algorithm::Weierstrass(*id),
+            PublicKey::Edwards(id, _) => Algorithm::Edwards(*id),
+            PublicKey::Montgomery(id, _) => Algorithm::Montgomery(*id),
+        }
+    }
+}
+
+impl Encode for PublicKey {
+    /// Encodes the point as the key formats do, i.e., as a SEC1 point for
+    /// Weierstrass curves, and following RFC 8032 and RFC 7748 for Edwards
+    /// and Montgomery curves, respectively, in which case `compress` has no
+    /// effect.
+    fn encode(&self, compress: bool) -> Vec<u8> {
+        match self {
+            PublicKey::Weierstrass(_, p) => p.encode(compress),
+            PublicKey::Edwards(_, p) => p.encode(true),
+            PublicKey::Montgomery(_, p) => p.encode(true),
+        }
+    }
+}
+
+impl Eq for PublicKey {}
+
+impl PartialEq for PublicKey {
+    /// Compares the keys by their encodings, so that the keys of Montgomery
+    /// curves are equal if their u-coordinates are.
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm() == other.algorithm() && self.encode(true) == other.encode(true)
+    }
+}
@@ -0,0 +1,143 @@
+//! This is documentation for the `secret` module.
+//!
+//! The secret module defines the secret keys, and derives their secret
+//! scalars and public keys.
+
+use num_traits::identities::Zero;
+
+use crate::ellipticcurve::{EllipticCurve, Encode};
+use crate::field::Field;
+use crate::instances::{EdCurveID, GetCurve, MtCurveID, WeCurveID};
+use crate::keys::algorithm::Algorithm;
+use crate::keys::PublicKey;
+use crate::ops::{Deserialize, Serialize};
+use crate::scalarfield::Scalar;
+use crate::{eddsa, DecodeError, Error};
+
+/// SecretKey is the secret of a key pair of one of the built-in curves. The
+/// keys of Weierstrass curves are scalars, whereas the keys of Edwards and
+/// Montgomery curves are the octet-strings of RFC 8032 and RFC 7748,
+/// respectively, from which the secret scalar is derived.
+#[derive(Clone, PartialEq, Eq)]
+pub enum SecretKey {
+    Weierstrass(WeCurveID, Scalar),
+    Edwards(EdCurveID, Vec<u8>),
+    Montgomery(MtCurveID, Vec<u8>),
+}
+
+impl SecretKey {
+    /// Returns the scalar that multiplies the generator to obtain the public
+    /// key.
+    pub fn scalar(&self) -> Scalar {
+        match self {
+            SecretKey::Weierstrass(_, k) => k.clone(),
+            SecretKey::Edwards(id, seed) => eddsa::expand_seed(*id, seed).0,
+            SecretKey::Montgomery(id, k) => {
+                let mut k = k.clone();
+                clamp(&mut k);
+                id.get().get_scalar_field().from_bytes_wide_le(&k)
+            }
+        }
+    }
+    /// Derives the public key.
+    pub fn public_key(&self) -> PublicKey {
+        let k = self.scalar();
+        match self {
+            SecretKey::Weierstrass(id, _) => {
+                PublicKey::Weierstrass(*id, id.get().get_generator() * k)
+            }
+            SecretKey::Edwards(id, _) => PublicKey::Edwards(*id, id.get().get_generator() * k),
+            SecretKey::Montgomery(id, _) => {
+                PublicKey::Montgomery(*id, id.get().get_generator() * k)
+            }
+        }
+    }
+    pub(super) fn algorithm(&self) -> Algorithm {
+        match self {
+            SecretKey::Weierstrass(id, _) => Algorithm::Weierstrass(*id),
+            SecretKey::Edwards(id, _) => Algorithm::Edwards(*id),
+            SecretKey::Montgomery(id, _) => Algorithm::Montgomery(*id),
+        }
+    }
+    /// Encodes the key as the private key formats do, i.e., a big-endian
+    /// scalar for Weierstrass curves, and the octet-string itself otherwise.
+    pub(super) fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            SecretKey::Weierstrass(_, k) => k.to_bytes_be(),
+            SecretKey::Edwards(_, k) | SecretKey::Montgomery(_, k) => k.clone(),
+        }
+    }
+    /// Decodes a key of the given algorithm. Scalars must be in the range
+    /// [1, q-1], although leading zeros can be omitted, as some encoders do.
+    pub(super) fn from_bytes(alg: Algorithm, buf: &[u8]) -> Result<Self, Error> {
+        match alg {
+            Algorithm::Weierstrass(id) => {
+                let fq = id.get().get_scalar_field();
+                let size = fq.size_bytes();
+                if buf.len() > size {
+                    return Err(DecodeError::WrongLength.into());
+                }
+                let padded = [vec![0; size - buf.len()], buf.to_vec()].concat();
+                let k = fq.from_bytes_be(&padded)?;
+                if k.is_zero() {
+                    return Err(DecodeError::Malformed.into());
+                }
+                Ok(SecretKey::Weierstrass(id, k))
+            }
+            Algorithm::Edwards(id) => {
+                if buf.len() != id.get().get_generator().encode(true).len() {
+                    return Err(DecodeError::WrongLength.into());
+                }
+                Ok(SecretKey::Edwards(id, buf.to_vec()))
+            }
+            Algorithm::Montgomery(id) => {
+                if buf.len() != id.get().get_field().size_bytes() {
+                    return Err(DecodeError::WrongLength.into());
+                }
+                Ok(SecretKey::Montgomery(id, buf.to_vec()))
+            }
+        }
+    }
+}
+
+/// PrivateKey is a secret key together with its public key, which some
+/// formats carry optionally.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PrivateKey {
+    pub secret: SecretKey,
+    pub public: Option<PublicKey>,
+}
+
+impl From<SecretKey> for PrivateKey {
+    fn from(secret: SecretKey) -> Self {
+        PrivateKey {
+            secret,
+            public: None,
+        }
+    }
+}
+
+impl PrivateKey {
+    /// Checks that the public key, if any, corresponds to the secret key.
+    pub(super) fn check(&self) -> Result<(), Error> {
+        match &self.public {
+            Some(p) if p.algorithm() != self.secret.algorithm() => Err(Error::CurveMismatch),
+            Some(p) if *p != self.secret.public_key() => Err(Error::InvalidParameters),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Clears the bits of the cofactor and sets the most significant bit of a
+/// little-endian scalar as in RFC 7748. The rules are chosen by length: 32
+/// bytes for X25519, and 56 bytes for X448.
+fn clamp(k: &mut [u8]) {
+    if k.len() == 32 {
+        k[0] &= 248;
+        k[31] &= 127;
+        k[31] |= 64;
+    } else {
+        k[0] &= 252;
+        k[55] |= 128;
+    }
+}
@@ -0,0 +1,199 @@
+//! This is documentation for the `spki` module.
+//!
+//! The spki module implements the SubjectPublicKeyInfo structure of X.509
+//! (RFC 5280), which is the format of public keys used by OpenSSL.
+
+use crate::ellipticcurve::Encode;
+use crate::keys::algorithm::Algorithm;
+use crate::keys::der::{self, Reader, BIT_STRING, SEQUENCE};
+use crate::keys::{pem, PublicKey};
+use crate::Error;
+
+const PEM_LABEL: &str = "PUBLIC KEY";
+
+impl PublicKey {
+    /// Encodes the key as a DER SubjectPublicKeyInfo. Points of Weierstrass
+    /// curves are not compressed. It fails for curves that have no object
+    /// identifier.
+    pub fn to_spki_der(&self) -> Result<Vec<u8>, Error> {
+        let alg = self.algorithm().to_der()?;
+        Ok(der::sequence(&[
+            alg,
+            der::bit_string(BIT_STRING, &self.encode(false)),
+        ]))
+    }
+    /// Decodes a DER SubjectPublicKeyInfo. Points of Weierstrass curves can
+    /// be either compressed or uncompressed, but not the point at infinity.
+    pub fn from_spki_der(buf: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(buf);
+        let mut spki = Reader::new(r.read(SEQUENCE)?);
+        r.finish()?;
+        let alg = spki.read(SEQUENCE)?;
+        let key = spki.read_bit_string(BIT_STRING)?;
+        spki.finish()?;
+        Algorithm::from_der(alg)?.public_key(key)
+    }
+    /// Encodes the key as a SubjectPublicKeyInfo in PEM format.
+    pub fn to_spki_pem(&self) -> Result<String, Error> {
+        Ok(pem::encode(PEM_LABEL, &self.to_spki_der()?))
+    }
+    /// Decodes a SubjectPublicKeyInfo in PEM format.
+    pub fn from_spki_pem(pem: &str) -> Result<Self, Error> {
+        Self::from_spki_der(&pem::decode(PEM_LABEL, pem)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
+    use crate::field::Field;
+    use crate::instances::{
+        GetCurve, BLS12381G1, CURVE25519, CURVE448, EDWARDS25519, EDWARDS448, P256,
+    };
+    use crate::keys::PublicKey;
+    use crate::{DecodeError, Error};
+
+    // generated with `openssl genpkey` and `openssl pkey -pubout`.
+    const P256_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE1GtEB9E4F4Q6xw6+p6LNV/eDYRNU
+x/xg5EMIZzJjyITTvT56GKeAxqi0bZgoAtpvgsc+7JfEpQRL9EJlXaaChw==
+-----END PUBLIC KEY-----
+";
+    const P256_COMPRESSED_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MDkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDIgAD1GtEB9E4F4Q6xw6+p6LNV/eDYRNU
+x/xg5EMIZzJjyIQ=
+-----END PUBLIC KEY-----
+";
+    const P384_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MHYwEAYHKoZIzj0CAQYFK4EEACIDYgAEB3vyjtfBvhe8VpmrC9wgTp8CqdkhRRNQ
+g3mSHlmyDaJyUd6EL/2/fasx6PVtdbPHVznnjj3D2TaV9aKt4VZePE24Y1gsWWH/
+C9HsZHfCxED2i84V5Y/A+Y5nGnoBtr9E
+-----END PUBLIC KEY-----
+";
+    const P521_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MIGbMBAGByqGSM49AgEGBSuBBAAjA4GGAAQBZhuXz6kkMNiTEVDy9Ks0QRYk6isc
+V5V6exbydc3pxgIG5sV1P4wi0+hL5Dogv5Kt0IAuD8uUNYjXO23LAjvQv7kAahOl
+Rtoj5RNoItvbLMpyQW/S+uJ0ZSC/U+vH6GBb7lkZpLjb6yUKbSfgri0Bh+k2P00V
+etJ40uzGeA8xHOEJjr0=
+-----END PUBLIC KEY-----
+";
+    const SECP256K1_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MFYwEAYHKoZIzj0CAQYFK4EEAAoDQgAEQlLWEbu52vFsjiSSsq+dVY7lgejaFB1I
+76pXKEaKCTZ417NrzroAm0UZjIaMD9Hme4Frs8mnQbkGUoTZK3jmhA==
+-----END PUBLIC KEY-----
+";
+    const X25519_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VuAyEAeZyAfo45H9PIDaQVykRuBSzCviXUP+Ml6sPvcDCy8Uc=
+-----END PUBLIC KEY-----
+";
+    const X448_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MEIwBQYDK2VvAzkAGL1Ijr3sRX8/COtsiq9V7k18D/74Pa7UT4LM2ZhCNWaO5/Gq
+NBCeX0w/clvPXMkxrqEUCaVrB0g=
+-----END PUBLIC KEY-----
+";
+    const ED25519_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEABAyB4R5b2t354MNKLlL3XejM1SZMuE9Gd/PEA7MFnCU=
+-----END PUBLIC KEY-----
+";
+    const ED448_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MEMwBQYDK2VxAzoAemK1f7q0qLhmpUtZxxssSt0WJkTU7i6jr53oVa3McuQwvH8P
+wbWapNT+tRMUQ4LBlunJlBLix5oA
+-----END PUBLIC KEY-----
+";
+
+    #[test]
+    fn openssl_keys() {
+        let check = |pem: &str, curve: &str| {
+            let key = PublicKey::from_spki_pem(pem).unwrap();
+            let name = match &key {
+                PublicKey::Weierstrass(id, _) => id.to_string(),
+                PublicKey::Edwards(id, _) => id.to_string(),
+                PublicKey::Montgomery(id, _) => id.to_string(),
+            };
+            assert_eq!(name, curve);
+            assert_eq!(key.to_spki_pem().unwrap(), pem);
+            key
+        };
+        let key = check(P256_PEM, "P256");
+        check(P384_PEM, "P384");
+        check(P521_PEM, "P521");
+        check(SECP256K1_PEM, "secp256k1");
+        check(X25519_PEM, "curve25519");
+        check(X448_PEM, "curve448");
+        check(ED25519_PEM, "edwards25519");
+        check(ED448_PEM, "edwards448");
+
+        let compressed = PublicKey::from_spki_pem(P256_COMPRESSED_PEM).unwrap();
+        assert!(compressed == key);
+        assert_eq!(key.encode(true)[1..], key.encode(false)[1..33]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let p = P256.get().get_generator();
+        let key = PublicKey::Weierstrass(P256, p);
+        let der = key.to_spki_der().unwrap();
+        assert!(PublicKey::from_spki_der(&der).unwrap() == key);
+        for id in [EDWARDS25519, EDWARDS448] {
+            let key = PublicKey::Edwards(id, id.get().get_generator());
+            let der = key.to_spki_der().unwrap();
+            assert!(PublicKey::from_spki_der(&der).unwrap() == key);
+        }
+        for id in [CURVE25519, CURVE448] {
+            let key = PublicKey::Montgomery(id, id.get().get_generator());
+            let der = key.to_spki_der().unwrap();
+            assert!(PublicKey::from_spki_der(&der).unwrap() == key);
+        }
+        let key = PublicKey::Weierstrass(BLS12381G1, BLS12381G1.get().get_generator());
+        assert_eq!(key.to_spki_der().err(), Some(Error::Unsupported));
+    }
+
+    #[test]
+    fn non_canonical_montgomery_keys() {
+        // RFC 7748 masks and reduces u-coordinates, so any string is a
+        // u-coordinate, e.g., a string of 0xff bytes.
+        for id in [CURVE25519, CURVE448] {
+            let ec = id.get();
+            let size = ec.get_field().size_bytes();
+            let mut der = PublicKey::Montgomery(id, ec.get_generator())
+                .to_spki_der()
+                .unwrap();
+            let n = der.len();
+            der[n - size..].fill(0xff);
+            let key = PublicKey::from_spki_der(&der).unwrap();
+            assert!(key == PublicKey::Montgomery(id, ec.decode(&vec![0xff; size]).unwrap()));
+        }
+    }
+
+    #[test]
+    fn bad_keys() {
+        let der = PublicKey::from_spki_pem(ED25519_PEM)
+            .unwrap()
+            .to_spki_der()
+            .unwrap();
+        let malformed = Some(Error::Decode(DecodeError::Malformed));
+        // trailing data, and truncated input.
+        let mut long = der.clone();
+        long.push(0);
+        assert_eq!(PublicKey::from_spki_der(&long).err(), malformed);
+        assert_eq!(PublicKey::from_spki_der(&der[..40]).err(), malformed);
+        // unknown algorithm.
+        let mut unknown = der.clone();
+        unknown[8] = 0x72;
+        assert_eq!(
+            PublicKey::from_spki_der(&unknown).err(),
+            Some(Error::Unsupported)
+        );
+        // the point at infinity is not a valid key.
+        let ec = P256.get();
+        let key = PublicKey::Weierstrass(P256, ec.identity());
+        let der = key.to_spki_der().unwrap();
+        assert_eq!(
+            PublicKey::from_spki_der(&der).err(),
+            Some(Error::Decode(DecodeError::InvalidTag))
+        );
+        // wrong label.
+        let pem = P256_PEM.replace("PUBLIC KEY", "PRIVATE KEY");
+        assert_eq!(PublicKey::from_spki_pem(&pem).err(), malformed);
+    }
+}
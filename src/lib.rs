@@ -33,20 +33,41 @@ extern crate doc_comment;
 #[macro_use]
 extern crate impl_ops;
 
+mod batch;
+mod error;
 mod macros;
 
+pub use crate::error::{DecodeError, Error};
+
 pub mod field;
 pub mod ops;
 pub mod primefield;
 pub mod quadraticfield;
+pub mod scalarfield;
 
+pub mod bip32;
+pub mod bip340;
+pub mod ecdh;
+pub mod ecdsa;
+pub mod ecvrf;
+pub mod eddsa;
 pub mod ellipticcurve;
+pub mod expander;
+pub mod hashtocurve;
+pub mod oprf;
 
 pub mod edwards;
 pub mod montgomery;
 pub mod weierstrass;
 
+pub mod decaf448;
+pub mod ristretto255;
+
 pub mod instances;
+pub mod keys;
+
+#[cfg(feature = "serde")]
+pub mod serialization;
 
 #[cfg(test)]
 mod tests;
@@ -13,3 +13,217 @@ macro_rules! do_if_eq {
         }
     };
 }
+
+/// Implements the element type of a prime-order group whose elements are
+/// represented by points of an Edwards curve, as ristretto255 and decaf448.
+/// The group must provide `element`, which wraps a point, and `encode_point`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_quotient_element {
+    (
+        group: $group:ident,
+        name: $name:literal,
+        curve: $curve:literal,
+        points: $points:literal,
+        size: $size:literal,
+        is_zero: |$z:ident| $is_zero:expr,
+        eq: |$x1:ident, $y1:ident, $x2:ident, $y2:ident| $eq:expr,
+    ) => {
+        #[doc = concat!("Element is an element of ", $name, ". It is represented by a point of")]
+        #[doc = concat!($curve, ", which is one of the ", $points, " points that encode to the")]
+        #[doc = "same octet-string."]
+        #[derive(Clone)]
+        pub struct Element {
+            pub(super) g: $group,
+            pub(super) p: $crate::edwards::Point,
+        }
+
+        impl $crate::ops::ScMulRef<$crate::scalarfield::Scalar> for Element {}
+        impl $crate::ellipticcurve::EcPoint<$crate::scalarfield::Scalar> for Element {
+            fn is_zero(&self) -> bool {
+                let $z = &self.p;
+                $is_zero
+            }
+            fn try_add(&self, q: &Self) -> Result<Self, $crate::Error> {
+                if self.g == q.g {
+                    Ok(self.g.element(&self.p + &q.p))
+                } else {
+                    Err($crate::Error::CurveMismatch)
+                }
+            }
+            fn try_mul(&self, k: &$crate::scalarfield::Scalar) -> Result<Self, $crate::Error> {
+                self.p.try_mul(k).map(|p| self.g.element(p))
+            }
+            fn mul_int(&self, k: &num_bigint::BigInt) -> Self {
+                self.g.element(self.p.mul_int(k))
+            }
+            /// Returns true, since the group has prime order.
+            fn is_torsion_free(&self) -> bool {
+                true
+            }
+            fn is_small_order(&self) -> bool {
+                self.is_zero()
+            }
+            fn torsion_component(&self) -> Self {
+                $crate::ellipticcurve::EllipticCurve::identity(&self.g)
+            }
+        }
+
+        impl $crate::ellipticcurve::Encode for Element {
+            #[doc = "Encodes the element following RFC 9496. Every element has a unique"]
+            #[doc = concat!("encoding of ", $size, " bytes, so `compress` has no effect.")]
+            fn encode(&self, _: bool) -> Vec<u8> {
+                self.g.encode_point(&self.p)
+            }
+        }
+
+        impl Eq for Element {}
+
+        impl PartialEq for Element {
+            fn eq(&self, other: &Self) -> bool {
+                let ($x1, $y1) = (&self.p.c.x, &self.p.c.y);
+                let ($x2, $y2) = (&other.p.c.x, &other.p.c.y);
+                self.g == other.g && $eq
+            }
+        }
+
+        impl_op_ex!(+|a: &Element, b: &Element| -> Element {
+            $crate::do_if_eq!(a.g == b.g, a.g.element(&a.p + &b.p), ERR_ADD_OP)
+        });
+        impl_op_ex!(-|a: &Element, b: &Element| -> Element { a + (-b) });
+        impl_op_ex!(-|a: &Element| -> Element { a.g.element(-&a.p) });
+        impl_op_ex!(*|a: &Element, b: &$crate::scalarfield::Scalar| -> Element {
+            a.g.element(&a.p * b)
+        });
+
+        const ERR_ADD_OP: &str = "elements of different groups";
+
+        impl<'b> std::ops::Mul<&'b Element> for &$crate::scalarfield::Scalar {
+            type Output = Element;
+            #[inline]
+            fn mul(self, other: &'b Element) -> Self::Output {
+                other * self
+            }
+        }
+        impl<'b> std::ops::Mul<&'b Element> for $crate::scalarfield::Scalar {
+            type Output = Element;
+            #[inline]
+            fn mul(self, other: &'b Element) -> Self::Output {
+                other * &self
+            }
+        }
+        impl std::ops::Mul<Element> for $crate::scalarfield::Scalar {
+            type Output = Element;
+            #[inline]
+            fn mul(self, other: Element) -> Self::Output {
+                other * &self
+            }
+        }
+
+        impl std::fmt::Display for Element {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                for b in $crate::ellipticcurve::Encode::encode(self, true) {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Implements the traits shared by the prime-order groups whose elements are
+/// represented by points of an Edwards curve, as ristretto255 and decaf448.
+/// The representatives of elements have orders that divide `order` times r.
+/// The group must be `Group(Arc<Inner>)`, where `Inner` holds the curve `ec`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_quotient_group {
+    (
+        name: $name:literal,
+        curve: $curve:literal,
+        order: $order:literal,
+        $(#[$gen_doc:meta])*
+        generator: |$ec:ident| $gen:expr,
+    ) => {
+        impl Default for Group {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl PartialEq for Group {
+            fn eq(&self, other: &Self) -> bool {
+                std::sync::Arc::ptr_eq(&self.0, &other.0) || self.0.ec == other.0.ec
+            }
+        }
+
+        impl Eq for Group {}
+
+        impl $crate::ellipticcurve::EllipticCurve for Group {
+            type F = $crate::primefield::Fp;
+            type Scalar = $crate::scalarfield::Scalar;
+            type ScalarField = $crate::scalarfield::Zq;
+            type Point = Element;
+            #[doc = "Creates an element from the affine coordinates of a point of"]
+            #[doc = concat!($curve, ", or fails if the point does not represent an element.")]
+            fn try_new_point(
+                &self,
+                x: $crate::primefield::FpElt,
+                y: $crate::primefield::FpElt,
+            ) -> Result<Self::Point, $crate::Error> {
+                let e = self.element(self.0.ec.try_new_point(x, y)?);
+                if self.is_on_curve(&e) {
+                    Ok(e)
+                } else {
+                    Err($crate::Error::NotOnCurve)
+                }
+            }
+            fn new_scalar(&self, k: num_bigint::BigInt) -> Self::Scalar {
+                self.0.ec.new_scalar(k)
+            }
+            fn identity(&self) -> Self::Point {
+                self.element(self.0.ec.identity())
+            }
+            #[doc = concat!("Determines whether the point of ", $curve, " that represents the")]
+            #[doc = concat!("element is valid, i.e., whether its order divides ", $order, "r.")]
+            fn is_on_curve(&self, p: &Self::Point) -> bool {
+                use $crate::ellipticcurve::EcPoint;
+                let n = num_bigint::BigInt::from(self.get_order()) * $order;
+                self.0.ec.is_on_curve(&p.p) && p.p.mul_int(&n).is_zero()
+            }
+            fn get_order(&self) -> num_bigint::BigUint {
+                self.0.ec.get_order()
+            }
+            fn get_cofactor(&self) -> num_bigint::BigInt {
+                num_bigint::BigInt::from(1)
+            }
+            fn get_field(&self) -> Self::F {
+                self.0.ec.get_field()
+            }
+            fn get_scalar_field(&self) -> Self::ScalarField {
+                self.0.ec.get_scalar_field()
+            }
+            $(#[$gen_doc])*
+            fn get_generator(&self) -> Self::Point {
+                let $ec = &self.0.ec;
+                self.element($gen)
+            }
+        }
+
+        impl std::fmt::Display for Group {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, $name)
+            }
+        }
+
+        /// Determines whether a field element is negative, i.e., it is odd.
+        fn is_negative(x: &$crate::primefield::FpElt) -> bool {
+            $crate::field::Sgn0::sgn0(x) == -1
+        }
+
+        /// Returns the non-negative one of x and -x.
+        fn abs(x: &$crate::primefield::FpElt) -> $crate::primefield::FpElt {
+            $crate::field::CMov::cmov(x, &-x, is_negative(x))
+        }
+    };
+}
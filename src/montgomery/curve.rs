@@ -7,59 +7,88 @@ use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
 
 use num_traits::identities::Zero;
 
-use std::io::{Error, ErrorKind};
 use std::str::FromStr;
+use std::sync::Arc;
 
 use crate::do_if_eq;
-use crate::ellipticcurve::{Decode, EllipticCurve};
+use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve};
 use crate::field::{Field, Sgn0, Sqrt};
 use crate::montgomery::point::{Point, ProyCoordinates};
-use crate::montgomery::scalar::Scalar;
-use crate::ops::FromFactory;
+use crate::ops::{Deserialize, FromFactory};
 use crate::primefield::{Fp, FpElt};
+use crate::scalarfield::{Scalar, Zq};
+use crate::{DecodeError, Error};
 
 /// This is an elliptic curve defined in Montgomery from and defined by the equation:
 /// by^2=x^3+ax^2+x.
 ///
-#[derive(Clone, PartialEq)]
-pub struct Curve {
-    f: Fp,
+/// Cloning a curve is cheap, since clones share the same curve parameters.
+#[derive(Clone)]
+pub struct Curve(pub(super) Arc<Inner>);
+
+#[derive(PartialEq)]
+pub(super) struct Inner {
+    pub(super) f: Fp,
     pub(super) a: FpElt,
     pub(super) b: FpElt,
     pub(super) s: FpElt,
-    pub(super) r: BigUint,
+    pub(super) q: Zq,
     pub(super) gx: FpElt,
     pub(super) gy: FpElt,
     pub(super) h: BigUint,
 }
 
 impl Curve {
+    /// Creates a point and validates it lies on the curve.
+    /// Use it for points coming from outside of the group law, e.g. outputs of rational maps.
     pub(crate) fn new_proy_point(&self, c: ProyCoordinates) -> Point {
+        let pt = self.new_proy_point_unchecked(c);
+        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+    }
+    /// Creates a point without checking the curve equation. The check is
+    /// only performed on debug builds.
+    pub(super) fn new_proy_point_unchecked(&self, c: ProyCoordinates) -> Point {
         let e = self.clone();
         let pt = Point { e, c };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        debug_assert!(self.is_on_curve(&pt), "{}", ERR_ECC_NEW);
+        pt
+    }
+}
+
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
     }
 }
 
 impl EllipticCurve for Curve {
     type F = Fp;
     type Scalar = Scalar;
+    type ScalarField = Zq;
     type Point = Point;
-    fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point {
+    fn try_new_point(
+        &self,
+        x: <Self::F as Field>::Elt,
+        y: <Self::F as Field>::Elt,
+    ) -> Result<Self::Point, Error> {
         let e = self.clone();
         let f = e.get_field();
         let pt = Point {
             c: ProyCoordinates { x, y, z: f.one() },
             e,
         };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        if self.is_on_curve(&pt) {
+            Ok(pt)
+        } else {
+            Err(Error::NotOnCurve)
+        }
     }
     fn new_scalar(&self, k: BigInt) -> Self::Scalar {
-        Scalar::new(k, &self.r)
+        self.0.q.elt(k)
     }
     fn identity(&self) -> Self::Point {
-        let f = &self.f;
-        self.new_proy_point(ProyCoordinates {
+        let f = &self.0.f;
+        self.new_proy_point_unchecked(ProyCoordinates {
             x: f.zero(),
             y: f.one(),
             z: f.zero(),
@@ -67,97 +96,141 @@ impl EllipticCurve for Curve {
     }
     fn is_on_curve(&self, p: &Self::Point) -> bool {
         let p = &p.c;
-        let l = &self.b * &(&p.y ^ 2u32) * &p.z;
-        let r = &p.x * &((&p.x ^ 2u32) + &self.a * &p.x * &p.z + &(&p.z ^ 2u32));
+        let l = &self.0.b * &(&p.y ^ 2u32) * &p.z;
+        let r = &p.x * &((&p.x ^ 2u32) + &self.0.a * &p.x * &p.z + &(&p.z ^ 2u32));
         let e = l - r;
         e.is_zero()
     }
     fn get_order(&self) -> BigUint {
-        self.r.clone()
+        self.0.q.get_modulus().to_biguint().unwrap()
     }
     fn get_cofactor(&self) -> BigInt {
-        self.h.to_bigint().unwrap()
+        self.0.h.to_bigint().unwrap()
     }
     fn get_field(&self) -> Self::F {
-        self.f.clone()
+        self.0.f.clone()
+    }
+    fn get_scalar_field(&self) -> Self::ScalarField {
+        self.0.q.clone()
     }
     fn get_generator(&self) -> Self::Point {
-        self.new_proy_point(ProyCoordinates {
-            x: self.gx.clone(),
-            y: self.gy.clone(),
-            z: self.f.one(),
+        self.new_proy_point_unchecked(ProyCoordinates {
+            x: self.0.gx.clone(),
+            y: self.0.gy.clone(),
+            z: self.0.f.one(),
         })
     }
 }
 
 impl Decode for Curve {
     type Deser = <Curve as EllipticCurve>::Point;
-    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, std::io::Error> {
-        let size = self.f.size_bytes();
-        let blen = buf.len();
-        if !(blen == 1 || blen == (size + 1) || blen == (2 * size + 1)) {
-            return Err(Error::new(ErrorKind::Other, "Wrong input buffer size."));
+    /// Decodes either a u-coordinate following RFC 7748, or the affine
+    /// coordinates (u, v), which are distinguished by their length. The
+    /// u-coordinate is lifted to the point with even v, whereas affine
+    /// coordinates must be canonical.
+    fn decode_with(&self, buf: &[u8], policy: &DecodePolicy) -> Result<Self::Deser, DecodeError> {
+        let size = self.0.f.size_bytes();
+        let p = if buf.len() == size {
+            let u = self.decode_u(buf)?;
+            self.lift_x(&u, false)?
+        } else if buf.len() == 2 * size {
+            let u = self.0.f.from_bytes_le(&buf[..size])?;
+            let v = self.0.f.from_bytes_le(&buf[size..])?;
+            self.try_new_point(u, v)
+                .map_err(|_| DecodeError::NotOnCurve)?
+        } else {
+            return Err(DecodeError::WrongLength);
+        };
+        if policy.subgroup_check && !p.is_torsion_free() {
+            return Err(DecodeError::NotInSubgroup);
         }
-        let tag = buf[0];
-        // check x coordinate is in the valid range, Sign::Plus => > 0
-        let x_val = BigInt::from_bytes_be(Sign::Plus, &buf[1..size + 1]);
-        let p = self.f.get_modulus();
-        if x_val >= p {
-            return Err(Error::new(ErrorKind::Other, "Invalid x coordinate"));
+        Ok(p)
+    }
+}
+
+impl Curve {
+    /// Decodes a u-coordinate following RFC 7748, i.e., a little-endian
+    /// integer whose unused most significant bits are masked, and whose value
+    /// is reduced modulo p. The u-coordinate may not correspond to a point on
+    /// the curve, e.g., it can be a point on the quadratic twist.
+    // based on https://tools.ietf.org/html/rfc7748#section-5
+    pub fn decode_u(&self, buf: &[u8]) -> Result<FpElt, DecodeError> {
+        let f = &self.0.f;
+        if buf.len() != f.size_bytes() {
+            return Err(DecodeError::WrongLength);
+        }
+        let bits = f.get_modulus().bits() as usize;
+        let mut u = buf.to_vec();
+        u[buf.len() - 1] &= 0xff >> (8 * buf.len() - bits);
+        Ok(f.elt(BigInt::from_bytes_le(Sign::Plus, &u)))
+    }
+    /// Returns the point with the given x-coordinate, and whose y-coordinate
+    /// is odd if `odd_y` is set, or even otherwise.
+    pub fn lift_x(&self, x: &FpElt, odd_y: bool) -> Result<Point, DecodeError> {
+        let f = &self.0.f;
+        let one = f.one();
+        let x_a = x + &self.0.a;
+        let xx_ax = &x_a * x;
+        let xx_ax_1 = &xx_ax + &one;
+        let byy = &xx_ax_1 * x;
+        let b_inv = &one / &self.0.b;
+        let yy = &byy * b_inv;
+        if !yy.is_square() {
+            return Err(DecodeError::NotOnCurve);
         }
-        match tag {
+        let y_sqrt = yy.sqrt();
+        let is_odd = y_sqrt.sgn0() == -1;
+        let y = if is_odd == odd_y { y_sqrt } else { -y_sqrt };
+        self.try_new_point(x.clone(), y)
+            .map_err(|_| DecodeError::NotOnCurve)
+    }
+    /// Decodes a point encoded as in SEC1, i.e., either the point at infinity
+    /// (0x00), an uncompressed point (0x04), a compressed point (0x02 or
+    /// 0x03), or a hybrid point (0x06 or 0x07), where coordinates are encoded
+    /// in big-endian order. The policy determines which of them are accepted.
+    pub fn decode_sec1(&self, buf: &[u8], policy: &DecodePolicy) -> Result<Point, DecodeError> {
+        let f = &self.0.f;
+        let size = f.size_bytes();
+        let (&tag, data) = buf.split_first().ok_or(DecodeError::WrongLength)?;
+        let p = match tag {
+            0x00 if policy.reject_identity => Err(DecodeError::InvalidTag),
+            0x02 | 0x03 if policy.reject_compressed => Err(DecodeError::InvalidTag),
             0x00 => {
                 // return point of infinity
-                if buf.len() != 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Point at infinity should just be a single zero byte",
-                    ));
+                if !data.is_empty() {
+                    return Err(DecodeError::WrongLength);
                 }
                 Ok(self.identity())
             }
-            0x04 => {
-                if buf.len() != 2 * size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
+            0x04 | 0x06 | 0x07 => {
+                if tag != 0x04 && !policy.accept_hybrid {
+                    return Err(DecodeError::InvalidTag);
                 }
-                let x = self.f.elt(x_val);
-                let y_val = BigInt::from_bytes_be(Sign::Plus, &buf[size + 1..]);
-                if y_val >= p {
-                    return Err(Error::new(ErrorKind::Other, "Invalid y coordinate"));
+                if data.len() != 2 * size {
+                    return Err(DecodeError::WrongLength);
                 }
-                let y = self.f.elt(y_val);
-                Ok(self.new_point(x, y))
+                let x = f.from_bytes_be(&data[..size])?;
+                let y = f.from_bytes_be(&data[size..])?;
+                // the tag of a hybrid point carries the parity of y.
+                if tag != 0x04 && (tag == 0x07) != (y.sgn0() == -1) {
+                    return Err(DecodeError::InvalidTag);
+                }
+                self.try_new_point(x, y)
+                    .map_err(|_| DecodeError::NotOnCurve)
             }
             0x02 | 0x03 => {
-                if buf.len() != size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
-                }
-                // recompute y coordinate
-                let one = self.f.one();
-                let x = self.f.elt(x_val);
-                let x_a = &x + &self.a;
-                let xx_ax = &x_a * &x;
-                let xx_ax_1 = &xx_ax + &one;
-                let byy = &xx_ax_1 * &x;
-                let b_inv = &one / &self.b;
-                let yy = &byy * b_inv;
-                let y_sqrt = yy.sqrt();
-                let s = y_sqrt.sgn0();
-                let deser_tag = (((s >> 1) & 0x1) + 2) as u8;
-                let mut y = y_sqrt;
-                if tag != deser_tag {
-                    y = -y;
+                if data.len() != size {
+                    return Err(DecodeError::WrongLength);
                 }
-                Ok(self.new_point(x, y))
+                let x = f.from_bytes_be(data)?;
+                self.lift_x(&x, tag == 0x03)
             }
-            _ => Err(Error::new(ErrorKind::Other, "Invalid tag specified")),
+            _ => Err(DecodeError::InvalidTag),
+        }?;
+        if policy.subgroup_check && !p.is_torsion_free() {
+            return Err(DecodeError::NotInSubgroup);
         }
+        Ok(p)
     }
 }
 
@@ -166,7 +239,7 @@ impl std::fmt::Display for Curve {
         write!(
             f,
             "Montgomery Curve by^2=x^3+ax^2+x\na: {}\nb: {}",
-            self.a, self.b,
+            self.0.a, self.0.b,
         )
     }
 }
@@ -187,16 +260,16 @@ pub struct Params {
 impl<'a> std::convert::From<&'a Params> for Curve {
     fn from(params: &'a Params) -> Curve {
         let f = Fp::new(BigUint::from_str(params.p).unwrap());
-        Curve {
+        Curve(Arc::new(Inner {
             a: f.from(params.a),
             b: f.from(params.b),
             s: f.from(params.s),
-            r: BigUint::from_str(params.r).unwrap(),
+            q: Zq::new(BigUint::from_str(params.r).unwrap()),
             h: BigUint::from_str(params.h).unwrap(),
             gx: f.from(params.gx),
             gy: f.from(params.gy),
             f,
-        }
+        }))
     }
 }
 
@@ -205,9 +278,13 @@ const ERR_ECC_NEW: &str = "not valid point";
 // tests for ser/deser
 #[cfg(test)]
 mod tests {
-    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
-    use crate::field::Field;
+    use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve, Encode};
+    use crate::field::{Field, Sgn0};
     use crate::instances::{GetCurve, CURVE25519, CURVE448};
+    use crate::montgomery::Curve;
+    use crate::ops::{FromFactory, Serialize};
+    use crate::DecodeError;
+    use num_bigint::{BigInt, Sign};
 
     #[test]
     fn point_serialization() {
@@ -216,7 +293,7 @@ mod tests {
             let len_p = ec.get_field().size_bytes();
             let gen = ec.get_generator();
             let ser = gen.encode(false);
-            assert_eq!(ser.len(), 2 * len_p + 1);
+            assert_eq!(ser.len(), 2 * len_p);
             let deser = ec.decode(&ser).unwrap();
             assert!(
                 ec.is_on_curve(&deser),
@@ -224,6 +301,15 @@ mod tests {
                 id
             );
             assert!(gen == deser, "decompressed point equality check for {}", id);
+            let ser = gen.encode_sec1(false);
+            assert_eq!(ser.len(), 2 * len_p + 1);
+            let deser = ec.decode_sec1(&ser, &DecodePolicy::default()).unwrap();
+            assert!(gen == deser, "SEC1 point equality check for {}", id);
+            let id = ec.identity().encode_sec1(false);
+            assert!(ec
+                .decode_sec1(&id, &DecodePolicy::default())
+                .unwrap()
+                .is_zero());
         }
     }
 
@@ -234,14 +320,198 @@ mod tests {
             let len_p = ec.get_field().size_bytes();
             let gen = ec.get_generator();
             let ser = gen.encode(true);
-            assert_eq!(ser.len(), len_p + 1);
+            assert_eq!(ser.len(), len_p);
             let deser = ec.decode(&ser).unwrap();
             assert!(
                 ec.is_on_curve(&deser),
                 "compressed point validity check for {}",
                 id
             );
-            assert!(gen == deser, "compressed point equality check for {}", id);
+            assert!(gen == deser || gen == -deser);
+            let u = ec.decode_u(&ser).unwrap();
+            let odd_y = ec.0.gy.sgn0() == -1;
+            assert!(gen == ec.lift_x(&u, odd_y).unwrap());
+            assert!(-gen.clone() == ec.lift_x(&u, !odd_y).unwrap());
+            let ser = gen.encode_sec1(true);
+            assert_eq!(ser.len(), len_p + 1);
+            let deser = ec.decode_sec1(&ser, &DecodePolicy::default()).unwrap();
+            assert!(
+                gen == deser,
+                "SEC1 compressed point equality check for {}",
+                id
+            );
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let policy = DecodePolicy::default();
+            let ser = ec.get_generator().encode(true);
+            let len = ser.len();
+            assert_eq!(
+                ec.decode(&ser[..len - 1]).err(),
+                Some(DecodeError::WrongLength)
+            );
+            let mut ser = ec.get_generator().encode(false);
+            ser[0] ^= 1;
+            assert_eq!(ec.decode(&ser).err(), Some(DecodeError::NotOnCurve));
+
+            let mut ser = ec.get_generator().encode_sec1(false);
+            let len = ser.len();
+            assert_eq!(
+                ec.decode_sec1(&ser[..len - 1], &policy).err(),
+                Some(DecodeError::WrongLength)
+            );
+            ser[len - 1] ^= 1;
+            assert_eq!(
+                ec.decode_sec1(&ser, &policy).err(),
+                Some(DecodeError::NotOnCurve)
+            );
+            ser[0] = 0x07;
+            assert_eq!(
+                ec.decode_sec1(&ser, &policy).err(),
+                Some(DecodeError::InvalidTag)
+            );
+        }
+    }
+
+    // Computes the X25519 and X448 functions of RFC 7748 on points of the curve.
+    fn x_function(ec: &Curve, k: &[u8], u: &[u8]) -> Vec<u8> {
+        let mut k = k.to_vec();
+        if k.len() == 32 {
+            k[0] &= 248;
+            k[31] &= 127;
+            k[31] |= 64;
+        } else {
+            k[0] &= 252;
+            k[55] |= 128;
+        }
+        let k = BigInt::from_bytes_le(Sign::Plus, &k);
+        let u = ec.decode_u(u).unwrap();
+        let p = ec.lift_x(&u, false).unwrap();
+        p.mul_int(&k).encode(true)
+    }
+
+    #[test]
+    fn rfc7748_vectors() {
+        // test vectors from RFC 7748 Section 6.
+        for (id, k_a, k_b, pk_a, pk_b, shared) in [
+            (
+                CURVE25519,
+                "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+                "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+                "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a",
+                "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+                "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+            ),
+            (
+                CURVE448,
+                "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b",
+                "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d",
+                "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0",
+                "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609",
+                "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d",
+            ),
+        ] {
+            let ec = id.get();
+            let [k_a, k_b, pk_a, pk_b, shared] =
+                [k_a, k_b, pk_a, pk_b, shared].map(|x| hex::decode(x).unwrap());
+            let g = ec.get_generator().encode(true);
+            assert_eq!(x_function(&ec, &k_a, &g), pk_a);
+            assert_eq!(x_function(&ec, &k_b, &g), pk_b);
+            assert_eq!(x_function(&ec, &k_a, &pk_b), shared);
+            assert_eq!(x_function(&ec, &k_b, &pk_a), shared);
+        }
+    }
+
+    #[test]
+    fn u_coordinate_decoding() {
+        let ec = CURVE25519.get();
+        let f = ec.get_field();
+        // the most significant bit is masked.
+        let mut u = ec.get_generator().encode(true);
+        u[31] |= 0x80;
+        assert!(ec.decode_u(&u).unwrap() == ec.0.gx);
+        // non-canonical values are reduced.
+        let mut u = (f.get_modulus() + 9u32).to_biguint().unwrap().to_bytes_le();
+        u.resize(32, 0);
+        assert!(ec.decode_u(&u).unwrap() == ec.0.gx);
+        assert!(ec.decode(&u).unwrap().encode(true) == ec.get_generator().encode(true));
+        // a string of 0xff bytes is masked and reduced to u = 18.
+        let u18 = f.elt(BigInt::from(18)).to_bytes_le();
+        assert!(ec.decode_u(&[0xff; 32]).unwrap() == f.elt(BigInt::from(18)));
+        assert!(ec.decode(&[0xff; 32]).unwrap().encode(true) == u18);
+        // x448 uses all the bits, so the same string is reduced to u = 2^224.
+        let ec = CURVE448.get();
+        let f = ec.get_field();
+        let u = [0xff; 56];
+        let expected = BigInt::from_bytes_le(Sign::Plus, &u) - f.get_modulus();
+        assert!(expected == BigInt::from(1) << 224);
+        assert!(ec.decode_u(&u).unwrap() == f.elt(expected.clone()));
+        assert!(ec.decode(&u).unwrap().encode(true) == f.elt(expected).to_bytes_le());
+    }
+
+    #[test]
+    fn identity_encoding() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            // the point (0,0) of order two is not mistaken for the identity,
+            // which has no u-coordinate and is only encoded in SEC1.
+            let t = ec.new_point(f.zero(), f.zero());
+            assert_eq!(ec.identity().encode_sec1(true), vec![0]);
+            for compress in [true, false] {
+                let ser = t.encode(compress);
+                assert!(ser.iter().all(|&b| b == 0));
+                let p = ec.decode(&ser).unwrap();
+                assert!(p == t && !p.is_zero());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn identity_has_no_u_coordinate() {
+        CURVE25519.get().identity().encode(true);
+    }
+
+    #[test]
+    fn subgroup_checks() {
+        let policy = DecodePolicy {
+            subgroup_check: true,
+            ..Default::default()
+        };
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let gen = ec.get_generator();
+            assert!(gen.is_torsion_free() && !gen.is_small_order());
+            assert!(gen.torsion_component().is_zero());
+            assert!(ec.decode_with(&gen.encode(false), &policy).unwrap() == gen);
+
+            // (0,0) has order two.
+            let t = ec.new_point(f.zero(), f.zero());
+            assert!(t.is_two_torsion() && t.is_small_order());
+            let p = &gen + &t;
+            assert!(!p.is_torsion_free() && !p.is_small_order());
+            assert!(p.torsion_component() == t);
+            assert_eq!(
+                ec.decode_with(&p.encode(false), &policy).err(),
+                Some(DecodeError::NotInSubgroup)
+            );
+            assert_eq!(
+                ec.decode_with(&p.encode(true), &policy).err(),
+                Some(DecodeError::NotInSubgroup)
+            );
+
+            // any point is the sum of its components.
+            let p = (1u32..)
+                .find_map(|i: u32| ec.lift_x(&f.from(i), false).ok())
+                .unwrap();
+            let t = p.torsion_component();
+            assert!(t.is_small_order() && (&p - &t).is_torsion_free());
         }
     }
 }
@@ -5,6 +5,7 @@ use crate::field::{CMov, Field, Sgn0, Sqrt};
 use crate::montgomery::Curve;
 use crate::ops::FromFactory;
 use crate::primefield::FpElt;
+use crate::Error;
 
 pub struct Ell2 {
     e: Curve,
@@ -15,18 +16,25 @@ pub struct Ell2 {
 
 impl Ell2 {
     pub fn new(e: Curve, z: FpElt) -> Ell2 {
+        match Ell2::try_new(e, z) {
+            Ok(m) => m,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    /// Returns an error if the curve does not satisfy the requirements of the map.
+    pub fn try_new(e: Curve, z: FpElt) -> Result<Ell2, Error> {
         if !Ell2::verify(&e) {
-            panic!("wrong input parameters")
+            Err(Error::InvalidParameters)
         } else {
-            let inb = 1u32 / &e.b;
-            let ca = &e.a / &inb;
+            let inb = 1u32 / &e.0.b;
+            let ca = &e.0.a / &inb;
             let cb = inb ^ 2u32;
-            Ell2 { e, z, ca, cb }
+            Ok(Ell2 { e, z, ca, cb })
         }
     }
     fn verify(e: &Curve) -> bool {
-        let cond1 = !e.a.is_zero();
-        let cond2 = !e.b.is_zero();
+        let cond1 = !e.0.a.is_zero();
+        let cond2 = !e.0.b.is_zero();
         cond1 && cond2
     }
 }
@@ -58,8 +66,8 @@ impl MapToCurve for Ell2 {
         let mut y = y2.sqrt(); //          17.   y = sqrt(y2)
         let e3 = y.sgn0() == 1; //         18.  e3 = sgn0(y) == 1        // Fix sign of y
         y = cmov(&(-&y), &y, e2 ^ e3); //  19.   y = CMOV(-y, y, e2 xor e3)
-        x = x * &self.e.b;
-        y = y * &self.e.b;
+        x = x * &self.e.0.b;
+        y = y * &self.e.0.b;
         self.e.new_point(x, y)
     }
 }
@@ -5,9 +5,8 @@
 mod curve;
 mod elligator2;
 mod point;
-mod scalar;
 
 pub use crate::montgomery::curve::{Curve, Params};
 pub use crate::montgomery::elligator2::Ell2;
 pub use crate::montgomery::point::{Point, ProyCoordinates};
-pub use crate::montgomery::scalar::Scalar;
+pub use crate::scalarfield::Scalar;
@@ -1,17 +1,19 @@
 use impl_ops::impl_op_ex;
-use num_bigint::ToBigInt;
+use num_bigint::BigInt;
+use num_integer::Integer;
 use num_traits::identities::{One, Zero};
 
 use std::ops;
 
 use crate::do_if_eq;
 use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
-use crate::field::Sgn0;
+use crate::field::{Field, Sgn0};
 use crate::montgomery::curve::Curve;
-use crate::montgomery::scalar::Scalar;
 use crate::ops::ScMulRef;
 use crate::ops::Serialize;
 use crate::primefield::FpElt;
+use crate::scalarfield::{mul_int, Scalar};
+use crate::Error;
 
 #[derive(Clone)]
 pub struct ProyCoordinates {
@@ -30,18 +32,70 @@ impl EcPoint<Scalar> for Point {
     fn is_zero(&self) -> bool {
         self.c.x.is_zero() && !self.c.y.is_zero() && self.c.z.is_zero()
     }
+    fn try_add(&self, p: &Self) -> Result<Self, Error> {
+        if self.e == p.e {
+            Ok(self.core_add(p))
+        } else {
+            Err(Error::CurveMismatch)
+        }
+    }
+    fn try_mul(&self, k: &Scalar) -> Result<Self, Error> {
+        if self.e.0.q == k.get_field() {
+            Ok(self.core_mul(k))
+        } else {
+            Err(Error::GroupMismatch)
+        }
+    }
+    fn mul_int(&self, k: &BigInt) -> Self {
+        mul_int(self, k, self.e.identity(), |q| q + q)
+    }
+    fn is_torsion_free(&self) -> bool {
+        self.e.0.h.is_one() || self.mul_int(&self.e.0.q.get_modulus()).is_zero()
+    }
+    fn is_small_order(&self) -> bool {
+        self.mul_int(&self.e.get_cofactor()).is_zero()
+    }
+    fn torsion_component(&self) -> Self {
+        let r = self.e.0.q.get_modulus();
+        let h = self.e.get_cofactor();
+        // [r*u]P with u = 1/r mod h removes the component of order r.
+        let u = r.extended_gcd(&h).x.mod_floor(&h);
+        self.mul_int(&r).mul_int(&u)
+    }
 }
 impl Encode for Point {
+    /// Encodes the u-coordinate of the point following RFC 7748 if `compress`
+    /// is set. Otherwise, it returns the affine coordinates (u, v), each one
+    /// in little-endian order. The point at infinity has no affine
+    /// coordinates, so it cannot be encoded, and this function panics; use
+    /// `encode_sec1` instead.
+    // based on https://tools.ietf.org/html/rfc7748#section-5
     fn encode(&self, compress: bool) -> Vec<u8> {
-        // normalize the point to ensure that z = 1
-        // clone so that we don't mutate the original point
+        assert!(!self.is_zero(), "{}", ERR_ENC_INF);
         let mut p_normal = self.clone();
         p_normal.normalize();
+        let mut o = p_normal.c.x.to_bytes_le();
+        if !compress {
+            o.append(&mut p_normal.c.y.to_bytes_le());
+        }
+        o
+    }
+}
+
+impl Point {
+    /// Encodes the point as in SEC1, i.e., either the point at infinity
+    /// (0x00), an uncompressed point (0x04), or a compressed point (0x02 or
+    /// 0x03), where coordinates are encoded in big-endian order.
+    pub fn encode_sec1(&self, compress: bool) -> Vec<u8> {
         // if the point is the point at infinity, then return a single
         // zeroed byte
-        if p_normal.is_zero() {
+        if self.is_zero() {
             return vec![0];
         }
+        // normalize the point to ensure that z = 1
+        // clone so that we don't mutate the original point
+        let mut p_normal = self.clone();
+        p_normal.normalize();
         let coords = &p_normal.c;
         let x = &coords.x;
         let y = &coords.y;
@@ -61,6 +115,16 @@ impl Encode for Point {
             o
         }
     }
+    /// Encodes the point in the hybrid format of SEC1, i.e., as an
+    /// uncompressed point whose tag (0x06 or 0x07) also carries the parity
+    /// of the y-coordinate.
+    pub fn encode_hybrid(&self) -> Vec<u8> {
+        let mut o = self.encode_sec1(false);
+        if o[0] == 0x04 {
+            o[0] = 0x06 | (o[o.len() - 1] & 0x01);
+        }
+        o
+    }
 }
 
 impl Point {
@@ -71,7 +135,7 @@ impl Point {
         self.c.z.set_one();
     }
     fn core_neg(&self) -> Point {
-        self.e.new_proy_point(ProyCoordinates {
+        self.e.new_proy_point_unchecked(ProyCoordinates {
             x: self.c.x.clone(),
             y: -&self.c.y,
             z: self.c.z.clone(),
@@ -80,7 +144,7 @@ impl Point {
     fn core_add(&self, p: &Point) -> Point {
         let (x1, y1, z1) = (&self.c.x, &self.c.y, &self.c.z);
         let (x2, y2, z2) = (&p.c.x, &p.c.y, &p.c.z);
-        let (a_ec, s_ec) = (&self.e.a, &self.e.s);
+        let (a_ec, s_ec) = (&self.e.0.a, &self.e.0.s);
         let (t0, t1, t2) = (x1 * x2, y1 * y2, z1 * z2);
         let (t3, t4) = (x1 * y2, x2 * y1);
         let (t5, t6) = (y1 * z2, y2 * z1);
@@ -96,7 +160,7 @@ impl Point {
         let x3 = &rr * &ss - &tt * &uu;
         let y3 = tt * &ww - &vv * &ss;
         let z3 = vv * uu - rr * ww;
-        self.e.new_proy_point(ProyCoordinates {
+        self.e.new_proy_point_unchecked(ProyCoordinates {
             x: x3,
             y: y3,
             z: z3,
@@ -135,10 +199,31 @@ impl_op_ex!(+|a: &Point , b: &Point | -> Point  {
 impl_op_ex!(-|a: &Point, b: &Point| -> Point { a + (-b) });
 impl_op_ex!(-|a: &Point| -> Point { a.core_neg() });
 impl_op_ex!(*|a: &Point, b: &Scalar| -> Point {
-    let r = a.e.r.to_bigint().unwrap();
-    do_if_eq!(r == b.r, a.core_mul(b), ERR_MUL_OP)
+    do_if_eq!(a.e.0.q == b.get_field(), a.core_mul(b), ERR_MUL_OP)
 });
 
+impl<'b> std::ops::Mul<&'b Point> for &Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: &'b Point) -> Self::Output {
+        other * self
+    }
+}
+impl<'b> std::ops::Mul<&'b Point> for Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: &'b Point) -> Self::Output {
+        other * &self
+    }
+}
+impl std::ops::Mul<Point> for Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: Point) -> Self::Output {
+        other * &self
+    }
+}
+
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "\nx: {}\ny: {}\nz: {}", self.c.x, self.c.y, self.c.z)
@@ -147,3 +232,4 @@ impl std::fmt::Display for Point {
 
 const ERR_MUL_OP: &str = "Scalar don't match with point";
 const ERR_ADD_OP: &str = "points of different curves";
+const ERR_ENC_INF: &str = "the point at infinity has no u-coordinate";
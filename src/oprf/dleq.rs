@@ -0,0 +1,117 @@
+//! This is documentation for the `dleq` module.
+//!
+//! The dleq module implements the proofs of discrete logarithm equality of
+//! RFC 9497 (Section 2.2), which show that the same secret scalar k relates
+//! every pair of a batch without revealing k. The pairs are merged into a
+//! single one using scalars derived from the transcript, so proofs have a
+//! constant size regardless of the size of the batch.
+
+use crate::ellipticcurve::{EcPoint, Encode};
+use crate::field::Field;
+use crate::oprf::group::OprfGroup;
+use crate::oprf::{i2osp2, Context};
+use crate::scalarfield::Scalar;
+use crate::{DecodeError, Error};
+
+/// Statement is the claim that k·A = B and k·C\[i\] = D\[i\] for every i.
+pub(super) struct Statement<'a, P> {
+    pub(super) a: &'a P,
+    pub(super) b: &'a P,
+    pub(super) c: &'a [P],
+    pub(super) d: &'a [P],
+}
+
+impl<G: OprfGroup> Context<G> {
+    /// Returns the composite elements (M, Z), which are the sums of the
+    /// elements of C and D weighted by the same pseudorandom scalars. The
+    /// prover, who knows k, computes Z as k·M.
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-2.2.1
+    fn composites(
+        &self,
+        st: &Statement<G::Point>,
+        k: Option<&Scalar>,
+    ) -> Result<(G::Point, G::Point), Error> {
+        if st.c.is_empty() || st.c.len() != st.d.len() || st.c.len() > 0xffff {
+            return Err(Error::InvalidParameters);
+        }
+        let bm = st.b.encode(true);
+        let seed_dst = self.dst("Seed-");
+        let seed = self.hash(&[&i2osp2(bm.len()), &bm, &i2osp2(seed_dst.len()), &seed_dst]);
+        let mut m = self.g.identity();
+        let mut z = self.g.identity();
+        for (i, (ci, di)) in st.c.iter().zip(st.d).enumerate() {
+            let (ci_bytes, di_bytes) = (ci.encode(true), di.encode(true));
+            let transcript = [
+                &i2osp2(seed.len()),
+                &seed[..],
+                &i2osp2(i),
+                &i2osp2(ci_bytes.len()),
+                &ci_bytes,
+                &i2osp2(di_bytes.len()),
+                &di_bytes,
+                b"Composite",
+            ]
+            .concat();
+            let w = self.hash_to_scalar(&transcript)?;
+            m = m.try_add(&ci.try_mul(&w)?)?;
+            if k.is_none() {
+                z = z.try_add(&di.try_mul(&w)?)?;
+            }
+        }
+        if let Some(k) = k {
+            z = m.try_mul(k)?;
+        }
+        Ok((m, z))
+    }
+    /// Returns the challenge, i.e., the hash of B, M, Z, t2, and t3.
+    fn challenge(&self, points: [&G::Point; 5]) -> Result<Scalar, Error> {
+        let mut transcript = Vec::new();
+        for p in points {
+            let p = p.encode(true);
+            transcript.extend(i2osp2(p.len()));
+            transcript.extend(p);
+        }
+        transcript.extend(b"Challenge");
+        self.hash_to_scalar(&transcript)
+    }
+    /// Proves the statement using the secret k and the random scalar r,
+    /// i.e., the GenerateProof function.
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-2.2.1
+    pub(super) fn generate_proof(
+        &self,
+        st: &Statement<G::Point>,
+        k: &Scalar,
+        r: &Scalar,
+    ) -> Result<Vec<u8>, Error> {
+        let (m, z) = self.composites(st, Some(k))?;
+        let t2 = st.a.try_mul(r)?;
+        let t3 = m.try_mul(r)?;
+        let c = self.challenge([st.b, &m, &z, &t2, &t3])?;
+        let s = r - &c * k;
+        Ok([self.g.serialize_scalar(&c), self.g.serialize_scalar(&s)].concat())
+    }
+    /// Verifies the proof of the statement, i.e., the VerifyProof function.
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-2.2.2
+    pub(super) fn verify_proof(&self, st: &Statement<G::Point>, proof: &[u8]) -> Result<(), Error> {
+        let ns = self.g.get_scalar_field().size_bytes();
+        if proof.len() != 2 * ns {
+            return Err(DecodeError::WrongLength.into());
+        }
+        let (c, s) = proof.split_at(ns);
+        let c = self
+            .g
+            .deserialize_scalar(c)
+            .map_err(|_| Error::InvalidProof)?;
+        let s = self
+            .g
+            .deserialize_scalar(s)
+            .map_err(|_| Error::InvalidProof)?;
+        let (m, z) = self.composites(st, None)?;
+        let t2 = st.a.try_mul(&s)?.try_add(&st.b.try_mul(&c)?)?;
+        let t3 = m.try_mul(&s)?.try_add(&z.try_mul(&c)?)?;
+        if self.challenge([st.b, &m, &z, &t2, &t3])? != c {
+            return Err(Error::InvalidProof);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+//! This is documentation for the `group` module.
+//!
+//! The group module implements the prime-order groups of the ciphersuites of
+//! RFC 9497, i.e., how to hash into elements and scalars, and how to
+//! serialize them.
+
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use sha2::{Sha256, Sha384, Sha512};
+use sha3::Shake256;
+
+use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve};
+use crate::expander::{expand_message_xmd, expand_message_xof};
+use crate::field::Field;
+use crate::hashtocurve::{hash_to_field, HashToCurve};
+use crate::oprf::Suite;
+use crate::ops::{Deserialize, FromFactory, Serialize};
+use crate::scalarfield::{Scalar, Zq};
+use crate::weierstrass::SSWU;
+use crate::{decaf448, ristretto255, weierstrass, DecodeError, Error};
+
+/// OprfGroup lists the operations that differ between the groups of the
+/// suites, so that the protocol is written once. Elements are serialized
+/// with `encode(true)`.
+pub(super) trait OprfGroup: EllipticCurve<Scalar = Scalar, ScalarField = Zq> {
+    /// Hashes a message into an element, i.e., the HashToGroup function.
+    fn hash_to_group(&self, suite: Suite, msg: &[u8], dst: &[u8]) -> Result<Self::Point, Error>;
+    /// Hashes a message into a scalar, i.e., the HashToScalar function.
+    fn hash_to_scalar(&self, suite: Suite, msg: &[u8], dst: &[u8]) -> Result<Scalar, Error>;
+    /// Encodes a scalar using Ns bytes, i.e., the SerializeScalar function.
+    fn serialize_scalar(&self, k: &Scalar) -> Vec<u8>;
+    /// Decodes a scalar, which must be less than the order of the group.
+    fn deserialize_scalar(&self, buf: &[u8]) -> Result<Scalar, DecodeError>;
+    /// Decodes an element, which must be in the group and must not be the
+    /// identity, i.e., the DeserializeElement function.
+    fn deserialize_element(&self, buf: &[u8]) -> Result<Self::Point, Error> {
+        let p = self.decode_element(buf)?;
+        if p.is_zero() {
+            return Err(Error::InvalidPoint);
+        }
+        Ok(p)
+    }
+    /// Decodes an element without checking whether it is the identity.
+    fn decode_element(&self, buf: &[u8]) -> Result<Self::Point, DecodeError>;
+}
+
+/// Hashes a message into a point of the curve with the `_RO_` encoding of
+/// RFC 9380 using the simplified SWU map.
+fn sswu_ro<D: Digest + BlockSizeUser>(
+    ec: &weierstrass::Curve,
+    z: i32,
+    k: usize,
+    msg: &[u8],
+    dst: &[u8],
+) -> Result<weierstrass::Point, Error> {
+    let map = SSWU::new(ec.clone(), ec.get_field().from(z));
+    HashToCurve::<D, _>::new(ec.clone(), map, dst, k).hash_to_curve(msg)
+}
+
+impl OprfGroup for weierstrass::Curve {
+    fn hash_to_group(&self, suite: Suite, msg: &[u8], dst: &[u8]) -> Result<Self::Point, Error> {
+        match suite {
+            Suite::P256Sha256 => sswu_ro::<Sha256>(self, -10, 128, msg, dst),
+            Suite::P384Sha384 => sswu_ro::<Sha384>(self, -12, 192, msg, dst),
+            Suite::P521Sha512 => sswu_ro::<Sha512>(self, -4, 256, msg, dst),
+            _ => Err(Error::CurveMismatch),
+        }
+    }
+    fn hash_to_scalar(&self, suite: Suite, msg: &[u8], dst: &[u8]) -> Result<Scalar, Error> {
+        let fq = self.get_scalar_field();
+        let mut k = match suite {
+            Suite::P256Sha256 => hash_to_field::<Sha256, _>(&fq, msg, dst, 1, 128),
+            Suite::P384Sha384 => hash_to_field::<Sha384, _>(&fq, msg, dst, 1, 192),
+            Suite::P521Sha512 => hash_to_field::<Sha512, _>(&fq, msg, dst, 1, 256),
+            _ => Err(Error::CurveMismatch),
+        }?;
+        Ok(k.remove(0))
+    }
+    fn serialize_scalar(&self, k: &Scalar) -> Vec<u8> {
+        k.to_bytes_be()
+    }
+    fn deserialize_scalar(&self, buf: &[u8]) -> Result<Scalar, DecodeError> {
+        self.get_scalar_field().from_bytes_be(buf)
+    }
+    fn decode_element(&self, buf: &[u8]) -> Result<Self::Point, DecodeError> {
+        // only compressed points are allowed.
+        if buf.len() != 1 + self.get_field().size_bytes() {
+            return Err(DecodeError::WrongLength);
+        }
+        let policy = DecodePolicy {
+            reject_identity: true,
+            ..DecodePolicy::default()
+        };
+        self.decode_with(buf, &policy)
+    }
+}
+
+impl OprfGroup for ristretto255::Group {
+    fn hash_to_group(&self, _: Suite, msg: &[u8], dst: &[u8]) -> Result<Self::Point, Error> {
+        Ok(ristretto255::Group::hash_to_group(self, msg, dst))
+    }
+    fn hash_to_scalar(&self, _: Suite, msg: &[u8], dst: &[u8]) -> Result<Scalar, Error> {
+        let bytes = expand_message_xmd::<Sha512>(msg, dst, 64)?;
+        Ok(self.get_scalar_field().from_bytes_wide_le(&bytes))
+    }
+    fn serialize_scalar(&self, k: &Scalar) -> Vec<u8> {
+        k.to_bytes_le()
+    }
+    fn deserialize_scalar(&self, buf: &[u8]) -> Result<Scalar, DecodeError> {
+        self.get_scalar_field().from_bytes_le(buf)
+    }
+    fn decode_element(&self, buf: &[u8]) -> Result<Self::Point, DecodeError> {
+        self.decode(buf)
+    }
+}
+
+impl OprfGroup for decaf448::Group {
+    fn hash_to_group(&self, _: Suite, msg: &[u8], dst: &[u8]) -> Result<Self::Point, Error> {
+        Ok(decaf448::Group::hash_to_group(self, msg, dst))
+    }
+    fn hash_to_scalar(&self, _: Suite, msg: &[u8], dst: &[u8]) -> Result<Scalar, Error> {
+        let bytes = expand_message_xof::<Shake256>(msg, dst, 64, 224)?;
+        Ok(self.get_scalar_field().from_bytes_wide_le(&bytes))
+    }
+    fn serialize_scalar(&self, k: &Scalar) -> Vec<u8> {
+        k.to_bytes_le()
+    }
+    fn deserialize_scalar(&self, buf: &[u8]) -> Result<Scalar, DecodeError> {
+        self.get_scalar_field().from_bytes_le(buf)
+    }
+    fn decode_element(&self, buf: &[u8]) -> Result<Self::Point, DecodeError> {
+        self.decode(buf)
+    }
+}
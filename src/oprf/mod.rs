@@ -0,0 +1,827 @@
+//! This is documentation for the `oprf` module.
+//!
+//! The oprf module implements the oblivious pseudorandom functions of
+//! RFC 9497 for the ciphersuites ristretto255-SHA512, decaf448-SHAKE256,
+//! P256-SHA256, P384-SHA384, and P521-SHA512. A client learns the output of
+//! the function keyed by the server on an input of its choice, while the
+//! server learns neither the input nor the output.
+//!
+//! The protocol runs in one of three [`Mode`]s: the base mode (OPRF), the
+//! verifiable mode (VOPRF), where the server proves that it used the key
+//! matching its public key, and the partially-oblivious mode (POPRF), where
+//! both parties also agree on a public input, the info. Elements and proofs
+//! are octet-strings, and so are keys except for the blinds.
+//!
+//! ```
+//!  use redox_ecc::oprf::{Client, Mode, Server, Suite};
+//!
+//!  let mut rng = rand::thread_rng();
+//!  let (suite, mode) = (Suite::Ristretto255Sha512, Mode::Voprf);
+//!  let server = Server::random(suite, mode, &mut rng);
+//!  let client = Client::new(suite, mode, Some(server.public_key())).unwrap();
+//!
+//!  let (blind, blinded) = client.blind(b"input", &mut rng).unwrap();
+//!  let (evaluated, proof) = server.blind_evaluate(&blinded, b"", &mut rng).unwrap();
+//!  let output = client
+//!      .finalize(b"input", &blind, &blinded, &evaluated, &proof, b"")
+//!      .unwrap();
+//!  assert_eq!(output, server.evaluate(b"input", b"").unwrap());
+//! ```
+
+mod dleq;
+mod group;
+
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+use crate::ellipticcurve::{EcPoint, Encode};
+use crate::instances::{GetCurve, P256, P384, P521};
+use crate::oprf::dleq::Statement;
+use crate::oprf::group::OprfGroup;
+use crate::scalarfield::Scalar;
+use crate::{decaf448, ristretto255, DecodeError, Error};
+
+/// Mode is one of the variants of the protocol defined in RFC 9497.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// The base mode, in which the client cannot check the evaluation.
+    Oprf,
+    /// The verifiable mode, in which the server proves the evaluation.
+    Voprf,
+    /// The partially-oblivious mode, which is verifiable and also binds a
+    /// public input, the info, to the evaluation.
+    Poprf,
+}
+
+impl Mode {
+    fn id(self) -> u8 {
+        match self {
+            Mode::Oprf => 0x00,
+            Mode::Voprf => 0x01,
+            Mode::Poprf => 0x02,
+        }
+    }
+}
+
+/// Suite is one of the ciphersuites of RFC 9497.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Suite {
+    Ristretto255Sha512,
+    Decaf448Shake256,
+    P256Sha256,
+    P384Sha384,
+    P521Sha512,
+}
+
+impl Suite {
+    /// Returns the identifier of the suite, which is part of the context.
+    fn identifier(self) -> &'static str {
+        match self {
+            Suite::Ristretto255Sha512 => "ristretto255-SHA512",
+            Suite::Decaf448Shake256 => "decaf448-SHAKE256",
+            Suite::P256Sha256 => "P256-SHA256",
+            Suite::P384Sha384 => "P384-SHA384",
+            Suite::P521Sha512 => "P521-SHA512",
+        }
+    }
+    /// Hashes the concatenation of the data, i.e., the Hash function, whose
+    /// output has Nh bytes.
+    fn hash(self, data: &[&[u8]]) -> Vec<u8> {
+        fn digest<D: Digest>(data: &[&[u8]]) -> Vec<u8> {
+            let mut h = D::new();
+            data.iter().for_each(|d| Digest::update(&mut h, d));
+            h.finalize().to_vec()
+        }
+        match self {
+            Suite::Ristretto255Sha512 | Suite::P521Sha512 => digest::<Sha512>(data),
+            Suite::P256Sha256 => digest::<Sha256>(data),
+            Suite::P384Sha384 => digest::<Sha384>(data),
+            Suite::Decaf448Shake256 => {
+                let mut h = Shake256::default();
+                data.iter().for_each(|d| h.update(d));
+                let mut out = vec![0u8; 64];
+                h.finalize_xof().read(&mut out);
+                out
+            }
+        }
+    }
+}
+
+/// Runs the body with `$c` bound to the [`Context`] of the suite and mode.
+macro_rules! with_context {
+    ($suite:expr, $mode:expr, |$c:ident| $body:expr) => {{
+        let (suite, mode) = ($suite, $mode);
+        match suite {
+            Suite::Ristretto255Sha512 => {
+                let $c = Context::new(ristretto255::Group::new(), suite, mode);
+                $body
+            }
+            Suite::Decaf448Shake256 => {
+                let $c = Context::new(decaf448::Group::new(), suite, mode);
+                $body
+            }
+            Suite::P256Sha256 => {
+                let $c = Context::new(P256.get(), suite, mode);
+                $body
+            }
+            Suite::P384Sha384 => {
+                let $c = Context::new(P384.get(), suite, mode);
+                $body
+            }
+            Suite::P521Sha512 => {
+                let $c = Context::new(P521.get(), suite, mode);
+                $body
+            }
+        }
+    }};
+}
+
+/// Encodes a length using two bytes, i.e., I2OSP(n, 2). Lengths of the
+/// inputs are checked by [`check_len`], and those of elements and hashes
+/// are always short.
+fn i2osp2(n: usize) -> [u8; 2] {
+    (n as u16).to_be_bytes()
+}
+
+/// Checks that an input or an info can be prefixed by its length.
+fn check_len(buf: &[u8]) -> Result<(), Error> {
+    if buf.len() > 0xffff {
+        return Err(Error::InvalidParameters);
+    }
+    Ok(())
+}
+
+/// Context is the group of a suite together with the mode, which determine
+/// the domain separation tags of every hash.
+struct Context<G> {
+    g: G,
+    suite: Suite,
+    mode: Mode,
+}
+
+impl<G: OprfGroup> Context<G> {
+    fn new(g: G, suite: Suite, mode: Mode) -> Self {
+        Context { g, suite, mode }
+    }
+    /// Returns the prefix followed by the contextString, i.e.,
+    /// "OPRFV1-" ‖ I2OSP(mode, 1) ‖ "-" ‖ identifier.
+    fn dst(&self, prefix: &str) -> Vec<u8> {
+        let id = self.suite.identifier().as_bytes();
+        [prefix.as_bytes(), b"OPRFV1-", &[self.mode.id()], b"-", id].concat()
+    }
+    fn hash(&self, data: &[&[u8]]) -> Vec<u8> {
+        self.suite.hash(data)
+    }
+    /// Hashes an input into an element, which must not be the identity.
+    fn hash_to_group(&self, input: &[u8]) -> Result<G::Point, Error> {
+        let p = self
+            .g
+            .hash_to_group(self.suite, input, &self.dst("HashToGroup-"))?;
+        if p.is_zero() {
+            return Err(Error::InvalidPoint);
+        }
+        Ok(p)
+    }
+    fn hash_to_scalar(&self, msg: &[u8]) -> Result<Scalar, Error> {
+        self.g
+            .hash_to_scalar(self.suite, msg, &self.dst("HashToScalar-"))
+    }
+    /// Samples a nonzero scalar, i.e., the RandomScalar function.
+    fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Scalar {
+        loop {
+            let k = self.g.get_scalar_field().random(rng);
+            if !k.is_zero() {
+                return k;
+            }
+        }
+    }
+    fn public_key(&self, sk: &Scalar) -> Result<Vec<u8>, Error> {
+        Ok(self.g.get_generator().try_mul(sk)?.encode(true))
+    }
+    /// Derives a secret key from a seed and an info, i.e., the
+    /// DeriveKeyPair function.
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-3.2.1
+    fn derive_key_pair(&self, seed: &[u8], info: &[u8]) -> Result<Scalar, Error> {
+        check_len(info)?;
+        let dst = self.dst("DeriveKeyPair");
+        let mut msg = [seed, &i2osp2(info.len()), info, &[0]].concat();
+        for counter in 0..=255u8 {
+            *msg.last_mut().unwrap() = counter;
+            let sk = self.g.hash_to_scalar(self.suite, &msg, &dst)?;
+            if !sk.is_zero() {
+                return Ok(sk);
+            }
+        }
+        Err(Error::InvalidParameters)
+    }
+    /// Returns the key t of the proofs together with the key k that
+    /// multiplies the elements. In POPRF, t = skS + m is tweaked by the hash
+    /// m of the info, and k is its inverse; otherwise, both are skS.
+    fn keys(&self, sk: &Scalar, info: &[u8]) -> Result<(Scalar, Scalar), Error> {
+        if self.mode != Mode::Poprf {
+            return Ok((sk.clone(), sk.clone()));
+        }
+        let t = sk + self.info_scalar(info)?;
+        if t.is_zero() {
+            return Err(Error::InvalidParameters);
+        }
+        let k = 1u32 / &t;
+        Ok((t, k))
+    }
+    fn info_scalar(&self, info: &[u8]) -> Result<Scalar, Error> {
+        self.hash_to_scalar(&[b"Info", &i2osp2(info.len())[..], info].concat())
+    }
+    /// Checks that the info has a valid length and that it is empty unless
+    /// the mode is POPRF.
+    fn check_info(&self, info: &[u8]) -> Result<(), Error> {
+        if self.mode != Mode::Poprf && !info.is_empty() {
+            return Err(Error::InvalidParameters);
+        }
+        check_len(info)
+    }
+    /// Returns the blinded element, i.e., blind·H(input).
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-3.3.1
+    fn blind(&self, input: &[u8], blind: &Scalar) -> Result<Vec<u8>, Error> {
+        check_len(input)?;
+        Ok(self.hash_to_group(input)?.try_mul(blind)?.encode(true))
+    }
+    /// Evaluates the blinded elements, and proves the evaluation with the
+    /// random scalar r unless the mode is OPRF.
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-3.3
+    fn blind_evaluate(
+        &self,
+        sk: &Scalar,
+        blinded: &[&[u8]],
+        info: &[u8],
+        r: &Scalar,
+    ) -> Result<(Vec<Vec<u8>>, Vec<u8>), Error> {
+        self.check_info(info)?;
+        let blinded = blinded
+            .iter()
+            .map(|b| self.g.deserialize_element(b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (t, k) = self.keys(sk, info)?;
+        let evaluated = blinded
+            .iter()
+            .map(|b| b.try_mul(&k))
+            .collect::<Result<Vec<_>, _>>()?;
+        let g = self.g.get_generator();
+        let b = g.try_mul(&t)?;
+        let (c, d) = match self.mode {
+            Mode::Poprf => (&evaluated[..], &blinded[..]),
+            Mode::Oprf | Mode::Voprf => (&blinded[..], &evaluated[..]),
+        };
+        let proof = match self.mode {
+            Mode::Oprf => Vec::new(),
+            Mode::Voprf | Mode::Poprf => {
+                self.generate_proof(&Statement { a: &g, b: &b, c, d }, &t, r)?
+            }
+        };
+        let evaluated = evaluated.iter().map(|e| e.encode(true)).collect();
+        Ok((evaluated, proof))
+    }
+    /// Verifies the proof of the evaluations unless the mode is OPRF, and
+    /// unblinds them.
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-3.3
+    fn finalize(
+        &self,
+        public: Option<&[u8]>,
+        items: &[BatchItem],
+        proof: &[u8],
+        info: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.check_info(info)?;
+        let evaluated = items
+            .iter()
+            .map(|item| self.g.deserialize_element(item.evaluated))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(public) = public {
+            let blinded = items
+                .iter()
+                .map(|item| self.g.deserialize_element(item.blinded))
+                .collect::<Result<Vec<_>, _>>()?;
+            let g = self.g.get_generator();
+            let pk = self.g.deserialize_element(public)?;
+            let (b, c, d) = match self.mode {
+                Mode::Poprf => {
+                    let b = g.try_mul(&self.info_scalar(info)?)?.try_add(&pk)?;
+                    if b.is_zero() {
+                        return Err(Error::InvalidPoint);
+                    }
+                    (b, &evaluated[..], &blinded[..])
+                }
+                _ => (pk, &blinded[..], &evaluated[..]),
+            };
+            self.verify_proof(&Statement { a: &g, b: &b, c, d }, proof)?;
+        }
+        items
+            .iter()
+            .zip(&evaluated)
+            .map(|(item, e)| {
+                check_len(item.input)?;
+                let n = e.try_mul(&(1u32 / item.blind))?;
+                Ok(self.output(item.input, info, &n))
+            })
+            .collect()
+    }
+    /// Evaluates the function on an input without blinding.
+    // based on https://www.rfc-editor.org/rfc/rfc9497.html#section-3.3.1
+    fn evaluate(&self, sk: &Scalar, input: &[u8], info: &[u8]) -> Result<Vec<u8>, Error> {
+        self.check_info(info)?;
+        check_len(input)?;
+        let (_, k) = self.keys(sk, info)?;
+        let n = self.hash_to_group(input)?.try_mul(&k)?;
+        Ok(self.output(input, info, &n))
+    }
+    /// Hashes the input, the info for POPRF, and the unblinded element.
+    fn output(&self, input: &[u8], info: &[u8], n: &G::Point) -> Vec<u8> {
+        let n = n.encode(true);
+        let info = match self.mode {
+            Mode::Poprf => [&i2osp2(info.len())[..], info].concat(),
+            Mode::Oprf | Mode::Voprf => Vec::new(),
+        };
+        let (input_len, n_len) = (i2osp2(input.len()), i2osp2(n.len()));
+        self.hash(&[&input_len, input, &info, &n_len, &n, b"Finalize"])
+    }
+}
+
+/// Server holds the secret key of the function, and evaluates it on the
+/// blinded elements of clients.
+#[derive(Clone)]
+pub struct Server {
+    suite: Suite,
+    mode: Mode,
+    sk: Scalar,
+    public: Vec<u8>,
+}
+
+impl Server {
+    fn from_scalar(suite: Suite, mode: Mode, sk: Scalar) -> Result<Self, Error> {
+        let public = with_context!(suite, mode, |c| c.public_key(&sk))?;
+        Ok(Server {
+            suite,
+            mode,
+            sk,
+            public,
+        })
+    }
+    /// Creates a server from a serialized secret key, which must be a
+    /// nonzero scalar.
+    pub fn new(suite: Suite, mode: Mode, sk: &[u8]) -> Result<Self, Error> {
+        let sk = with_context!(suite, mode, |c| c.g.deserialize_scalar(sk))?;
+        if sk.is_zero() {
+            return Err(DecodeError::Malformed.into());
+        }
+        Self::from_scalar(suite, mode, sk)
+    }
+    /// Derives the key from a seed and an info, i.e., the DeriveKeyPair
+    /// function. Note that the key depends on the mode.
+    pub fn derive(suite: Suite, mode: Mode, seed: &[u8], info: &[u8]) -> Result<Self, Error> {
+        let sk = with_context!(suite, mode, |c| c.derive_key_pair(seed, info))?;
+        Self::from_scalar(suite, mode, sk)
+    }
+    /// Generates a random key, i.e., the GenerateKeyPair function.
+    pub fn random<R: RngCore + CryptoRng>(suite: Suite, mode: Mode, rng: &mut R) -> Self {
+        let sk = with_context!(suite, mode, |c| c.random_scalar(rng));
+        Self::from_scalar(suite, mode, sk).unwrap()
+    }
+    /// Returns the serialization of the secret key.
+    pub fn secret_key(&self) -> Vec<u8> {
+        with_context!(self.suite, self.mode, |c| c.g.serialize_scalar(&self.sk))
+    }
+    /// Returns the serialization of the public key.
+    pub fn public_key(&self) -> &[u8] {
+        &self.public
+    }
+    /// Evaluates a blinded element, and returns the evaluated element
+    /// together with its proof, which is empty in OPRF mode. The info must
+    /// be empty unless the mode is POPRF.
+    pub fn blind_evaluate<R: RngCore + CryptoRng>(
+        &self,
+        blinded: &[u8],
+        info: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let (mut evaluated, proof) = self.blind_evaluate_batch(&[blinded], info, rng)?;
+        Ok((evaluated.remove(0), proof))
+    }
+    /// Evaluates a batch of blinded elements, and returns the evaluated
+    /// elements together with a single proof for all of them.
+    pub fn blind_evaluate_batch<R: RngCore + CryptoRng>(
+        &self,
+        blinded: &[&[u8]],
+        info: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<Vec<u8>>, Vec<u8>), Error> {
+        let r = with_context!(self.suite, self.mode, |c| c.random_scalar(rng));
+        self.blind_evaluate_with(blinded, info, &r)
+    }
+    fn blind_evaluate_with(
+        &self,
+        blinded: &[&[u8]],
+        info: &[u8],
+        r: &Scalar,
+    ) -> Result<(Vec<Vec<u8>>, Vec<u8>), Error> {
+        if blinded.is_empty() {
+            return Err(Error::InvalidParameters);
+        }
+        with_context!(self.suite, self.mode, |c| c
+            .blind_evaluate(&self.sk, blinded, info, r))
+    }
+    /// Evaluates the function on an input, which gives the same output as
+    /// the protocol run with a client.
+    pub fn evaluate(&self, input: &[u8], info: &[u8]) -> Result<Vec<u8>, Error> {
+        with_context!(self.suite, self.mode, |c| c.evaluate(&self.sk, input, info))
+    }
+}
+
+/// BatchItem is an input of a client together with its blind, the blinded
+/// element sent to the server, and the evaluated element received.
+#[derive(Clone, Copy)]
+pub struct BatchItem<'a> {
+    pub input: &'a [u8],
+    pub blind: &'a Scalar,
+    pub blinded: &'a [u8],
+    pub evaluated: &'a [u8],
+}
+
+/// Client blinds its inputs, and computes the outputs of the function from
+/// the evaluations of the server.
+#[derive(Clone)]
+pub struct Client {
+    suite: Suite,
+    mode: Mode,
+    public: Option<Vec<u8>>,
+}
+
+impl Client {
+    /// Creates a client, which needs the public key of the server in the
+    /// VOPRF and POPRF modes, and must not be given one in the OPRF mode.
+    pub fn new(suite: Suite, mode: Mode, public: Option<&[u8]>) -> Result<Self, Error> {
+        match (mode, public) {
+            (Mode::Oprf, None) => {}
+            (Mode::Voprf | Mode::Poprf, Some(public)) => {
+                with_context!(suite, mode, |c| c.g.deserialize_element(public).map(|_| ()))?;
+            }
+            _ => return Err(Error::InvalidParameters),
+        }
+        Ok(Client {
+            suite,
+            mode,
+            public: public.map(<[u8]>::to_vec),
+        })
+    }
+    /// Blinds an input, and returns the blind, which must be kept until
+    /// finalization, together with the blinded element.
+    pub fn blind<R: RngCore + CryptoRng>(
+        &self,
+        input: &[u8],
+        rng: &mut R,
+    ) -> Result<(Scalar, Vec<u8>), Error> {
+        with_context!(self.suite, self.mode, |c| {
+            let blind = c.random_scalar(rng);
+            c.blind(input, &blind).map(|blinded| (blind, blinded))
+        })
+    }
+    /// Computes the output of the function on the input from the evaluated
+    /// element, after checking the proof unless the mode is OPRF. The info
+    /// must be empty unless the mode is POPRF.
+    pub fn finalize(
+        &self,
+        input: &[u8],
+        blind: &Scalar,
+        blinded: &[u8],
+        evaluated: &[u8],
+        proof: &[u8],
+        info: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let item = BatchItem {
+            input,
+            blind,
+            blinded,
+            evaluated,
+        };
+        let mut outputs = self.finalize_batch(&[item], proof, info)?;
+        Ok(outputs.remove(0))
+    }
+    /// Computes the outputs of a batch evaluated with a single proof. The
+    /// items must be in the order in which the server evaluated them.
+    pub fn finalize_batch(
+        &self,
+        items: &[BatchItem],
+        proof: &[u8],
+        info: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        if items.is_empty() {
+            return Err(Error::InvalidParameters);
+        }
+        let public = self.public.as_deref();
+        with_context!(self.suite, self.mode, |c| c
+            .finalize(public, items, proof, info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchItem, Client, Context, Mode, Server, Suite};
+    use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
+    use crate::instances::{GetCurve, P256, P384, P521};
+    use crate::oprf::group::OprfGroup;
+    use crate::{decaf448, ristretto255, DecodeError, Error};
+    use num_bigint::BigInt;
+    use std::collections::HashMap;
+
+    const SUITES: [Suite; 5] = [
+        Suite::Ristretto255Sha512,
+        Suite::Decaf448Shake256,
+        Suite::P256Sha256,
+        Suite::P384Sha384,
+        Suite::P521Sha512,
+    ];
+
+    /// Returns the suites and modes to run the protocol with. Every mode is
+    /// run on ristretto255 and P-256, and the other suites, whose arithmetic
+    /// is slower, run POPRF.
+    fn cases() -> impl Iterator<Item = (Suite, Mode)> {
+        SUITES.into_iter().flat_map(|suite| {
+            let modes = match suite {
+                Suite::Ristretto255Sha512 | Suite::P256Sha256 => {
+                    &[Mode::Oprf, Mode::Voprf, Mode::Poprf][..]
+                }
+                _ => &[Mode::Poprf][..],
+            };
+            modes.iter().map(move |&mode| (suite, mode))
+        })
+    }
+
+    fn client(server: &Server) -> Client {
+        let public = (server.mode != Mode::Oprf).then(|| server.public_key());
+        Client::new(server.suite, server.mode, public).unwrap()
+    }
+
+    fn info(mode: Mode) -> &'static [u8] {
+        match mode {
+            Mode::Poprf => b"test info",
+            Mode::Oprf | Mode::Voprf => b"",
+        }
+    }
+
+    // The test vectors of RFC 9497 Appendix A, for every suite and mode.
+    const VECTORS: &str = include_str!("../../tests/data/rfc9497-vectors.txt");
+
+    /// Parses the lines "key = value" of a section of the vectors.
+    fn fields(section: &str) -> HashMap<&str, &str> {
+        section
+            .lines()
+            .filter_map(|line| line.split_once(" = "))
+            .collect()
+    }
+
+    #[test]
+    fn rfc9497_vectors() {
+        for section in VECTORS.split("\n## ").skip(1) {
+            let (name, section) = section.split_once('\n').unwrap();
+            let (id, mode) = name.split_once(' ').unwrap();
+            let suite = SUITES.into_iter().find(|s| s.identifier() == id).unwrap();
+            let mode = match mode {
+                "OPRF" => Mode::Oprf,
+                "VOPRF" => Mode::Voprf,
+                _ => Mode::Poprf,
+            };
+            let mut vectors = section.split("\n### ");
+            let config = fields(vectors.next().unwrap());
+            let bytes = |s: &str| hex::decode(s).unwrap();
+            let scalar = |s: &str| {
+                with_context!(suite, mode, |c| c.g.deserialize_scalar(&bytes(s))).unwrap()
+            };
+
+            // the key pair, which depends on the mode.
+            let (seed, key_info) = (bytes(config["Seed"]), bytes(config["KeyInfo"]));
+            let server = Server::derive(suite, mode, &seed, &key_info).unwrap();
+            assert_eq!(hex::encode(server.secret_key()), config["skSm"], "{name}");
+            if mode != Mode::Oprf {
+                assert_eq!(hex::encode(server.public_key()), config["pkSm"], "{name}");
+            }
+            let again = Server::new(suite, mode, &server.secret_key()).unwrap();
+            assert_eq!(again.public_key(), server.public_key());
+
+            for vector in vectors {
+                let v = fields(vector);
+                let list = |key: &str| v[key].split(',').map(bytes).collect::<Vec<_>>();
+                let (inputs, blinded, evaluated, outputs) = (
+                    list("Input"),
+                    list("BlindedElement"),
+                    list("EvaluationElement"),
+                    list("Output"),
+                );
+                let blinds: Vec<_> = v["Blind"].split(',').map(scalar).collect();
+                let info = bytes(v.get("Info").unwrap_or(&""));
+                for i in 0..inputs.len() {
+                    let got = with_context!(suite, mode, |c| c.blind(&inputs[i], &blinds[i]));
+                    assert_eq!(got.unwrap(), blinded[i], "{name} {vector}");
+                }
+
+                // the proof is generated with the given random scalar.
+                let r = v
+                    .get("ProofRandomScalar")
+                    .map_or(blinds[0].clone(), |r| scalar(r));
+                let proof = bytes(v.get("Proof").unwrap_or(&""));
+                let refs: Vec<&[u8]> = blinded.iter().map(Vec::as_slice).collect();
+                let got = server.blind_evaluate_with(&refs, &info, &r).unwrap();
+                assert_eq!(got, (evaluated.clone(), proof.clone()), "{name} {vector}");
+
+                let items: Vec<_> = (0..inputs.len())
+                    .map(|i| BatchItem {
+                        input: &inputs[i],
+                        blind: &blinds[i],
+                        blinded: &blinded[i],
+                        evaluated: &evaluated[i],
+                    })
+                    .collect();
+                let got = client(&server).finalize_batch(&items, &proof, &info);
+                assert_eq!(got.unwrap(), outputs, "{name} {vector}");
+            }
+        }
+    }
+
+    #[test]
+    fn batches() {
+        let mut rng = rand::thread_rng();
+        for (suite, mode) in cases() {
+            let server = Server::random(suite, mode, &mut rng);
+            let client = client(&server);
+            let info = info(mode);
+            let inputs: [&[u8]; 3] = [b"", b"one", b"two"];
+            let (blinds, blinded): (Vec<_>, Vec<_>) = inputs
+                .iter()
+                .map(|input| client.blind(input, &mut rng).unwrap())
+                .unzip();
+            let refs: Vec<&[u8]> = blinded.iter().map(Vec::as_slice).collect();
+            let (evaluated, proof) = server.blind_evaluate_batch(&refs, info, &mut rng).unwrap();
+            assert_eq!(proof.is_empty(), mode == Mode::Oprf, "{suite:?} {mode:?}");
+
+            let items: Vec<_> = (0..inputs.len())
+                .map(|i| BatchItem {
+                    input: inputs[i],
+                    blind: &blinds[i],
+                    blinded: &blinded[i],
+                    evaluated: &evaluated[i],
+                })
+                .collect();
+            let outputs = client.finalize_batch(&items, &proof, info).unwrap();
+            for (input, output) in inputs.iter().zip(&outputs) {
+                assert_eq!(*output, server.evaluate(input, info).unwrap());
+            }
+            assert_ne!(outputs[1], outputs[2]);
+            if mode == Mode::Oprf {
+                continue;
+            }
+
+            // the proof covers the whole batch, in the same order.
+            let mut swapped = items.clone();
+            swapped.swap(1, 2);
+            assert_eq!(
+                client.finalize_batch(&swapped, &proof, info),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                client.finalize_batch(&items[..2], &proof, info),
+                Err(Error::InvalidProof)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_proofs() {
+        let mut rng = rand::thread_rng();
+        for (suite, mode) in cases().filter(|(_, mode)| *mode != Mode::Oprf) {
+            let server = Server::random(suite, mode, &mut rng);
+            let client = client(&server);
+            let info = info(mode);
+            let (blind, blinded) = client.blind(b"input", &mut rng).unwrap();
+            let (evaluated, proof) = server.blind_evaluate(&blinded, info, &mut rng).unwrap();
+            let finalize = |evaluated: &[u8], proof: &[u8], info: &[u8]| {
+                client.finalize(b"input", &blind, &blinded, evaluated, proof, info)
+            };
+            assert!(finalize(&evaluated, &proof, info).is_ok());
+
+            // a modified challenge, another key, and another info.
+            let mut bad = proof.clone();
+            bad[1] ^= 1;
+            assert_eq!(finalize(&evaluated, &bad, info), Err(Error::InvalidProof));
+            let other = Server::random(suite, mode, &mut rng);
+            let (other_eval, other_proof) = other.blind_evaluate(&blinded, info, &mut rng).unwrap();
+            assert_eq!(
+                finalize(&other_eval, &other_proof, info),
+                Err(Error::InvalidProof)
+            );
+            if mode == Mode::Poprf {
+                assert_eq!(
+                    finalize(&evaluated, &proof, b"other info"),
+                    Err(Error::InvalidProof)
+                );
+            }
+
+            // scalars that are not reduced, and truncated proofs.
+            let mut bad = proof.clone();
+            bad.fill(0xff);
+            assert_eq!(finalize(&evaluated, &bad, info), Err(Error::InvalidProof));
+            assert_eq!(
+                finalize(&evaluated, &proof[1..], info),
+                Err(DecodeError::WrongLength.into())
+            );
+        }
+    }
+
+    #[test]
+    fn parameters() {
+        let mut rng = rand::thread_rng();
+        let suite = Suite::P256Sha256;
+        let server = Server::random(suite, Mode::Oprf, &mut rng);
+        let public = Some(server.public_key());
+
+        // the public key is only given in the verifiable modes.
+        assert!(Client::new(suite, Mode::Oprf, public).is_err());
+        assert_eq!(
+            Client::new(suite, Mode::Voprf, None).err(),
+            Some(Error::InvalidParameters)
+        );
+        assert!(Client::new(suite, Mode::Poprf, public).is_ok());
+        assert!(Client::new(Suite::P384Sha384, Mode::Voprf, public).is_err());
+
+        // the info is only allowed in POPRF.
+        let client = client(&server);
+        let (blind, blinded) = client.blind(b"input", &mut rng).unwrap();
+        assert_eq!(
+            server.blind_evaluate(&blinded, b"info", &mut rng),
+            Err(Error::InvalidParameters)
+        );
+        assert_eq!(
+            server.evaluate(b"input", b"info"),
+            Err(Error::InvalidParameters)
+        );
+        let (evaluated, _) = server.blind_evaluate(&blinded, b"", &mut rng).unwrap();
+        assert_eq!(
+            client.finalize(b"input", &blind, &blinded, &evaluated, b"", b"info"),
+            Err(Error::InvalidParameters)
+        );
+        assert_eq!(
+            server.blind_evaluate_batch(&[], b"", &mut rng),
+            Err(Error::InvalidParameters)
+        );
+        assert_eq!(
+            client.finalize_batch(&[], b"", b""),
+            Err(Error::InvalidParameters)
+        );
+        let long = vec![0u8; 0x10000];
+        assert_eq!(
+            client.blind(&long, &mut rng).err(),
+            Some(Error::InvalidParameters)
+        );
+
+        // keys must be nonzero and reduced.
+        assert_eq!(
+            Server::new(suite, Mode::Oprf, &[0u8; 32]).err(),
+            Some(DecodeError::Malformed.into())
+        );
+        assert!(Server::new(suite, Mode::Oprf, &[0xffu8; 32]).is_err());
+        assert_eq!(
+            Server::new(suite, Mode::Oprf, &[1u8; 31]).err(),
+            Some(DecodeError::WrongLength.into())
+        );
+    }
+
+    #[test]
+    fn elements() {
+        let mut rng = rand::thread_rng();
+        for suite in SUITES {
+            let server = Server::random(suite, Mode::Voprf, &mut rng);
+            let identity = with_context!(suite, Mode::Voprf, |c| c.g.identity().encode(true));
+            let gen = with_context!(suite, Mode::Voprf, |c| c.g.get_generator().encode(true));
+            let size = gen.len();
+
+            // the identity is rejected, whatever its encoding is.
+            let identity = if identity.len() == size {
+                identity
+            } else {
+                [identity, vec![0; size - 1]].concat()
+            };
+            assert!(server.blind_evaluate(&identity, b"", &mut rng).is_err());
+            assert!(Client::new(suite, Mode::Voprf, Some(&identity)).is_err());
+            assert!(server.blind_evaluate(&gen[1..], b"", &mut rng).is_err());
+            assert!(server.blind_evaluate(&gen, b"", &mut rng).is_ok());
+        }
+
+        // the identity is never serialized.
+        let g = decaf448::Group::new();
+        let p = g.get_generator() * g.new_scalar(BigInt::from(0));
+        assert!(p.is_zero());
+        assert_eq!(
+            g.deserialize_element(&p.encode(true)).err(),
+            Some(Error::InvalidPoint)
+        );
+    }
+}
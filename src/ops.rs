@@ -1,3 +1,5 @@
+use crate::{DecodeError, Error};
+
 #[doc(hidden)]
 macro_rules! make_trait {
     (binary, $trait:ident, $name:ident) => {
@@ -73,10 +75,11 @@ pub trait Serialize {
 }
 
 /// The Deserialize trait recovers native types from arrays of bytes
+#[allow(clippy::wrong_self_convention)]
 pub trait Deserialize {
     type Deser;
-    fn from_bytes_be(&self, _: &[u8]) -> Result<Self::Deser, std::io::Error>;
-    fn from_bytes_le(&self, _: &[u8]) -> Result<Self::Deser, std::io::Error>;
+    fn from_bytes_be(&self, _: &[u8]) -> Result<Self::Deser, DecodeError>;
+    fn from_bytes_le(&self, _: &[u8]) -> Result<Self::Deser, DecodeError>;
 }
 
 pub trait IntoFactory<T, Out>: Sized {
@@ -96,3 +99,9 @@ pub trait FromFactory<T: Sized> {
     type Output;
     fn from(&self, _: T) -> Self::Output;
 }
+
+/// TryFromFactory is the fallible version of FromFactory.
+pub trait TryFromFactory<T: Sized> {
+    type Output;
+    fn try_from(&self, _: T) -> Result<Self::Output, Error>;
+}
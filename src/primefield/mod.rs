@@ -2,7 +2,6 @@
 //!
 //! The primefield module is meant to be used for bar.
 
-use atomic_refcell::AtomicRefCell;
 use impl_ops::impl_op_ex;
 use num_bigint::{BigInt, BigUint, ToBigInt};
 use num_integer::Integer;
@@ -11,15 +10,16 @@ use num_traits::identities::{One, Zero};
 
 use std::ops;
 use std::ops::{BitXor, Div};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use crate::do_if_eq;
 use crate::field::{CMov, Field, FieldElement, Sgn0, Sqrt};
-use crate::ops::{Deserialize, FromFactory, Serialize};
+use crate::ops::{Deserialize, FromFactory, Serialize, TryFromFactory};
+use crate::{DecodeError, Error};
 
 struct Params {
     p: BigInt,
-    sqrt_precmp: AtomicRefCell<Option<SqrtPrecmp>>,
+    sqrt_precmp: OnceLock<SqrtPrecmp>,
 }
 
 impl Eq for Params {}
@@ -31,9 +31,17 @@ impl PartialEq for Params {
 }
 
 /// Fp implements a base field of prime characteristic.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct Fp(Arc<Params>);
 
+impl Eq for Fp {}
+
+impl PartialEq for Fp {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
 impl Fp {
     /// Use `new` to generate a prime field instance.
     /// ```
@@ -46,7 +54,7 @@ impl Fp {
         // TODO: verify whether p is prime.
         Fp(Arc::new(Params {
             p: modulus.to_bigint().unwrap(),
-            sqrt_precmp: AtomicRefCell::new(None),
+            sqrt_precmp: OnceLock::new(),
         }))
     }
 }
@@ -68,19 +76,32 @@ impl Field for Fp {
         self.0.p.clone()
     }
     fn size_bytes(&self) -> usize {
-        (self.0.p.bits() as usize + 7) / 8
+        (self.0.p.bits() as usize).div_ceil(8)
+    }
+}
+
+impl Fp {
+    fn decode_biguint(&self, len: usize, n: BigUint) -> Result<FpElt, DecodeError> {
+        let n = n.to_bigint().unwrap();
+        if len != self.size_bytes() {
+            Err(DecodeError::WrongLength)
+        } else if n >= self.0.p {
+            Err(DecodeError::NonCanonical)
+        } else {
+            Ok(self.elt(n))
+        }
     }
 }
 
 impl Deserialize for Fp {
     type Deser = <Fp as Field>::Elt;
-    fn from_bytes_be(&self, bytes: &[u8]) -> Result<Self::Deser, std::io::Error> {
-        let n = BigUint::from_bytes_be(bytes);
-        Ok(self.elt(n.to_bigint().unwrap()))
+    /// Decodes exactly `size_bytes()` bytes into a field element, rejecting
+    /// values that are not reduced modulo p.
+    fn from_bytes_be(&self, bytes: &[u8]) -> Result<Self::Deser, DecodeError> {
+        self.decode_biguint(bytes.len(), BigUint::from_bytes_be(bytes))
     }
-    fn from_bytes_le(&self, bytes: &[u8]) -> Result<Self::Deser, std::io::Error> {
-        let n = BigUint::from_bytes_le(bytes);
-        Ok(self.elt(n.to_bigint().unwrap()))
+    fn from_bytes_le(&self, bytes: &[u8]) -> Result<Self::Deser, DecodeError> {
+        self.decode_biguint(bytes.len(), BigUint::from_bytes_le(bytes))
     }
 }
 
@@ -102,9 +123,29 @@ impl_from_factory!(Fp, <u8 u16 u32 u64 i8 i16 i32 i64>);
 impl FromFactory<&str> for Fp {
     type Output = <Fp as Field>::Elt;
     fn from(&self, s: &str) -> Self::Output {
+        match self.try_from(s) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+impl TryFromFactory<&str> for Fp {
+    type Output = <Fp as Field>::Elt;
+    /// Parses a string in decimal, or in hexadecimal, octal, or binary when
+    /// prefixed by `0x`, `0o`, or `0b`, respectively.
+    /// ```
+    ///  use num_bigint::BigUint;
+    ///  use redox_ecc::ops::TryFromFactory;
+    ///  use redox_ecc::primefield::Fp;
+    ///  let f = Fp::new(BigUint::from(101u32));
+    ///  assert!(f.try_from("-0x1f").is_ok());
+    ///  assert!(f.try_from("0xz1").is_err());
+    /// ```
+    fn try_from(&self, s: &str) -> Result<Self::Output, Error> {
         let mut sl = &s[0..];
         if sl.is_empty() {
-            return self.zero();
+            return Ok(self.zero());
         }
         let neg = if sl.starts_with('-') {
             sl = &sl[1..];
@@ -131,7 +172,10 @@ impl FromFactory<&str> for Fp {
         } else {
             10
         };
-        self.elt(neg * BigInt::parse_bytes(sl.as_bytes(), radix).unwrap())
+        match BigInt::parse_bytes(sl.as_bytes(), radix) {
+            Some(n) => Ok(self.elt(neg * n)),
+            None => Err(Error::InvalidString),
+        }
     }
 }
 
@@ -167,13 +211,13 @@ impl Serialize for FpElt {
 
 // impl<'b> EltOps<&'b FpElt, FpElt> for FpElt {}
 // impl<'a> EltOps<FpElt, FpElt> for &'a FpElt {}
-impl<'a, 'b> std::ops::Add<&'b FpElt> for &'a FpElt {
+impl<'b> std::ops::Add<&'b FpElt> for &FpElt {
     type Output = FpElt;
     fn add(self, other: &'b FpElt) -> FpElt {
         do_if_eq!(self.f == other.f, self.red(&self.n + &other.n), ERR_BIN_OP)
     }
 }
-impl<'a> std::ops::Add<FpElt> for &'a FpElt {
+impl std::ops::Add<FpElt> for &FpElt {
     type Output = FpElt;
     fn add(self, other: FpElt) -> FpElt {
         do_if_eq!(self.f == other.f, self.red(&self.n + &other.n), ERR_BIN_OP)
@@ -181,6 +225,13 @@ impl<'a> std::ops::Add<FpElt> for &'a FpElt {
 }
 
 impl FpElt {
+    /// Returns the integer in [0, p) that represents the element.
+    pub(crate) fn value(&self) -> &BigInt {
+        &self.n
+    }
+    pub(crate) fn field(&self) -> &Fp {
+        &self.f
+    }
     #[inline]
     fn red(&self, n: BigInt) -> FpElt {
         let n = n.mod_floor(&self.f.0.p);
@@ -217,7 +268,7 @@ impl_op_ex!(^|a: &FpElt, b: i32| -> FpElt {
     do_if_eq!(b == -1i32, a.inv_mod(), ERR_EXP_INV_OP)
 });
 
-impl<'a> Div<&'a FpElt> for u32 {
+impl Div<&FpElt> for u32 {
     type Output = FpElt;
     #[inline]
     fn div(self, other: &FpElt) -> Self::Output {
@@ -225,7 +276,7 @@ impl<'a> Div<&'a FpElt> for u32 {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigUint> for &'a FpElt {
+impl<'b> BitXor<&'b BigUint> for &FpElt {
     type Output = FpElt;
     #[inline]
     fn bitxor(self, exp: &'b BigUint) -> Self::Output {
@@ -234,7 +285,7 @@ impl<'a, 'b> BitXor<&'b BigUint> for &'a FpElt {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigInt> for &'a FpElt {
+impl<'b> BitXor<&'b BigInt> for &FpElt {
     type Output = FpElt;
     #[inline]
     fn bitxor(self, exp: &'b BigInt) -> Self::Output {
@@ -249,15 +300,13 @@ impl CMov for FpElt {}
 enum SqrtPrecmp {
     P3MOD4 { exp: BigInt },
     P5MOD8 { exp: BigInt, sqrt_minus_one: FpElt },
-    P9MOD16,
-    P1MOD16,
+    P1MOD8 { s: u64, exp: BigInt, z: FpElt },
 }
 impl Fp {
     fn get_sqrt_precmp(&self) -> SqrtPrecmp {
         self.0
             .sqrt_precmp
-            .borrow_mut()
-            .get_or_insert(self.calc_sqrt_precmp())
+            .get_or_init(|| self.calc_sqrt_precmp())
             .clone()
     }
     fn calc_sqrt_precmp(&self) -> SqrtPrecmp {
@@ -279,10 +328,18 @@ impl Fp {
                 exp,
                 sqrt_minus_one,
             }
-        } else if 9u32 == (res % 16u32) {
-            SqrtPrecmp::P9MOD16
         } else {
-            SqrtPrecmp::P1MOD16
+            // p-1 = 2^s*t, with t odd.
+            let p_minus_1 = p - 1u32;
+            let s = p_minus_1.trailing_zeros().unwrap();
+            let t = p_minus_1 >> s;
+            let exp = (&t - 1u32) >> 1usize;
+            let mut z = self.from(2u32);
+            while z.is_square() {
+                z = z + self.one();
+            }
+            let z = &z ^ &t;
+            SqrtPrecmp::P1MOD8 { s, exp, z }
         }
     }
 }
@@ -308,8 +365,33 @@ impl Sqrt for FpElt {
                 let t1 = &t0 * sqrt_minus_one;
                 FpElt::cmov(&t1, &t0, e)
             }
-            SqrtPrecmp::P9MOD16 => unimplemented!(),
-            SqrtPrecmp::P1MOD16 => unimplemented!(),
+            SqrtPrecmp::P1MOD8 { s, exp, z } => {
+                // Tonelli-Shanks algorithm.
+                let w = self ^ &exp; //  w = a^((t-1)/2)
+                let mut x = self * &w; // x = a^((t+1)/2)
+                let mut b = &x * &w; //  b = a^t
+                let mut c = z;
+                let mut m = s;
+                while !b.is_one() && !b.is_zero() {
+                    let mut i = 0;
+                    let mut b2 = b.clone();
+                    while !b2.is_one() && i < m {
+                        b2 = &b2 ^ 2u32;
+                        i += 1;
+                    }
+                    if i == m {
+                        break; // a is a non-square.
+                    }
+                    for _ in 0..(m - i - 1) {
+                        c = &c ^ 2u32;
+                    }
+                    x = x * &c;
+                    c = &c ^ 2u32;
+                    b = b * &c;
+                    m = i;
+                }
+                x
+            }
         }
     }
 }
@@ -2,23 +2,23 @@
 //!
 //! The quadraticfield module is meant to be used for bar.
 
-use atomic_refcell::AtomicRefCell;
 use num_bigint::{BigInt, BigUint};
 use num_traits::cast::ToPrimitive;
 use num_traits::identities::{One, Zero};
 
 use std::ops;
 use std::ops::{BitXor, Div};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use crate::do_if_eq;
 use crate::field::{CMov, Field, FieldElement, Sgn0, Sqrt};
-use crate::ops::{Deserialize, FromFactory, Serialize};
+use crate::ops::{Deserialize, FromFactory, Serialize, TryFromFactory};
 use crate::primefield::{Fp, FpElt};
+use crate::{DecodeError, Error};
 
 struct Params {
     base: Fp,
-    sqrt_precmp: AtomicRefCell<Option<SqrtPrecmp>>,
+    sqrt_precmp: OnceLock<SqrtPrecmp>,
 }
 
 impl Eq for Params {}
@@ -30,9 +30,17 @@ impl PartialEq for Params {
 }
 
 /// Fp implements a base field of prime characteristic.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct Fp2(Arc<Params>);
 
+impl Eq for Fp2 {}
+
+impl PartialEq for Fp2 {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
 impl Fp2 {
     /// Use `new` to generate a prime field instance.
     /// ```
@@ -43,7 +51,7 @@ impl Fp2 {
     /// The `modulus` should be a prime number.
     pub fn new(modulus: BigUint) -> Self {
         let base = Fp::new(modulus);
-        let sqrt_precmp = AtomicRefCell::new(None);
+        let sqrt_precmp = OnceLock::new();
         Fp2(Arc::new(Params { base, sqrt_precmp }))
     }
 }
@@ -72,27 +80,27 @@ impl Field for Fp2 {
 
 impl Deserialize for Fp2 {
     type Deser = <Fp2 as Field>::Elt;
-    fn from_bytes_be(&self, bytes: &[u8]) -> Result<Self::Deser, std::io::Error> {
+    fn from_bytes_be(&self, bytes: &[u8]) -> Result<Self::Deser, DecodeError> {
         let len = self.size_bytes();
         if len != bytes.len() {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "wrong size"));
+            return Err(DecodeError::WrongLength);
         }
         let size = len / 2;
-        let n0 = self.0.base.from_bytes_be(&bytes[0..size]).unwrap();
-        let n1 = self.0.base.from_bytes_be(&bytes[size..2 * size]).unwrap();
+        let n0 = self.0.base.from_bytes_be(&bytes[0..size])?;
+        let n1 = self.0.base.from_bytes_be(&bytes[size..2 * size])?;
         Ok(Fp2Elt {
             n: vec![n0, n1],
             f: self.clone(),
         })
     }
-    fn from_bytes_le(&self, bytes: &[u8]) -> Result<Self::Deser, std::io::Error> {
+    fn from_bytes_le(&self, bytes: &[u8]) -> Result<Self::Deser, DecodeError> {
         let len = self.size_bytes();
         if len != bytes.len() {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "wrong size"));
+            return Err(DecodeError::WrongLength);
         }
         let size = len / 2;
-        let n0 = self.0.base.from_bytes_le(&bytes[0..size]).unwrap();
-        let n1 = self.0.base.from_bytes_le(&bytes[size..2 * size]).unwrap();
+        let n0 = self.0.base.from_bytes_le(&bytes[0..size])?;
+        let n1 = self.0.base.from_bytes_le(&bytes[size..2 * size])?;
         Ok(Fp2Elt {
             n: vec![n0, n1],
             f: self.clone(),
@@ -118,13 +126,24 @@ impl_from_factory!(Fp2, <u8 u16 u32 u64 i8 i16 i32 i64>);
 impl FromFactory<&str> for Fp2 {
     type Output = <Fp2 as Field>::Elt;
     fn from(&self, s: &str) -> Self::Output {
-        let vs: Vec<&str> = s.splitn(2, ',').collect();
-        let n0: FpElt = self.0.base.from(vs[0]);
-        let n1: FpElt = self.0.base.from(vs[1]);
-        Fp2Elt {
+        match self.try_from(s) {
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+impl TryFromFactory<&str> for Fp2 {
+    type Output = <Fp2 as Field>::Elt;
+    /// Parses a string of the form `"a,b"` representing the element `a+ib`.
+    fn try_from(&self, s: &str) -> Result<Self::Output, Error> {
+        let (s0, s1) = s.split_once(',').ok_or(Error::InvalidString)?;
+        let n0: FpElt = self.0.base.try_from(s0)?;
+        let n1: FpElt = self.0.base.try_from(s1)?;
+        Ok(Fp2Elt {
             n: vec![n0, n1],
             f: self.clone(),
-        }
+        })
     }
 }
 
@@ -156,7 +175,7 @@ impl Serialize for Fp2Elt {
     }
 }
 
-impl<'a, 'b> std::ops::Add<&'b Fp2Elt> for &'a Fp2Elt {
+impl<'b> std::ops::Add<&'b Fp2Elt> for &Fp2Elt {
     type Output = Fp2Elt;
     fn add(self, other: &'b Fp2Elt) -> Fp2Elt {
         do_if_eq!(
@@ -166,7 +185,7 @@ impl<'a, 'b> std::ops::Add<&'b Fp2Elt> for &'a Fp2Elt {
         )
     }
 }
-impl<'a> std::ops::Add<Fp2Elt> for &'a Fp2Elt {
+impl std::ops::Add<Fp2Elt> for &Fp2Elt {
     type Output = Fp2Elt;
     fn add(self, other: Fp2Elt) -> Fp2Elt {
         do_if_eq!(
@@ -227,7 +246,7 @@ impl_op_ex!(^|a: &Fp2Elt, b: i32| -> Fp2Elt {
     do_if_eq!(b == -1i32, a.inv_mod(), ERR_EXP_INV_OP)
 });
 
-impl<'a> Div<&'a Fp2Elt> for u32 {
+impl Div<&Fp2Elt> for u32 {
     type Output = Fp2Elt;
     #[inline]
     fn div(self, other: &Fp2Elt) -> Self::Output {
@@ -235,7 +254,7 @@ impl<'a> Div<&'a Fp2Elt> for u32 {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigUint> for &'a Fp2Elt {
+impl<'b> BitXor<&'b BigUint> for &Fp2Elt {
     type Output = Fp2Elt;
     #[inline]
     fn bitxor(self, exp: &'b BigUint) -> Self::Output {
@@ -254,7 +273,7 @@ impl<'a, 'b> BitXor<&'b BigUint> for &'a Fp2Elt {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigInt> for &'a Fp2Elt {
+impl<'b> BitXor<&'b BigInt> for &Fp2Elt {
     type Output = Fp2Elt;
     #[inline]
     fn bitxor(self, exp: &'b BigInt) -> Self::Output {
@@ -274,8 +293,7 @@ impl Fp2 {
     fn get_sqrt_precmp(&self) -> SqrtPrecmp {
         self.0
             .sqrt_precmp
-            .borrow_mut()
-            .get_or_insert(self.calc_sqrt_precmp())
+            .get_or_init(|| self.calc_sqrt_precmp())
             .clone()
     }
     fn calc_sqrt_precmp(&self) -> SqrtPrecmp {
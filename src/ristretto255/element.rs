@@ -0,0 +1,24 @@
+//! This is documentation for the `element` module.
+//!
+//! The element module implements the group law of ristretto255 on top of the
+//! points of edwards25519.
+
+use impl_ops::impl_op_ex;
+use num_traits::identities::Zero;
+
+use std::ops;
+
+use crate::impl_quotient_element;
+use crate::ristretto255::Group;
+
+impl_quotient_element!(
+    group: Group,
+    name: "ristretto255",
+    curve: "edwards25519",
+    points: "four",
+    size: 32,
+    // the identity is represented by the points of order 1, 2, and 4.
+    is_zero: |p| p.c.x.is_zero() || p.c.y.is_zero(),
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-4.3.3
+    eq: |x1, y1, x2, y2| x1 * y2 == y1 * x2 || y1 * y2 == x1 * x2,
+);
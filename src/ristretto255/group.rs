@@ -0,0 +1,421 @@
+//! This is documentation for the `group` module.
+//!
+//! The group module implements the encoding, the decoding, and the one-way
+//! map of ristretto255 following RFC 9496.
+
+use num_bigint::{BigInt, Sign};
+use num_traits::identities::Zero;
+use sha2::Sha512;
+
+use std::sync::{Arc, OnceLock};
+
+use crate::edwards::{Curve, Point, ProyCoordinates};
+use crate::ellipticcurve::{Decode, DecodePolicy, EllipticCurve};
+use crate::expander::expand_message_xmd;
+use crate::field::{CMov, Field};
+use crate::impl_quotient_group;
+use crate::instances::{GetCurve, EDWARDS25519};
+use crate::ops::{Deserialize, FromFactory, Serialize};
+use crate::primefield::FpElt;
+use crate::ristretto255::Element;
+use crate::DecodeError;
+
+/// Group is the ristretto255 group, whose elements are represented by
+/// points of the edwards25519 curve. Its order is the prime
+/// r = 2^252+27742317777372353535851937790883648493.
+///
+/// Cloning a group is cheap, since clones share the same parameters.
+#[derive(Clone)]
+pub struct Group(Arc<Inner>);
+
+struct Inner {
+    ec: Curve,
+    d: FpElt,
+    exp: BigInt,
+    sqrt_m1: FpElt,
+    sqrt_ad_minus_one: FpElt,
+    invsqrt_a_minus_d: FpElt,
+    one_minus_d_sq: FpElt,
+    d_minus_one_sq: FpElt,
+}
+
+static GROUP: OnceLock<Group> = OnceLock::new();
+
+impl Group {
+    /// Returns the ristretto255 group. All the calls share the same
+    /// parameters, so comparing the groups obtained is cheap.
+    pub fn new() -> Self {
+        GROUP
+            .get_or_init(|| Group::build(EDWARDS25519.get()))
+            .clone()
+    }
+    /// Maps 64 uniformly random bytes to an element, such that the output
+    /// is uniformly distributed. It is the one-way map of RFC 9496.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-4.3.4
+    pub fn from_uniform_bytes(&self, bytes: &[u8; 64]) -> Element {
+        let p1 = self.map(&self.decode_field(&bytes[..32]));
+        let p2 = self.map(&self.decode_field(&bytes[32..]));
+        self.element(p1 + p2)
+    }
+    /// Hashes a message into an element using expand_message_xmd with
+    /// SHA-512 and the domain separation tag `dst`, followed by the one-way
+    /// map. This is the hash_to_ristretto255 function used by RFC 9497.
+    pub fn hash_to_group(&self, msg: &[u8], dst: &[u8]) -> Element {
+        let bytes = expand_message_xmd::<Sha512>(msg, dst, 64).unwrap();
+        self.from_uniform_bytes(bytes.as_slice().try_into().unwrap())
+    }
+    pub(super) fn element(&self, p: Point) -> Element {
+        let g = self.clone();
+        Element { g, p }
+    }
+    /// Encodes the point representing an element.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-4.3.2
+    pub(super) fn encode_point(&self, p: &Point) -> Vec<u8> {
+        let (x0, y0, z0, t0) = (&p.c.x, &p.c.y, &p.c.z, &p.c.t);
+        let u1 = (z0 + y0) * (z0 - y0);
+        let u2 = x0 * y0;
+        let (_, invsqrt) = self.sqrt_ratio_m1(&self.get_field().one(), &(&u1 * &(&u2 ^ 2u32)));
+        let den1 = &invsqrt * &u1;
+        let den2 = &invsqrt * &u2;
+        let z_inv = &den1 * &den2 * t0;
+        let ix0 = x0 * &self.0.sqrt_m1;
+        let iy0 = y0 * &self.0.sqrt_m1;
+        let enchanted_denominator = &den1 * &self.0.invsqrt_a_minus_d;
+        let rotate = is_negative(&(t0 * &z_inv));
+        let x = FpElt::cmov(x0, &iy0, rotate);
+        let y = FpElt::cmov(y0, &ix0, rotate);
+        let den_inv = FpElt::cmov(&den2, &enchanted_denominator, rotate);
+        let y = FpElt::cmov(&y, &-&y, is_negative(&(x * &z_inv)));
+        let s = abs(&(den_inv * (z0 - &y)));
+        s.to_bytes_le()
+    }
+    /// Returns whether u/v is a square, and the non-negative square root of
+    /// either u/v or sqrt(-1)*u/v.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-4.2
+    fn sqrt_ratio_m1(&self, u: &FpElt, v: &FpElt) -> (bool, FpElt) {
+        let v3 = &(v ^ 2u32) * v;
+        let v7 = &(&v3 ^ 2u32) * v;
+        let r = (u * &v3) * &(&(u * &v7) ^ &self.0.exp);
+        let check = v * &(&r ^ 2u32);
+        let minus_u = -u;
+        let correct_sign_sqrt = check == *u;
+        let flipped_sign_sqrt = check == minus_u;
+        let flipped_sign_sqrt_i = check == &minus_u * &self.0.sqrt_m1;
+        let r_prime = &self.0.sqrt_m1 * &r;
+        let r = FpElt::cmov(&r, &r_prime, flipped_sign_sqrt || flipped_sign_sqrt_i);
+        (correct_sign_sqrt || flipped_sign_sqrt, abs(&r))
+    }
+    /// Decodes a field element ignoring the most significant bit.
+    fn decode_field(&self, buf: &[u8]) -> FpElt {
+        let mut bytes = buf.to_vec();
+        bytes[31] &= 0x7f;
+        self.get_field()
+            .elt(BigInt::from_bytes_le(Sign::Plus, &bytes))
+    }
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-4.3.4
+    fn map(&self, t: &FpElt) -> Point {
+        let one = self.get_field().one();
+        let d = &self.0.d;
+        let r = &self.0.sqrt_m1 * &(t ^ 2u32);
+        let u = (&r + &one) * &self.0.one_minus_d_sq;
+        let v = (-&one - &r * d) * (&r + d);
+        let (was_square, s) = self.sqrt_ratio_m1(&u, &v);
+        let s_prime = -abs(&(&s * t));
+        let s = FpElt::cmov(&s_prime, &s, was_square);
+        let c = FpElt::cmov(&r, &-&one, was_square);
+        let n = c * (&r - &one) * &self.0.d_minus_one_sq - &v;
+        let w0 = (&s + &s) * &v;
+        let w1 = n * &self.0.sqrt_ad_minus_one;
+        let ss = &s ^ 2u32;
+        let w2 = &one - &ss;
+        let w3 = one + &ss;
+        self.0.ec.new_proy_point(ProyCoordinates {
+            x: &w0 * &w3,
+            y: &w2 * &w1,
+            t: w0 * w2,
+            z: w1 * w3,
+        })
+    }
+}
+
+impl Group {
+    fn build(ec: Curve) -> Group {
+        let f = ec.get_field();
+        let d = ec.coefficients().1.clone();
+        let one = f.one();
+        Group(Arc::new(Inner {
+            exp: (f.get_modulus() - 5u32) >> 3usize,
+            sqrt_m1: f.from(SQRT_M1),
+            sqrt_ad_minus_one: f.from(SQRT_AD_MINUS_ONE),
+            invsqrt_a_minus_d: f.from(INVSQRT_A_MINUS_D),
+            one_minus_d_sq: &one - &(&d ^ 2u32),
+            d_minus_one_sq: &(&d - &one) ^ 2u32,
+            d,
+            ec,
+        }))
+    }
+}
+
+impl_quotient_group!(
+    name: "ristretto255",
+    curve: "edwards25519",
+    order: 4,
+    generator: |ec| ec.get_generator(),
+);
+
+impl Decode for Group {
+    type Deser = Element;
+    /// Decodes an element following RFC 9496. Every element has a unique
+    /// encoding, and decoded elements are always in the prime-order group,
+    /// so the policy has no effect.
+    // based on https://www.rfc-editor.org/rfc/rfc9496.html#section-4.3.1
+    fn decode_with(&self, buf: &[u8], _: &DecodePolicy) -> Result<Self::Deser, DecodeError> {
+        let f = self.get_field();
+        let s = f.from_bytes_le(buf)?;
+        if is_negative(&s) {
+            return Err(DecodeError::NonCanonical);
+        }
+        let one = f.one();
+        let ss = &s ^ 2u32;
+        let u1 = &one - &ss;
+        let u2 = &one + &ss;
+        let u2_sqr = &u2 ^ 2u32;
+        let v = -(&self.0.d * &(&u1 ^ 2u32)) - &u2_sqr;
+        let (was_square, invsqrt) = self.sqrt_ratio_m1(&one, &(&v * &u2_sqr));
+        let den_x = &invsqrt * &u2;
+        let den_y = &invsqrt * &den_x * &v;
+        let x = abs(&((&s + &s) * &den_x));
+        let y = &u1 * &den_y;
+        let t = &x * &y;
+        if !was_square || is_negative(&t) || y.is_zero() {
+            return Err(DecodeError::NotOnCurve);
+        }
+        let p = self
+            .0
+            .ec
+            .new_proy_point(ProyCoordinates { x, y, t, z: one });
+        Ok(self.element(p))
+    }
+}
+
+const SQRT_M1: &str =
+    "19681161376707505956807079304988542015446066515923890162744021073123829784752";
+const SQRT_AD_MINUS_ONE: &str =
+    "25063068953384623474111414158702152701244531502492656460079210482610430750235";
+const INVSQRT_A_MINUS_D: &str =
+    "54469307008909316920995813868745141605393597292927456921205312896311721017578";
+
+#[cfg(test)]
+mod tests {
+    use crate::edwards::ProyCoordinates;
+    use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode};
+    use crate::expander::expand_message_xmd;
+    use crate::field::Field;
+    use crate::ristretto255::{Element, Group};
+    use crate::DecodeError;
+    use num_bigint::BigInt;
+    use sha2::Sha512;
+
+    // Encodings of the multiples [i]B of the generator, from RFC 9496
+    // Appendix A.1.
+    const MULTIPLES: [&str; 16] = [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+        "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+        "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+        "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+        "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+        "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+        "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+        "903293d8f2287ebe10e2374dc1a53e0bc887e592699f02d077d5263cdd55601c",
+        "02622ace8f7303a31cafc63f8fc48fdc16e1c8c8d234b2f0d6685282a9076031",
+        "20706fd788b2720a1ed2a5dad4952b01f413bcf0e7564de8cdc816689e2db95f",
+        "bce83f8ba5dd2fa572864c24ba1810f9522bc6004afe95877ac73241cafdab42",
+        "e4549ee16b9aa03099ca208c67adafcafa4c3f3e4e5303de6026e3ca8ff84460",
+        "aa52e000df2e16f55fb1032fc33bc42742dad6bd5a8fc0be0167436c5948501f",
+        "46376b80f409b29dc2b5f6f0c52591990896e5716f41477cd30085ab7f10301e",
+        "e0c418f7c8d9c4cdd7395b93ea124f3ad99021bb681dfc3302a9d99a2e53e64e",
+    ];
+
+    #[test]
+    fn constants() {
+        let g = Group::new();
+        let f = g.get_field();
+        let (a, d) = g.0.ec.coefficients();
+        let one = f.one();
+        assert!(&g.0.sqrt_m1 ^ 2u32 == -&one);
+        assert!(&g.0.sqrt_ad_minus_one ^ 2u32 == a * d - &one);
+        assert!(&(&g.0.invsqrt_a_minus_d ^ 2u32) * &(a - d) == one);
+    }
+
+    #[test]
+    fn rfc9496_vectors() {
+        let g = Group::new();
+        let gen = g.get_generator();
+        let mut p = g.identity();
+        for (i, enc) in MULTIPLES.iter().enumerate() {
+            let enc = hex::decode(enc).unwrap();
+            assert_eq!(p.encode(true), enc, "encoding of [{}]B", i);
+            let q = g.decode(&enc).unwrap();
+            assert!(q == p && g.is_on_curve(&q));
+            assert!(q == gen.clone() * g.new_scalar(BigInt::from(i)));
+            p = p + &gen;
+        }
+    }
+
+    #[test]
+    fn bad_encodings() {
+        let g = Group::new();
+        // Non-canonical field encodings.
+        for enc in [
+            "00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            // Negative field elements.
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "ed57ffd8c914fb201471d1c3d245ce3c746fcbe63a3679d51b6a516ebebe0e20",
+            "c34c4e1826e5d403b78e246e88aa051c36ccf0aafebffe137d148a2bf9104562",
+        ] {
+            let enc = hex::decode(enc).unwrap();
+            assert_eq!(g.decode(&enc).err(), Some(DecodeError::NonCanonical));
+        }
+        for enc in [
+            // Non-square x^2.
+            "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+            "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+            "de6a7b00deadc788eb6b6c8d20c0ae96c2f2019078fa604fee5b87d6e989ad7b",
+            "bcab477be20861e01e4a0e295284146a510150d9817763caf1a6f4b422d67042",
+            // Negative xy value.
+            "3eb858e78f5a7254d8c9731174a94f76755fd3941c0ac93735c07ba14579630e",
+            "a45fdc55c76448c049a1ab33f17023edfb2be3581e9c7aade8a6125215e04220",
+            "d483fe813c6ba647ebbfd3ec41adca1c6130c2beeee9d9bf065c8d151c5f396e",
+            "8a2e1d30050198c65a54483123960ccc38aef6848e1ec8f5f780e8523769ba32",
+            // s = -1, which causes y = 0.
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        ] {
+            let enc = hex::decode(enc).unwrap();
+            assert_eq!(g.decode(&enc).err(), Some(DecodeError::NotOnCurve));
+        }
+        assert_eq!(g.decode(&[0u8; 31]).err(), Some(DecodeError::WrongLength));
+    }
+
+    #[test]
+    fn one_way_map() {
+        let g = Group::new();
+        // Test vectors from RFC 9496 Appendix A.3.
+        for (input, output) in [
+            (
+                "5d1be09e3d0c82fc538112490e35701979d99e06ca3e2b5b54bffe8b4dc772c1\
+                 4d98b696a1bbfb5ca32c436cc61c16563790306c79eaca7705668b47dffe5bb6",
+                "3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46",
+            ),
+            (
+                "f116b34b8f17ceb56e8732a60d913dd10cce47a6d53bee9204be8b44f6678b27\
+                 0102a56902e2488c46120e9276cfe54638286b9e4b3cdb470b542d46c2068d38",
+                "f26e5b6f7d362d2d2a94c5d0e7602cb4773c95a2e5c31a64f133189fa76ed61b",
+            ),
+            (
+                "8422e1bbdaab52938b81fd602effb6f89110e1e57208ad12d9ad767e2e25510c\
+                 27140775f9337088b982d83d7fcf0b2fa1edffe51952cbe7365e95c86eaf325c",
+                "006ccd2a9e6867e6a2c5cea83d3302cc9de128dd2a9a57dd8ee7b9d7ffe02826",
+            ),
+            (
+                "ac22415129b61427bf464e17baee8db65940c233b98afce8d17c57beeb7876c2\
+                 150d15af1cb1fb824bbd14955f2b57d08d388aab431a391cfc33d5bafb5dbbaf",
+                "f8f0c87cf237953c5890aec3998169005dae3eca1fbb04548c635953c817f92a",
+            ),
+            (
+                "165d697a1ef3d5cf3c38565beefcf88c0f282b8e7dbd28544c483432f1cec767\
+                 5debea8ebb4e5fe7d6f6e5db15f15587ac4d4d4a1de7191e0c1ca6664abcc413",
+                "ae81e7dedf20a497e10c304a765c1767a42d6e06029758d2d7e8ef7cc4c41179",
+            ),
+            (
+                "a836e6c9a9ca9f1e8d486273ad56a78c70cf18f0ce10abb1c7172ddd605d7fd2\
+                 979854f47ae1ccf204a33102095b4200e5befc0465accc263175485f0e17ea5c",
+                "e2705652ff9f5e44d3e841bf1c251cf7dddb77d140870d1ab2ed64f1a9ce8628",
+            ),
+            (
+                "2cdc11eaeb95daf01189417cdddbf95952993aa9cb9c640eb5058d09702c7462\
+                 2c9965a697a3b345ec24ee56335b556e677b30e6f90ac77d781064f866a3c982",
+                "80bd07262511cdde4863f8a7434cef696750681cb9510eea557088f76d9e5065",
+            ),
+            // Inputs that are equivalent after masking the top bit and
+            // reducing modulo p.
+            (
+                "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+                 1200000000000000000000000000000000000000000000000000000000000000",
+                "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f",
+            ),
+            (
+                "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f\
+                 ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000080\
+                 ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+                "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f",
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000000\
+                 1200000000000000000000000000000000000000000000000000000000000080",
+                "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f",
+            ),
+        ] {
+            let input: [u8; 64] = hex::decode(input).unwrap().try_into().unwrap();
+            let p = g.from_uniform_bytes(&input);
+            assert!(g.is_on_curve(&p));
+            assert_eq!(hex::encode(p.encode(true)), output);
+        }
+
+        let (msg, dst) = (
+            b"abc",
+            b"QUUX-V01-CS02-with-ristretto255_XMD:SHA-512_R255MAP_RO_",
+        );
+        let bytes = expand_message_xmd::<Sha512>(msg, dst, 64).unwrap();
+        let p = g.from_uniform_bytes(&bytes.try_into().unwrap());
+        assert!(g.hash_to_group(msg, dst) == p);
+    }
+
+    #[test]
+    fn prime_order_group() {
+        let g = Group::new();
+        let ec = &g.0.ec;
+        let gen = g.get_generator();
+        let r = g.get_scalar_field().get_modulus();
+        assert!((gen.clone() * g.new_scalar(r - 1u32) + &gen).is_zero());
+        assert!(gen.is_torsion_free() && !gen.is_small_order());
+        assert!(gen.torsion_component() == g.identity());
+
+        // Points that differ by a point of order 4 represent the same element.
+        let f = g.get_field();
+        let (sqrt_m1, one) = (&g.0.sqrt_m1, f.one());
+        let t4 = ec.new_proy_point(ProyCoordinates {
+            x: sqrt_m1.clone(),
+            y: f.zero(),
+            t: f.zero(),
+            z: one,
+        });
+        for k in 1..4u32 {
+            let p: Element = gen.clone() * g.new_scalar(BigInt::from(k));
+            let q = g.element(&p.p + &t4);
+            assert!(p == q && p.encode(true) == q.encode(true));
+            assert!(g.element(t4.clone()).is_zero());
+        }
+
+        // The points outside of 2E do not represent any element.
+        let t8 = hex::decode("26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05");
+        let t8 = ec.decode(&t8.unwrap()).unwrap();
+        assert!(!t8.mul_int(&BigInt::from(4)).is_zero());
+        let (x, y) = {
+            let mut t = ec.get_generator() + &t8;
+            t.normalize();
+            (t.c.x, t.c.y)
+        };
+        assert!(g.try_new_point(x, y).is_err());
+        let (x, y) = (ec.get_generator().c.x, ec.get_generator().c.y);
+        assert!(g.try_new_point(x, y).unwrap() == gen);
+    }
+}
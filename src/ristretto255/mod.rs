@@ -0,0 +1,23 @@
+//! This is documentation for the `ristretto255` module.
+//!
+//! The ristretto255 module implements the prime-order group ristretto255
+//! specified in RFC 9496. Its elements are represented by points of
+//! edwards25519, but two points that differ by a point of order 4 represent
+//! the same element, so that there is no cofactor to take care of.
+//!
+//! ```
+//!  use redox_ecc::ellipticcurve::{Decode, EllipticCurve, Encode};
+//!  use redox_ecc::ristretto255::Group;
+//!
+//!  let g = Group::new();
+//!  let p = g.get_generator() * g.new_scalar(2.into());
+//!  let bytes = p.encode(true);
+//!  assert!(g.decode(&bytes).unwrap() == p);
+//! ```
+
+mod element;
+mod group;
+
+pub use crate::ristretto255::element::Element;
+pub use crate::ristretto255::group::Group;
+pub use crate::scalarfield::Scalar;
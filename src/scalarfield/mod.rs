@@ -0,0 +1,319 @@
+//! This is documentation for the `scalarfield` module.
+//!
+//! The scalarfield module implements the field of integers modulo the order
+//! of the prime-order subgroup of an elliptic curve. Its elements are the
+//! scalars used to multiply points.
+
+use impl_ops::impl_op_ex;
+use num_bigint::{BigInt, BigUint};
+use rand_core::{CryptoRng, RngCore};
+
+use std::ops;
+use std::ops::{BitXor, Div};
+
+use crate::ellipticcurve::EcScalar;
+use crate::field::{CMov, Field, FieldElement, Sgn0, Sqrt};
+use crate::ops::{Deserialize, FromFactory, Serialize, TryFromFactory};
+use crate::primefield::{Fp, FpElt};
+use crate::{DecodeError, Error};
+
+mod recoding;
+
+pub(crate) use crate::scalarfield::recoding::mul_int;
+
+/// Zq implements the field of integers modulo a prime q, where q is the
+/// order of a group.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Zq(Fp);
+
+impl Zq {
+    /// Use `new` to generate the scalar field of a group of order `q`.
+    /// ```
+    ///  use num_bigint::BigUint;
+    ///  use redox_ecc::scalarfield::Zq;
+    ///  let q = Zq::new(BigUint::from(101u32));
+    /// ```
+    /// The `order` should be a prime number.
+    pub fn new(order: BigUint) -> Self {
+        Zq(Fp::new(order))
+    }
+    /// Reduces a big-endian integer of any length modulo q. It is meant to
+    /// map uniform strings, e.g., of 64 or 114 bytes, into uniform scalars.
+    pub fn from_bytes_wide_be(&self, bytes: &[u8]) -> Scalar {
+        self.elt(BigUint::from_bytes_be(bytes).into())
+    }
+    /// Reduces a little-endian integer of any length modulo q. It is meant to
+    /// map uniform strings, e.g., of 64 or 114 bytes, into uniform scalars.
+    pub fn from_bytes_wide_le(&self, bytes: &[u8]) -> Scalar {
+        self.elt(BigUint::from_bytes_le(bytes).into())
+    }
+    /// Samples a scalar uniformly at random. The statistical distance to the
+    /// uniform distribution is at most 2^-128.
+    pub fn random<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Scalar {
+        let mut bytes = vec![0u8; self.size_bytes() + 16];
+        rng.fill_bytes(&mut bytes);
+        self.from_bytes_wide_be(&bytes)
+    }
+}
+
+impl Field for Zq {
+    type Elt = Scalar;
+    fn elt(&self, n: BigInt) -> Self::Elt {
+        Scalar(self.0.elt(n))
+    }
+    fn zero(&self) -> Self::Elt {
+        Scalar(self.0.zero())
+    }
+    fn one(&self) -> Self::Elt {
+        Scalar(self.0.one())
+    }
+    fn get_modulus(&self) -> BigInt {
+        self.0.get_modulus()
+    }
+    fn size_bytes(&self) -> usize {
+        self.0.size_bytes()
+    }
+}
+
+impl Deserialize for Zq {
+    type Deser = <Zq as Field>::Elt;
+    /// Decodes exactly `size_bytes()` bytes into a scalar, rejecting
+    /// values that are not reduced modulo q.
+    fn from_bytes_be(&self, bytes: &[u8]) -> Result<Self::Deser, DecodeError> {
+        self.0.from_bytes_be(bytes).map(Scalar)
+    }
+    fn from_bytes_le(&self, bytes: &[u8]) -> Result<Self::Deser, DecodeError> {
+        self.0.from_bytes_le(bytes).map(Scalar)
+    }
+}
+
+macro_rules! impl_from_factory {
+    ($target:ident, <$($other:ty)+> ) => {
+     $(
+         impl FromFactory<$other> for $target{
+            type Output = <Zq as Field>::Elt;
+            fn from(&self, n: $other) -> Self::Output{
+                Scalar(self.0.from(n))
+            }
+        }
+    )+
+    };
+}
+
+impl_from_factory!(Zq, <u8 u16 u32 u64 i8 i16 i32 i64 &str>);
+
+impl TryFromFactory<&str> for Zq {
+    type Output = <Zq as Field>::Elt;
+    fn try_from(&self, s: &str) -> Result<Self::Output, Error> {
+        self.0.try_from(s).map(Scalar)
+    }
+}
+
+/// Scalar is an element of the field of integers modulo q.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Scalar(FpElt);
+
+impl FieldElement for Scalar {}
+impl EcScalar for Scalar {}
+
+impl Serialize for Scalar {
+    /// serializes the scalar into big-endian bytes
+    fn to_bytes_be(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+    /// serializes the scalar into little-endian bytes
+    fn to_bytes_le(&self) -> Vec<u8> {
+        self.0.to_bytes_le()
+    }
+}
+
+impl Scalar {
+    /// Returns the field of the scalar.
+    pub fn get_field(&self) -> Zq {
+        Zq(self.0.field().clone())
+    }
+    pub(crate) fn value(&self) -> &BigInt {
+        self.0.value()
+    }
+}
+
+impl_op_ex!(+|a: &Scalar, b: &Scalar| -> Scalar { Scalar(&a.0 + &b.0) });
+impl_op_ex!(-|a: &Scalar, b: &Scalar| -> Scalar { Scalar(&a.0 - &b.0) });
+impl_op_ex!(*|a: &Scalar, b: &Scalar| -> Scalar { Scalar(&a.0 * &b.0) });
+impl_op_ex!(/|a: &Scalar, b: &Scalar| -> Scalar { Scalar(&a.0 / &b.0) });
+impl_op_ex!(-|a: &Scalar| -> Scalar { Scalar(-&a.0) });
+impl_op_ex!(^|a: &Scalar, b: u32| -> Scalar { Scalar(&a.0 ^ b) });
+impl_op_ex!(^|a: &Scalar, b: i32| -> Scalar { Scalar(&a.0 ^ b) });
+
+impl Div<&Scalar> for u32 {
+    type Output = Scalar;
+    #[inline]
+    fn div(self, other: &Scalar) -> Self::Output {
+        Scalar(self / &other.0)
+    }
+}
+
+impl<'b> BitXor<&'b BigUint> for &Scalar {
+    type Output = Scalar;
+    #[inline]
+    fn bitxor(self, exp: &'b BigUint) -> Self::Output {
+        Scalar(&self.0 ^ exp)
+    }
+}
+
+impl<'b> BitXor<&'b BigInt> for &Scalar {
+    type Output = Scalar;
+    #[inline]
+    fn bitxor(self, exp: &'b BigInt) -> Self::Output {
+        Scalar(&self.0 ^ exp)
+    }
+}
+
+impl CMov for Scalar {}
+
+impl Sqrt for Scalar {
+    fn is_square(&self) -> bool {
+        self.0.is_square()
+    }
+    fn sqrt(&self) -> Scalar {
+        Scalar(self.0.sqrt())
+    }
+}
+
+impl Sgn0 for Scalar {
+    fn sgn0(&self) -> i32 {
+        self.0.sgn0()
+    }
+}
+
+impl num_traits::identities::Zero for Scalar {
+    fn zero() -> Self {
+        unimplemented!()
+    }
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+    fn set_zero(&mut self) {
+        self.0.set_zero();
+    }
+}
+
+impl num_traits::identities::One for Scalar {
+    fn one() -> Self {
+        unimplemented!()
+    }
+    fn is_one(&self) -> bool {
+        self.0.is_one()
+    }
+    fn set_one(&mut self) {
+        self.0.set_one();
+    }
+}
+
+pub(crate) struct Iterino {
+    pub(crate) l: usize,
+    pub(crate) i: usize,
+    pub(crate) v: std::vec::Vec<u32>,
+    pub(crate) is_lr: bool,
+}
+
+impl std::iter::Iterator for Iterino {
+    type Item = bool;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i < self.l {
+            let bit = self.v.get(self.i / 32usize).unwrap_or(&0) >> (self.i % 32);
+            let b = (bit & 1) != 0;
+            if self.is_lr {
+                let (x, _) = self.i.overflowing_sub(1usize);
+                self.i = x
+            } else {
+                self.i += 1usize
+            }
+            Some(b)
+        } else {
+            None
+        }
+    }
+}
+
+impl Scalar {
+    /// Iterates over the bits of the scalar, from the most significant one.
+    pub fn iter_lr(&self) -> impl std::iter::Iterator<Item = bool> {
+        let l = self.value().bits() as usize;
+        let (i, _) = l.overflowing_sub(1usize);
+        let (_, v) = self.value().to_u32_digits();
+        let is_lr = true;
+        Iterino { l, i, v, is_lr }
+    }
+    /// Iterates over the bits of the scalar, from the least significant one.
+    pub fn iter_rl(&self) -> impl std::iter::Iterator<Item = bool> {
+        let l = self.value().bits() as usize;
+        let i = 0usize;
+        let (_, v) = self.value().to_u32_digits();
+        let is_lr = false;
+        Iterino { l, i, v, is_lr }
+    }
+}
+
+impl std::fmt::Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::fmt::Display for Zq {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Z/{}", self.0.get_modulus())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::instances::{GetCurve, EDWARDS25519, EDWARDS448, P256, P384};
+    use num_traits::identities::Zero;
+
+    #[test]
+    fn sqrt() {
+        // The orders of these groups are 3 mod 4, 5 mod 8, and 1 mod 8.
+        for q in [
+            P384.get().get_scalar_field(),
+            EDWARDS25519.get().get_scalar_field(),
+            P256.get().get_scalar_field(),
+        ] {
+            for i in 0..32u32 {
+                let x = q.from(i);
+                let x2 = &x ^ 2u32;
+                assert!(x2.is_square());
+                assert!(&x2.sqrt() ^ 2u32 == x2, "sqrt of {} in {}", x2, q);
+            }
+        }
+    }
+
+    #[test]
+    fn wide_reduction() {
+        let q = EDWARDS448.get().get_scalar_field();
+        let all_ones = q.from_bytes_wide_le(&[0xff; 114]);
+        let two: Scalar = q.from(2);
+        let expected = &(&two ^ &BigInt::from(912)) - &q.one();
+        assert!(all_ones == expected);
+        let k: Scalar = q.from(-1);
+        assert!(q.from_bytes_le(&k.to_bytes_le()).unwrap() == k);
+        assert!(q.from_bytes_wide_be(&k.to_bytes_be()) == k);
+        assert!(
+            q.from_bytes_be(&vec![0xff; q.size_bytes()]).err() == Some(DecodeError::NonCanonical)
+        );
+        assert!(q.from_bytes_be(&[0x00; 64]).err() == Some(DecodeError::WrongLength));
+    }
+
+    #[test]
+    fn random() {
+        let q = P256.get().get_scalar_field();
+        let mut rng = rand::thread_rng();
+        let k = q.random(&mut rng);
+        assert!(!k.is_zero());
+        assert!(&k * (1u32 / &k) == q.one());
+        assert!(k.get_field() == q);
+    }
+}
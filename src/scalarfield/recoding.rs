@@ -0,0 +1,266 @@
+//! This is documentation for the `recoding` module.
+//!
+//! The recoding module provides representations of scalars as sequences of
+//! signed digits. In all of them, digits are ordered from the least
+//! significant one, i.e., the i-th digit has weight 2^i, or 2^(wi) for
+//! recodings in radix 2^w.
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::cast::ToPrimitive;
+use num_traits::identities::{One, Zero};
+
+use crate::do_if_eq;
+use crate::field::Field;
+use crate::ops::{AddRef, SubRef};
+use crate::scalarfield::{Iterino, Scalar};
+
+impl Scalar {
+    /// Iterates over the bits of the scalar, from the most significant one.
+    /// Unlike `iter_lr`, the number of bits is always the bit length of the
+    /// order q, so the leading zeros are also produced.
+    pub fn iter_lr_fixed(&self) -> impl std::iter::Iterator<Item = bool> {
+        let l = self.order_bits();
+        let (i, _) = l.overflowing_sub(1usize);
+        let (_, v) = self.value().to_u32_digits();
+        let is_lr = true;
+        Iterino { l, i, v, is_lr }
+    }
+    /// Iterates over the bits of the scalar, from the least significant one.
+    /// Unlike `iter_rl`, the number of bits is always the bit length of the
+    /// order q, so the leading zeros are also produced.
+    pub fn iter_rl_fixed(&self) -> impl std::iter::Iterator<Item = bool> {
+        let l = self.order_bits();
+        let i = 0usize;
+        let (_, v) = self.value().to_u32_digits();
+        let is_lr = false;
+        Iterino { l, i, v, is_lr }
+    }
+    /// Returns the non-adjacent form (NAF) of the scalar, i.e., digits in
+    /// {-1,0,1} such that no two consecutive digits are non-zero.
+    pub fn naf(&self) -> Vec<i8> {
+        self.wnaf(2)
+    }
+    /// Returns the width-w non-adjacent form (wNAF) of the scalar, i.e.,
+    /// digits that are either zero or odd and in (-2^(w-1), 2^(w-1)), such
+    /// that at most one of any w consecutive digits is non-zero.
+    /// The width must be in the range [2, 7].
+    pub fn wnaf(&self, w: usize) -> Vec<i8> {
+        do_if_eq!((2..=7).contains(&w), wnaf(self.value(), w), ERR_WIDTH)
+    }
+    /// Returns the recoding of the scalar in radix 2^w using signed digits
+    /// in [-2^(w-1), 2^(w-1)]. The number of digits is always
+    /// ceil((b+1)/w), where b is the bit length of the order q.
+    /// The width must be in the range [1, 7].
+    pub fn signed_windows(&self, w: usize) -> Vec<i8> {
+        assert!((1..=7).contains(&w), "{}", ERR_WIDTH);
+        let radix = 1i32 << w;
+        let len = (self.order_bits() + 1).div_ceil(w);
+        let mut k = self.value().clone();
+        let mut digits = Vec::with_capacity(len);
+        for i in 0..len {
+            let mut d = low_bits(&k, w);
+            // the top digit absorbs the last carry and is at most 2^(w-1).
+            if d > radix / 2 || (d == radix / 2 && i + 1 < len) {
+                d -= radix;
+            }
+            k -= d;
+            k >>= w;
+            digits.push(d as i8);
+        }
+        digits
+    }
+    /// Returns the joint sparse form (JSF) of two scalars. It is the
+    /// representation of (k0,k1) with digits in {-1,0,1} that minimizes
+    /// the number of non-zero columns.
+    pub fn jsf(k0: &Scalar, k1: &Scalar) -> Vec<(i8, i8)> {
+        assert!(k0.get_field() == k1.get_field(), "{}", ERR_JSF);
+        let mut k = [k0.value().clone(), k1.value().clone()];
+        let mut d = [0i32; 2];
+        let mut digits = Vec::with_capacity(k0.order_bits() + 1);
+        while !(&k[0] + d[0]).is_zero() || !(&k[1] + d[1]).is_zero() {
+            let l = [d[0] + low_bits(&k[0], 3), d[1] + low_bits(&k[1], 3)];
+            let mut u = [0i32; 2];
+            for i in 0..2 {
+                if l[i] % 2 != 0 {
+                    u[i] = 2 - l[i].rem_euclid(4);
+                    let li = l[i].rem_euclid(8);
+                    if (li == 3 || li == 5) && l[1 - i].rem_euclid(4) == 2 {
+                        u[i] = -u[i];
+                    }
+                }
+            }
+            for i in 0..2 {
+                if 2 * d[i] == 1 + u[i] {
+                    d[i] = 1 - d[i];
+                }
+                k[i] >>= 1usize;
+            }
+            digits.push((u[0] as i8, u[1] as i8));
+        }
+        digits
+    }
+    fn order_bits(&self) -> usize {
+        self.get_field().get_modulus().bits() as usize
+    }
+}
+
+/// Multiplies a point by a non-negative integer k using its NAF, starting
+/// from the identity of the group and doubling with `double`. It implements
+/// `EcPoint::mul_int` for every point type.
+pub(crate) fn mul_int<P: AddRef + SubRef>(
+    p: &P,
+    k: &BigInt,
+    identity: P,
+    double: impl Fn(&P) -> P,
+) -> P {
+    let mut q = identity;
+    for d in wnaf(k, 2).into_iter().rev() {
+        q = double(&q);
+        match d {
+            1 => q = q + p,
+            -1 => q = q - p,
+            _ => {}
+        }
+    }
+    q
+}
+
+/// Returns the width-w non-adjacent form of a non-negative integer k.
+pub(crate) fn wnaf(k: &BigInt, w: usize) -> Vec<i8> {
+    let radix = 1i32 << w;
+    let mut k = k.clone();
+    let mut digits = Vec::with_capacity(k.bits() as usize + 1);
+    while !k.is_zero() {
+        let mut d = 0i32;
+        if k.is_odd() {
+            d = low_bits(&k, w);
+            if d >= radix / 2 {
+                d -= radix;
+            }
+            k -= d;
+        }
+        digits.push(d as i8);
+        k >>= 1usize;
+    }
+    digits
+}
+
+/// Returns k mod 2^w for a non-negative integer k.
+fn low_bits(k: &BigInt, w: usize) -> i32 {
+    let mask = (BigInt::one() << w) - 1u32;
+    (k & mask).to_i32().unwrap()
+}
+
+const ERR_WIDTH: &str = "window width out of range";
+const ERR_JSF: &str = "scalars of different groups";
+
+#[cfg(test)]
+mod tests {
+    use crate::ellipticcurve::{EcPoint, EllipticCurve};
+    use crate::field::Field;
+    use crate::instances::{GetCurve, CURVE25519, EDWARDS448, P256, P384};
+    use crate::scalarfield::Scalar;
+    use num_bigint::BigInt;
+
+    fn eval(digits: &[i8], w: usize) -> BigInt {
+        digits
+            .iter()
+            .rev()
+            .fold(BigInt::from(0), |acc, &d| (acc << w) + d)
+    }
+
+    fn scalars() -> Vec<Scalar> {
+        let mut rng = rand::thread_rng();
+        let mut ks = Vec::new();
+        for q in [
+            P256.get().get_scalar_field(),
+            P384.get().get_scalar_field(),
+            CURVE25519.get().get_scalar_field(),
+        ] {
+            ks.push(q.zero());
+            ks.push(q.one());
+            ks.push(q.elt(q.get_modulus() - 1));
+            ks.push(q.random(&mut rng));
+        }
+        ks
+    }
+
+    #[test]
+    fn fixed_bits() {
+        for k in scalars() {
+            let n = k.get_field().get_modulus().bits() as usize;
+            let lr: Vec<bool> = k.iter_lr_fixed().collect();
+            let mut rl: Vec<bool> = k.iter_rl_fixed().collect();
+            rl.reverse();
+            assert!(lr.len() == n && lr == rl);
+            let v = lr.iter().fold(BigInt::from(0), |acc, &b| 2 * acc + b as u8);
+            assert!(&v == k.value());
+        }
+    }
+
+    #[test]
+    fn wnaf() {
+        for k in scalars() {
+            assert!(k.naf() == k.wnaf(2));
+            for w in 2..=7 {
+                let digits = k.wnaf(w);
+                assert!(&eval(&digits, 1) == k.value(), "wnaf({}) of {}", w, k);
+                assert!(digits.last() != Some(&0));
+                for (i, &d) in digits.iter().enumerate() {
+                    if d != 0 {
+                        assert!(d % 2 != 0 && (d as i32).abs() < 1 << (w - 1));
+                        let next = &digits[i + 1..(i + w).min(digits.len())];
+                        assert!(next.iter().all(|&d| d == 0));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn signed_windows() {
+        for k in scalars() {
+            let n = k.get_field().get_modulus().bits() as usize;
+            for w in 1..=7 {
+                let digits = k.signed_windows(w);
+                let half = 1i32 << (w - 1);
+                assert!(digits.len() == (n + 1).div_ceil(w));
+                assert!(digits.iter().all(|&d| (d as i32).abs() <= half));
+                assert!(&eval(&digits, w) == k.value(), "windows({}) of {}", w, k);
+            }
+        }
+    }
+
+    #[test]
+    fn jsf() {
+        let ks = scalars();
+        for pair in ks.chunks(2) {
+            let (k0, k1) = (&pair[0], &pair[1]);
+            let digits = Scalar::jsf(k0, k1);
+            let (d0, d1): (Vec<i8>, Vec<i8>) = digits.iter().cloned().unzip();
+            assert!(&eval(&d0, 1) == k0.value());
+            assert!(&eval(&d1, 1) == k1.value());
+            // of any three consecutive columns, at least one is zero.
+            for c in digits.windows(3) {
+                assert!(c.iter().any(|&(a, b)| a == 0 && b == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn mul_int() {
+        // unlike scalars, integers are not reduced modulo r.
+        let ec = EDWARDS448.get();
+        let fq = ec.get_scalar_field();
+        let g = ec.get_generator();
+        let r = fq.get_modulus();
+        assert!(g.mul_int(&BigInt::from(0)).is_zero());
+        assert!(g.mul_int(&r).is_zero());
+        assert!(g.mul_int(&(&r + 5)) == &g * &fq.elt(5.into()));
+        for k in scalars().into_iter().take(4) {
+            let g = P256.get().get_generator();
+            assert!(g.mul_int(k.value()) == &g * &k);
+        }
+    }
+}
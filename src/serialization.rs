@@ -0,0 +1,610 @@
+//! This is documentation for the `serialization` module.
+//!
+//! The serialization module implements the traits of serde for field
+//! elements, scalars, points, and curve identifiers. It is enabled by the
+//! `serde` feature.
+//!
+//! Binary formats get octet-strings: field elements and scalars are encoded
+//! in big-endian order, and points use their compressed encoding. Montgomery
+//! points are the exception, as their u-coordinate does not determine the
+//! point, so they use the compressed format of SEC1. Human-readable formats
+//! get the same octet-strings encoded in hex, or in base64 with the [`Base64`]
+//! adapter.
+//!
+//! Elements are deserialized with the help of their field, or curve, which is
+//! passed as a `DeserializeSeed`. Strings are read as hex, or as base64 when
+//! the seed is wrapped in [`Base64`]; the encoding is never guessed from the
+//! string, as some base64 strings are also valid hex.
+//!
+//! ```
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use serde::de::DeserializeSeed;
+//!
+//!  let ec = P256.get();
+//!  let g = ec.get_generator();
+//!  let json = serde_json::to_string(&g).unwrap();
+//!  let mut de = serde_json::Deserializer::from_str(&json);
+//!  let p = (&ec).deserialize(&mut de).unwrap();
+//!  assert!(p == g);
+//! ```
+//!
+//! Points that must be read without context, e.g., from configuration files,
+//! can be wrapped in a [`CurvePoint`], which also records the curve.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::ellipticcurve::{Decode, DecodePolicy, EllipticCurve, Encode};
+use crate::instances::{
+    EdCurveID, GetCurve, MtCurveID, WeCurveID, BLS12381G1, BLS12381G1_11ISO, CURVE25519, CURVE448,
+    EDWARDS25519, EDWARDS448, P256, P384, P521, SECP256K1, SECP256K1_3ISO,
+};
+use crate::keys::Jwk;
+use crate::ops::{Deserialize, Serialize};
+use crate::primefield::{Fp, FpElt};
+use crate::quadraticfield::{Fp2, Fp2Elt};
+use crate::scalarfield::{Scalar, Zq};
+use crate::DecodeError;
+use crate::{decaf448, edwards, montgomery, ristretto255, weierstrass};
+
+/// Octets is implemented by the types serialized as octet-strings.
+trait Octets {
+    fn octets(&self) -> Vec<u8>;
+}
+
+/// DecodeOctets is implemented by the fields and curves that deserialize
+/// octet-strings into their elements.
+trait DecodeOctets {
+    type Value;
+    fn decode_octets(&self, buf: &[u8]) -> Result<Self::Value, DecodeError>;
+}
+
+fn serialize_octets<S: Serializer>(buf: &[u8], s: S) -> Result<S::Ok, S::Error> {
+    if s.is_human_readable() {
+        s.serialize_str(&hex::encode(buf))
+    } else {
+        s.serialize_bytes(buf)
+    }
+}
+
+macro_rules! impl_serde {
+    ($($ctx:ty => $elt:ty,
+       |$e:ident| $octets:expr,
+       |$c:ident, $buf:ident| $decode_octets:expr;)+) => {$(
+        impl Octets for $elt {
+            fn octets(&self) -> Vec<u8> {
+                let $e = self;
+                $octets
+            }
+        }
+
+        impl serde::Serialize for $elt {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                serialize_octets(&self.octets(), s)
+            }
+        }
+
+        impl serde::Serialize for Base64<$elt> {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize_base64(s)
+            }
+        }
+
+        impl serde::Serialize for Base64<&$elt> {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize_base64(s)
+            }
+        }
+
+        impl DecodeOctets for $ctx {
+            type Value = $elt;
+            fn decode_octets(&self, $buf: &[u8]) -> Result<Self::Value, DecodeError> {
+                let $c = self;
+                $decode_octets
+            }
+        }
+
+        impl<'de> DeserializeSeed<'de> for &$ctx {
+            type Value = $elt;
+            fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+                <Raw as serde::Deserialize>::deserialize(d)?.decode(self, Text::Hex)
+            }
+        }
+
+        impl<'de> DeserializeSeed<'de> for Base64<&$ctx> {
+            type Value = $elt;
+            fn deserialize<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+                <Raw as serde::Deserialize>::deserialize(d)?.decode(self.0, Text::Base64)
+            }
+        }
+    )+};
+}
+
+impl_serde!(
+    Fp => FpElt, |e| e.to_bytes_be(), |f, buf| f.from_bytes_be(buf);
+    Fp2 => Fp2Elt, |e| e.to_bytes_be(), |f, buf| f.from_bytes_be(buf);
+    Zq => Scalar, |k| k.to_bytes_be(), |f, buf| f.from_bytes_be(buf);
+    weierstrass::Curve => weierstrass::Point, |p| p.encode(true), |ec, buf| ec.decode(buf);
+    edwards::Curve => edwards::Point, |p| p.encode(true), |ec, buf| ec.decode(buf);
+    montgomery::Curve => montgomery::Point,
+        |p| p.encode_sec1(true),
+        |ec, buf| ec.decode_sec1(buf, &DecodePolicy::default());
+    ristretto255::Group => ristretto255::Element, |p| p.encode(true), |g, buf| g.decode(buf);
+    decaf448::Group => decaf448::Element, |p| p.encode(true), |g, buf| g.decode(buf);
+);
+
+/// Base64 serializes field elements, scalars, and points in base64 for
+/// human-readable formats. Wrapping a field or a curve gives the seed that
+/// deserializes them from base64. Binary formats are not affected.
+/// ```
+///  use redox_ecc::ellipticcurve::EllipticCurve;
+///  use redox_ecc::instances::{GetCurve, EDWARDS25519};
+///  use redox_ecc::serialization::Base64;
+///  use serde::de::DeserializeSeed;
+///
+///  let ec = EDWARDS25519.get();
+///  let g = ec.get_generator();
+///  let json = serde_json::to_string(&Base64(&g)).unwrap();
+///  assert_eq!(json, "\"WGZmZmZmZmZmZmZmZmZmZmZmZmZmZmZmZmZmZmZmZmY=\"");
+///  let mut de = serde_json::Deserializer::from_str(&json);
+///  assert!(Base64(&ec).deserialize(&mut de).unwrap() == g);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Base64<T>(pub T);
+
+trait SerializeBase64 {
+    fn serialize_base64<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error>;
+}
+
+impl<T: Octets> SerializeBase64 for T {
+    fn serialize_base64<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let buf = self.octets();
+        if s.is_human_readable() {
+            s.serialize_str(&BASE64.encode(buf))
+        } else {
+            s.serialize_bytes(&buf)
+        }
+    }
+}
+
+/// Text is the encoding of octet-strings in human-readable formats.
+#[derive(Clone, Copy)]
+enum Text {
+    Hex,
+    Base64,
+}
+
+/// Raw holds an octet-string as it was read, i.e., either as bytes or as a
+/// string that still has to be decoded from hex or base64.
+enum Raw {
+    Bytes(Vec<u8>),
+    Text(String),
+}
+
+impl Raw {
+    fn decode<C: DecodeOctets, E: de::Error>(self, ctx: &C, text: Text) -> Result<C::Value, E> {
+        let buf = match self {
+            Raw::Bytes(buf) => buf,
+            Raw::Text(s) => {
+                let buf = match text {
+                    Text::Hex => hex::decode(&s).ok(),
+                    Text::Base64 => BASE64.decode(&s).ok(),
+                };
+                buf.ok_or_else(|| E::invalid_value(Unexpected::Str(&s), &RawVisitor))?
+            }
+        };
+        ctx.decode_octets(&buf).map_err(E::custom)
+    }
+}
+
+struct RawVisitor;
+
+impl<'de> Visitor<'de> for RawVisitor {
+    type Value = Raw;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an octet-string, or its hex or base64 encoding")
+    }
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Raw, E> {
+        Ok(Raw::Bytes(v.to_vec()))
+    }
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Raw, E> {
+        Ok(Raw::Bytes(v))
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Raw, E> {
+        Ok(Raw::Text(v.to_string()))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Raw, A::Error> {
+        let mut buf = Vec::new();
+        while let Some(b) = seq.next_element::<u8>()? {
+            buf.push(b);
+        }
+        Ok(Raw::Bytes(buf))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Raw {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        if d.is_human_readable() {
+            d.deserialize_any(RawVisitor)
+        } else {
+            d.deserialize_bytes(RawVisitor)
+        }
+    }
+}
+
+macro_rules! impl_serde_curve_id {
+    ($($id:ty => [$($c:ident),+];)+) => {$(
+        /// Serializes the identifier as the name of the curve.
+        impl serde::Serialize for $id {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $id {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let name = <String as serde::Deserialize>::deserialize(d)?;
+                [$($c),+]
+                    .into_iter()
+                    .find(|id| id.to_string() == name)
+                    .ok_or_else(|| {
+                        de::Error::invalid_value(Unexpected::Str(&name), &"the name of a curve")
+                    })
+            }
+        }
+
+        impl serde::Serialize for CurvePoint<$id> {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                let mut st = s.serialize_struct("CurvePoint", 2)?;
+                st.serialize_field("curve", &self.curve)?;
+                st.serialize_field("point", &self.point)?;
+                st.end()
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for CurvePoint<$id> {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_struct("CurvePoint", FIELDS, CurvePointVisitor(PhantomData))
+            }
+        }
+    )+};
+}
+
+impl_serde_curve_id!(
+    WeCurveID => [P256, P384, P521, SECP256K1, SECP256K1_3ISO, BLS12381G1, BLS12381G1_11ISO];
+    EdCurveID => [EDWARDS25519, EDWARDS448];
+    MtCurveID => [CURVE25519, CURVE448];
+);
+
+/// CurvePoint is a point together with the identifier of its curve. Unlike
+/// points, it can be deserialized without context, so it can be embedded in
+/// configuration files as `{"curve": "P256", "point": "03..."}`.
+/// ```
+///  use redox_ecc::ellipticcurve::EllipticCurve;
+///  use redox_ecc::instances::{GetCurve, WeCurveID, P256};
+///  use redox_ecc::serialization::CurvePoint;
+///
+///  let json = r#"{"curve": "P256", "point": "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"}"#;
+///  let cp: CurvePoint<WeCurveID> = serde_json::from_str(json).unwrap();
+///  assert!(cp.curve == P256 && cp.point == P256.get().get_generator());
+/// ```
+pub struct CurvePoint<I: GetCurve> {
+    pub curve: I,
+    pub point: <I::E as EllipticCurve>::Point,
+}
+
+const FIELDS: &[&str] = &["curve", "point"];
+
+enum Field {
+    Curve,
+    Point,
+    Other,
+}
+
+impl<'de> serde::Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Ok(
+            match <String as serde::Deserialize>::deserialize(d)?.as_str() {
+                "curve" => Field::Curve,
+                "point" => Field::Point,
+                _ => Field::Other,
+            },
+        )
+    }
+}
+
+struct CurvePointVisitor<I>(PhantomData<I>);
+
+impl<'de, I> Visitor<'de> for CurvePointVisitor<I>
+where
+    I: GetCurve + serde::Deserialize<'de>,
+    I::E: DecodeOctets<Value = <I::E as EllipticCurve>::Point>,
+{
+    type Value = CurvePoint<I>;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a curve identifier and a point")
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let curve: I = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let point: Raw = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let point = point.decode(&curve.get(), Text::Hex)?;
+        Ok(CurvePoint { curve, point })
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        // the point is decoded at the end, since it may come before the curve.
+        let (mut curve, mut point): (Option<I>, Option<Raw>) = (None, None);
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Curve if curve.is_some() => return Err(de::Error::duplicate_field("curve")),
+                Field::Point if point.is_some() => return Err(de::Error::duplicate_field("point")),
+                Field::Curve => curve = Some(map.next_value()?),
+                Field::Point => point = Some(map.next_value()?),
+                Field::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        let curve = curve.ok_or_else(|| de::Error::missing_field("curve"))?;
+        let point = point.ok_or_else(|| de::Error::missing_field("point"))?;
+        let point = point.decode(&curve.get(), Text::Hex)?;
+        Ok(CurvePoint { curve, point })
+    }
+}
+
+/// Serializes the key as a JSON object with its members kty, crv, x, y, and
+/// d, the last two being omitted when absent.
+impl serde::Serialize for Jwk {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let len = 3 + self.y.is_some() as usize + self.d.is_some() as usize;
+        let mut st = s.serialize_struct("Jwk", len)?;
+        st.serialize_field("kty", &self.kty)?;
+        st.serialize_field("crv", &self.crv)?;
+        st.serialize_field("x", &self.x)?;
+        for (key, value) in [("y", &self.y), ("d", &self.d)] {
+            match value {
+                Some(v) => st.serialize_field(key, v)?,
+                None => st.skip_field(key)?,
+            }
+        }
+        st.end()
+    }
+}
+
+/// Deserializes a JSON object, ignoring the members other than kty, crv, x,
+/// y, and d, e.g., kid or use.
+impl<'de> serde::Deserialize<'de> for Jwk {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_map(JwkVisitor)
+    }
+}
+
+struct JwkVisitor;
+
+impl<'de> Visitor<'de> for JwkVisitor {
+    type Value = Jwk;
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a JSON Web Key")
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let (mut kty, mut crv, mut x) = (None, None, None);
+        let mut jwk = Jwk::default();
+        while let Some(key) = map.next_key::<String>()? {
+            let member = match key.as_str() {
+                "kty" => &mut kty,
+                "crv" => &mut crv,
+                "x" => &mut x,
+                "y" => &mut jwk.y,
+                "d" => &mut jwk.d,
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                    continue;
+                }
+            };
+            if member.is_some() {
+                return Err(de::Error::custom(format!("duplicate member {}", key)));
+            }
+            *member = Some(map.next_value()?);
+        }
+        jwk.kty = kty.ok_or_else(|| de::Error::missing_field("kty"))?;
+        jwk.crv = crv.ok_or_else(|| de::Error::missing_field("crv"))?;
+        jwk.x = x.ok_or_else(|| de::Error::missing_field("x"))?;
+        Ok(jwk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use serde::de::DeserializeSeed;
+    use serde::Deserialize as _;
+
+    use super::{Base64, CurvePoint};
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::field::Field;
+    use crate::instances::{
+        EdCurveID, GetCurve, MtCurveID, WeCurveID, CURVE25519, CURVE448, EDWARDS25519, EDWARDS448,
+        P256, P384, SECP256K1,
+    };
+    use crate::keys::{Jwk, PublicKey};
+    use crate::ops::TryFromFactory;
+    use crate::quadraticfield::Fp2;
+    use crate::{decaf448, ristretto255};
+
+    fn from_json<'de, S: DeserializeSeed<'de>>(seed: S, json: &'de str) -> Option<S::Value> {
+        let mut de = serde_json::Deserializer::from_str(json);
+        seed.deserialize(&mut de).ok()
+    }
+
+    fn from_postcard<'de, S: DeserializeSeed<'de>>(seed: S, buf: &'de [u8]) -> Option<S::Value> {
+        let mut de = postcard::Deserializer::from_bytes(buf);
+        seed.deserialize(&mut de).ok()
+    }
+
+    macro_rules! round_trip {
+        ($ctx:expr, $elt:expr) => {{
+            let (ctx, elt) = (&$ctx, &$elt);
+            let json = serde_json::to_string(elt).unwrap();
+            assert!(from_json(ctx, &json).unwrap() == *elt, "json: {}", json);
+            let json = serde_json::to_string(&Base64(elt)).unwrap();
+            assert!(
+                from_json(Base64(ctx), &json).unwrap() == *elt,
+                "base64: {}",
+                json
+            );
+            let buf = postcard::to_allocvec(elt).unwrap();
+            assert!(from_postcard(ctx, &buf).unwrap() == *elt);
+            assert!(from_postcard(Base64(ctx), &buf).unwrap() == *elt);
+            assert!(postcard::to_allocvec(&Base64(elt)).unwrap() == buf);
+        }};
+    }
+
+    #[test]
+    fn fields() {
+        let ec = P256.get();
+        let f = ec.get_field();
+        let x = f.elt(7.into());
+        round_trip!(f, x);
+        let json = serde_json::to_string(&x).unwrap();
+        let expected = format!("\"{}07\"", "00".repeat(31));
+        assert_eq!(json, expected);
+        let buf = postcard::to_allocvec(&x).unwrap();
+        assert_eq!(buf.len(), 1 + 32);
+
+        let fq = ec.get_scalar_field();
+        let k = -fq.elt(5.into());
+        round_trip!(fq, k);
+
+        let fp2 = Fp2::new(BigUint::from(103u32));
+        let z = fp2.try_from("3,4").unwrap();
+        round_trip!(fp2, z);
+    }
+
+    #[test]
+    fn points() {
+        for id in [P256, P384, SECP256K1] {
+            let ec = id.get();
+            let g = ec.get_generator();
+            round_trip!(ec, g);
+            round_trip!(ec, ec.identity());
+        }
+        for id in [EDWARDS25519, EDWARDS448] {
+            let ec = id.get();
+            round_trip!(ec, ec.get_generator());
+        }
+        for id in [CURVE25519, CURVE448] {
+            let ec = id.get();
+            let g = ec.get_generator();
+            round_trip!(ec, g);
+            round_trip!(ec, -g);
+        }
+        let g = ristretto255::Group::new();
+        round_trip!(g, g.get_generator());
+        let g = decaf448::Group::new();
+        round_trip!(g, g.get_generator());
+    }
+
+    #[test]
+    fn bad_inputs() {
+        let ec = P256.get();
+        let f = ec.get_field();
+        // wrong length, invalid tag, and malformed inputs.
+        assert!(from_json(&f, "\"0102\"").is_none());
+        assert!(from_json(&ec, &format!("\"05{}\"", "00".repeat(32))).is_none());
+        assert!(from_json(Base64(&f), "\"not base64!\"").is_none());
+        assert!(from_json(Base64(&f), "\"0102\"").is_none());
+        assert!(from_json(&f, "17").is_none());
+        // a field element encoded as an array of bytes is accepted.
+        let json = serde_json::to_string(&[0u8; 32]).unwrap();
+        assert!(from_json(&f, &json).unwrap() == f.zero());
+
+        // the base64 encoding of 48 zeros only has hex digits, but it is not
+        // the hex encoding of the element.
+        let f = P384.get().get_field();
+        let json = serde_json::to_string(&Base64(f.zero())).unwrap();
+        assert_eq!(json, format!("\"{}\"", "A".repeat(64)));
+        assert!(from_json(Base64(&f), &json).unwrap() == f.zero());
+        assert!(from_json(&f, &json).is_none());
+    }
+
+    #[test]
+    fn curve_ids() {
+        let json = serde_json::to_string(&P256).unwrap();
+        assert_eq!(json, "\"P256\"");
+        assert!(serde_json::from_str::<WeCurveID>(&json).unwrap() == P256);
+        let json = serde_json::to_string(&CURVE448).unwrap();
+        assert!(serde_json::from_str::<MtCurveID>(&json).unwrap() == CURVE448);
+        assert!(serde_json::from_str::<EdCurveID>(&json).is_err());
+        assert!(serde_json::from_str::<WeCurveID>("\"P257\"").is_err());
+    }
+
+    #[test]
+    fn embedded_points() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Config {
+            name: String,
+            key: CurvePoint<EdCurveID>,
+        }
+
+        let ec = EDWARDS448.get();
+        let g = ec.get_generator();
+        let p = &g + &g;
+        let cfg = Config {
+            name: String::from("server"),
+            key: CurvePoint {
+                curve: EDWARDS448,
+                point: p.clone(),
+            },
+        };
+        let json = serde_json::to_string(&cfg).unwrap();
+        let got: Config = serde_json::from_str(&json).unwrap();
+        assert!(got.name == cfg.name && got.key.curve == EDWARDS448 && got.key.point == p);
+
+        let buf = postcard::to_allocvec(&cfg).unwrap();
+        let got: Config = postcard::from_bytes(&buf).unwrap();
+        assert!(got.key.point == p);
+
+        // the point may come before the curve, and it is read as hex.
+        let hex = serde_json::to_string(&p).unwrap();
+        let b64 = serde_json::to_string(&Base64(&p)).unwrap();
+        for (point, ok) in [(hex, true), (b64, false)] {
+            let json = format!(r#"{{"point": {}, "curve": "edwards448"}}"#, point);
+            let got = CurvePoint::<EdCurveID>::deserialize(
+                &mut serde_json::Deserializer::from_str(&json),
+            );
+            assert!(got.map(|cp| cp.point == p).unwrap_or(false) == ok);
+        }
+        let json = r#"{"curve": "edwards25519", "point": "00"}"#;
+        assert!(serde_json::from_str::<CurvePoint<EdCurveID>>(json).is_err());
+        assert!(
+            serde_json::from_str::<CurvePoint<EdCurveID>>(r#"{"curve": "edwards25519"}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn jwk() {
+        // RFC 7517 (Appendix A.1), with additional members.
+        let json = r#"{"kty":"EC","crv":"P-256",
+            "x":"MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+            "y":"4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+            "use":"enc","kid":"1"}"#;
+        let jwk: Jwk = serde_json::from_str(json).unwrap();
+        let key = PublicKey::from_jwk(&jwk).unwrap();
+        let out = serde_json::to_string(&key.to_jwk().unwrap()).unwrap();
+        let expected = r#"{"kty":"EC","crv":"P-256","x":"MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4","y":"4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM"}"#;
+        assert_eq!(out, expected);
+
+        let json = r#"{"kty":"OKP","crv":"Ed25519","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo","x":"a"}"#;
+        assert!(serde_json::from_str::<Jwk>(json).is_err());
+        assert!(serde_json::from_str::<Jwk>(r#"{"kty":"OKP","crv":"Ed25519"}"#).is_err());
+    }
+}
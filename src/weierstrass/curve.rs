@@ -2,146 +2,169 @@
 //!
 //! The curve module is meant to be used for bar.
 
-use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use num_bigint::{BigInt, BigUint, ToBigInt};
 use num_traits::identities::Zero;
 
-use std::io::{Error, ErrorKind};
 use std::str::FromStr;
+use std::sync::Arc;
 
-use crate::do_if_eq;
-use crate::ellipticcurve::{Decode, EllipticCurve};
+use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve};
 use crate::field::{Field, Sgn0, Sqrt};
-use crate::ops::FromFactory;
+use crate::ops::{Deserialize, FromFactory};
 use crate::primefield::{Fp, FpElt};
+use crate::scalarfield::{Scalar, Zq};
 use crate::weierstrass::point::{Point, ProyCoordinates};
-use crate::weierstrass::scalar::Scalar;
+use crate::{DecodeError, Error};
 
 /// This is an elliptic curve defined by the Weierstrass equation `y^2=x^3+ax+b`.
 ///
 /// **Atention** This implementation only supports curves of prime order.
-#[derive(Clone, std::cmp::PartialEq)]
-pub struct Curve {
-    f: Fp,
+///
+/// Cloning a curve is cheap, since clones share the same curve parameters.
+#[derive(Clone)]
+pub struct Curve(pub(super) Arc<Inner>);
+
+#[derive(PartialEq)]
+pub(super) struct Inner {
+    pub(super) f: Fp,
     pub(super) a: FpElt,
     pub(super) b: FpElt,
-    pub(super) r: BigUint,
+    pub(super) q: Zq,
     pub(super) gx: FpElt,
     pub(super) gy: FpElt,
     pub(super) h: BigUint,
 }
+
 impl Curve {
-    pub(crate) fn new_proy_point(&self, c: ProyCoordinates) -> Point {
+    /// Creates a point without checking the curve equation. The check is
+    /// only performed on debug builds.
+    pub(super) fn new_proy_point_unchecked(&self, c: ProyCoordinates) -> Point {
         let e = self.clone();
         let pt = Point { e, c };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        debug_assert!(self.is_on_curve(&pt), "{}", ERR_ECC_NEW);
+        pt
+    }
+}
+
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
     }
 }
 
 impl EllipticCurve for Curve {
     type F = Fp;
     type Scalar = Scalar;
+    type ScalarField = Zq;
     type Point = Point;
-    fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point {
+    fn try_new_point(
+        &self,
+        x: <Self::F as Field>::Elt,
+        y: <Self::F as Field>::Elt,
+    ) -> Result<Self::Point, Error> {
         let e = self.clone();
         let f = e.get_field();
         let pt = Point {
             c: ProyCoordinates { x, y, z: f.one() },
             e,
         };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        if self.is_on_curve(&pt) {
+            Ok(pt)
+        } else {
+            Err(Error::NotOnCurve)
+        }
     }
     fn new_scalar(&self, k: BigInt) -> Self::Scalar {
-        Scalar::new(k, &self.r)
+        self.0.q.elt(k)
     }
     fn identity(&self) -> Self::Point {
-        self.new_proy_point(ProyCoordinates {
-            x: self.f.zero(),
-            y: self.f.one(),
-            z: self.f.zero(),
+        let f = &self.0.f;
+        self.new_proy_point_unchecked(ProyCoordinates {
+            x: f.zero(),
+            y: f.one(),
+            z: f.zero(),
         })
     }
     fn is_on_curve(&self, p: &Self::Point) -> bool {
         let p = &p.c;
         let x3 = &p.x * &(&p.x ^ 2u32);
-        let bz = &self.b * &p.z;
-        let ax = &self.a * &p.x;
+        let bz = &self.0.b * &p.z;
+        let ax = &self.0.a * &p.x;
         let zz = &p.z * &(ax + &bz);
         let zy = &p.z * &(zz - &(&p.y ^ 2u32));
         let eq = x3 + &zy;
         eq.is_zero()
     }
     fn get_order(&self) -> BigUint {
-        self.r.clone()
+        self.0.q.get_modulus().to_biguint().unwrap()
     }
     fn get_field(&self) -> Self::F {
-        self.f.clone()
+        self.0.f.clone()
+    }
+    fn get_scalar_field(&self) -> Self::ScalarField {
+        self.0.q.clone()
     }
     fn get_cofactor(&self) -> BigInt {
-        self.h.to_bigint().unwrap()
+        self.0.h.to_bigint().unwrap()
     }
     fn get_generator(&self) -> Self::Point {
-        self.new_proy_point(ProyCoordinates {
-            x: self.gx.clone(),
-            y: self.gy.clone(),
-            z: self.f.one(),
+        self.new_proy_point_unchecked(ProyCoordinates {
+            x: self.0.gx.clone(),
+            y: self.0.gy.clone(),
+            z: self.0.f.one(),
         })
     }
 }
 
 impl Decode for Curve {
     type Deser = Point;
-    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, Error> {
-        let size = self.f.size_bytes();
-        let blen = buf.len();
-        if !(blen == 1 || blen == (size + 1) || blen == (2 * size + 1)) {
-            return Err(Error::new(ErrorKind::Other, "Wrong input buffer size."));
-        }
-        let tag = buf[0];
-        // check x coordinate is in the valid range, Sign::Plus => > 0
-        let x_val = BigInt::from_bytes_be(Sign::Plus, &buf[1..size + 1]);
-        let p = self.f.get_modulus();
-        if x_val >= p {
-            return Err(Error::new(ErrorKind::Other, "Invalid x coordinate"));
-        }
-        match tag {
+    /// Decodes a point encoded as in SEC1, i.e., either the point at infinity
+    /// (0x00), an uncompressed point (0x04), a compressed point (0x02 or
+    /// 0x03), or a hybrid point (0x06 or 0x07). The policy determines which
+    /// of them are accepted.
+    fn decode_with(&self, buf: &[u8], policy: &DecodePolicy) -> Result<Self::Deser, DecodeError> {
+        let f = &self.0.f;
+        let size = f.size_bytes();
+        let (&tag, data) = buf.split_first().ok_or(DecodeError::WrongLength)?;
+        let p = match tag {
+            0x00 if policy.reject_identity => Err(DecodeError::InvalidTag),
+            0x02 | 0x03 if policy.reject_compressed => Err(DecodeError::InvalidTag),
             0x00 => {
                 // return point of infinity
-                if buf.len() != 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Point at infinity should just be a single zero byte",
-                    ));
+                if !data.is_empty() {
+                    return Err(DecodeError::WrongLength);
                 }
                 Ok(self.identity())
             }
-            0x04 => {
-                if buf.len() != 2 * size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
+            0x04 | 0x06 | 0x07 => {
+                if tag != 0x04 && !policy.accept_hybrid {
+                    return Err(DecodeError::InvalidTag);
                 }
-                let x = self.f.elt(x_val);
-                let y_val = BigInt::from_bytes_be(Sign::Plus, &buf[size + 1..]);
-                if y_val >= p {
-                    return Err(Error::new(ErrorKind::Other, "Invalid y coordinate"));
+                if data.len() != 2 * size {
+                    return Err(DecodeError::WrongLength);
                 }
-                let y = self.f.elt(y_val);
-                Ok(self.new_point(x, y))
+                let x = f.from_bytes_be(&data[..size])?;
+                let y = f.from_bytes_be(&data[size..])?;
+                // the tag of a hybrid point carries the parity of y.
+                if tag != 0x04 && (tag == 0x07) != (y.sgn0() == -1) {
+                    return Err(DecodeError::InvalidTag);
+                }
+                self.try_new_point(x, y)
+                    .map_err(|_| DecodeError::NotOnCurve)
             }
             0x02 | 0x03 => {
-                if buf.len() != size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
+                if data.len() != size {
+                    return Err(DecodeError::WrongLength);
                 }
                 // recompute y coordinate
-                let x = self.f.elt(x_val);
+                let x = f.from_bytes_be(data)?;
                 let xx = &x * &x;
-                let xx_a = &xx + &self.a;
+                let xx_a = &xx + &self.0.a;
                 let xxx_ax = &xx_a * &x;
-                let xxx_ax_b = &xxx_ax + &self.b;
+                let xxx_ax_b = &xxx_ax + &self.0.b;
+                if !xxx_ax_b.is_square() {
+                    return Err(DecodeError::NotOnCurve);
+                }
                 let y_sqrt = xxx_ax_b.sqrt();
                 let s = y_sqrt.sgn0();
                 let deser_tag = (((s >> 1) & 0x1) + 2) as u8;
@@ -149,10 +172,15 @@ impl Decode for Curve {
                 if tag != deser_tag {
                     y = -y;
                 }
-                Ok(self.new_point(x, y))
+                self.try_new_point(x, y)
+                    .map_err(|_| DecodeError::NotOnCurve)
             }
-            _ => Err(Error::new(ErrorKind::Other, "Invalid tag specified")),
+            _ => Err(DecodeError::InvalidTag),
+        }?;
+        if policy.subgroup_check && !p.is_torsion_free() {
+            return Err(DecodeError::NotInSubgroup);
         }
+        Ok(p)
     }
 }
 
@@ -161,7 +189,7 @@ impl std::fmt::Display for Curve {
         write!(
             f,
             "Weierstrass Curve y^2=x^3+ax+b\na: {}\nb: {}",
-            self.a, self.b,
+            self.0.a, self.0.b,
         )
     }
 }
@@ -181,15 +209,15 @@ pub struct Params {
 impl<'a> std::convert::From<&'a Params> for Curve {
     fn from(params: &'a Params) -> Curve {
         let f = Fp::new(BigUint::from_str(params.p).unwrap());
-        Curve {
+        Curve(Arc::new(Inner {
             a: f.from(params.a),
             b: f.from(params.b),
-            r: BigUint::from_str(params.r).unwrap(),
+            q: Zq::new(BigUint::from_str(params.r).unwrap()),
             h: BigUint::from_str(params.h).unwrap(),
             gx: f.from(params.gx),
             gy: f.from(params.gy),
             f,
-        }
+        }))
     }
 }
 
@@ -198,9 +226,13 @@ const ERR_ECC_NEW: &str = "not valid point";
 // tests for ser/deser
 #[cfg(test)]
 mod tests {
-    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
-    use crate::field::Field;
+    use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve, Encode};
+    use crate::field::{Field, Sqrt};
     use crate::instances::{GetCurve, P256, P384, P521};
+    use crate::ops::{FromFactory, Serialize};
+    use crate::primefield::FpElt;
+    use crate::weierstrass::SSWU;
+    use crate::{DecodeError, Error};
 
     #[test]
     fn point_serialization() {
@@ -220,6 +252,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn shared_curve_handle() {
+        for &id in [P256, P384, P521].iter() {
+            let (e0, e1) = (id.get(), id.get());
+            assert!(e0 == e1, "curve handle equality check for {}", id);
+            let g0 = e0.get_generator();
+            let g1 = e1.get_generator();
+            let g2 = &g0 + &g1;
+            assert!(e0.is_on_curve(&g2), "sum validity check for {}", id);
+            assert!(g2 - g1 == g0, "sum equality check for {}", id);
+        }
+    }
+
     #[test]
     fn point_serialization_compressed() {
         for &id in [P256, P384, P521].iter() {
@@ -237,4 +282,155 @@ mod tests {
             assert!(gen == deser, "compressed point equality check for {}", id);
         }
     }
+
+    #[test]
+    fn fallible_api() {
+        let ec = P256.get();
+        let f = ec.get_field();
+        let gen = ec.get_generator();
+        let mut ser = gen.encode(false);
+        let last = ser.len() - 1;
+        ser[last] ^= 1;
+        assert_eq!(ec.decode(&ser).err(), Some(DecodeError::NotOnCurve));
+        assert_eq!(
+            ec.try_new_point(f.zero(), f.zero()).err(),
+            Some(Error::NotOnCurve)
+        );
+
+        let other = P384.get().get_generator();
+        assert_eq!(gen.try_add(&other).err(), Some(Error::CurveMismatch));
+        assert!(gen.try_add(&gen).unwrap() == &gen + &gen);
+
+        let k = P384.get().new_scalar(3.into());
+        assert_eq!(gen.try_mul(&k).err(), Some(Error::GroupMismatch));
+
+        // z = -10 is the SSWU parameter of P256, whereas z = 4 is a square.
+        assert!(SSWU::try_new(ec.clone(), f.from(-10)).is_ok());
+        assert_eq!(
+            SSWU::try_new(ec, f.from(4)).err(),
+            Some(Error::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        for &id in [P256, P384, P521].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let size = f.size_bytes();
+            assert!(ec.decode(&[0x00]).unwrap().is_zero());
+            assert_eq!(ec.decode(&[]).err(), Some(DecodeError::WrongLength));
+            assert_eq!(
+                ec.decode(&[0x00, 0x00]).err(),
+                Some(DecodeError::WrongLength)
+            );
+            assert_eq!(ec.decode(&[0x04]).err(), Some(DecodeError::WrongLength));
+
+            let mut ser = ec.get_generator().encode(true);
+            assert_eq!(
+                ec.decode(&ser[..size]).err(),
+                Some(DecodeError::WrongLength)
+            );
+            ser[0] = 0x05;
+            assert_eq!(ec.decode(&ser).err(), Some(DecodeError::InvalidTag));
+
+            // x = p is not the canonical representative of 0.
+            let p = f.get_modulus().to_biguint().unwrap().to_bytes_be();
+            let mut ser = vec![0x02; size + 1 - p.len()];
+            ser.extend_from_slice(&p);
+            assert_eq!(ec.decode(&ser).err(), Some(DecodeError::NonCanonical));
+
+            // finds an x such that x^3+ax+b is a non-square.
+            let x = (0..)
+                .map(|i: i64| -> FpElt { f.from(i) })
+                .find(|x| !(x * &(x * x + &ec.0.a) + &ec.0.b).is_square())
+                .unwrap();
+            let mut ser = vec![0x03];
+            ser.append(&mut x.to_bytes_be());
+            assert_eq!(ec.decode(&ser).err(), Some(DecodeError::NotOnCurve));
+        }
+    }
+
+    #[test]
+    fn subgroup_checks() {
+        let policy = DecodePolicy {
+            subgroup_check: true,
+            ..Default::default()
+        };
+        for &id in [P256, P384].iter() {
+            let ec = id.get();
+            let gen = ec.get_generator();
+            assert!(gen.is_torsion_free() && !gen.is_small_order());
+            assert!(gen.torsion_component().is_zero());
+            assert!(ec.decode_with(&gen.encode(true), &policy).unwrap() == gen);
+        }
+    }
+
+    #[test]
+    fn sec1_tags() {
+        let default = DecodePolicy::default();
+        let hybrid = DecodePolicy {
+            accept_hybrid: true,
+            ..Default::default()
+        };
+        let strict = DecodePolicy {
+            reject_identity: true,
+            reject_compressed: true,
+            ..Default::default()
+        };
+        for &id in [P256, P384, P521].iter() {
+            let ec = id.get();
+            let gen = ec.get_generator();
+            // points with even and odd y-coordinates.
+            for p in [gen.clone(), -gen.clone()] {
+                // 0x04
+                let ser = p.encode(false);
+                assert_eq!(ser[0], 0x04);
+                assert!(ec.decode_with(&ser, &strict).unwrap() == p);
+
+                // 0x02 and 0x03
+                let ser = p.encode(true);
+                let odd = ser[0] == 0x03;
+                assert!(ec.decode_with(&ser, &default).unwrap() == p);
+                assert_eq!(
+                    ec.decode_with(&ser, &strict).err(),
+                    Some(DecodeError::InvalidTag)
+                );
+
+                // 0x06 and 0x07
+                let mut ser = p.encode_hybrid();
+                assert_eq!(ser[0], if odd { 0x07 } else { 0x06 });
+                assert!(ser[1..] == p.encode(false)[1..]);
+                assert!(ec.decode_with(&ser, &hybrid).unwrap() == p);
+                assert_eq!(
+                    ec.decode_with(&ser, &default).err(),
+                    Some(DecodeError::InvalidTag)
+                );
+                ser[0] ^= 0x01;
+                assert_eq!(
+                    ec.decode_with(&ser, &hybrid).err(),
+                    Some(DecodeError::InvalidTag)
+                );
+            }
+
+            // 0x00
+            let ser = ec.identity().encode(false);
+            assert_eq!(ser, vec![0x00]);
+            assert!(ec.identity().encode_hybrid() == ser);
+            assert!(ec.decode_with(&ser, &default).unwrap().is_zero());
+            assert_eq!(
+                ec.decode_with(&ser, &strict).err(),
+                Some(DecodeError::InvalidTag)
+            );
+
+            for tag in [0x01, 0x05, 0x08, 0xff] {
+                let mut ser = gen.encode(false);
+                ser[0] = tag;
+                assert_eq!(
+                    ec.decode_with(&ser, &hybrid).err(),
+                    Some(DecodeError::InvalidTag)
+                );
+            }
+        }
+    }
 }
@@ -4,14 +4,13 @@
 
 mod curve;
 mod point;
-mod scalar;
 mod sswu;
 mod sswuab0;
 mod svdw;
 
+pub use crate::scalarfield::Scalar;
 pub use crate::weierstrass::curve::{Curve, Params};
 pub use crate::weierstrass::point::{Point, ProyCoordinates};
-pub use crate::weierstrass::scalar::Scalar;
 pub use crate::weierstrass::sswu::SSWU;
 pub use crate::weierstrass::sswuab0::SSWUAB0;
 pub use crate::weierstrass::svdw::SVDW;
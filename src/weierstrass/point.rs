@@ -3,19 +3,21 @@
 //! The curve module is meant to be used for bar.
 
 use impl_ops::impl_op_ex;
-use num_bigint::ToBigInt;
+use num_bigint::BigInt;
+use num_integer::Integer;
 use num_traits::identities::{One, Zero};
 
 use std::ops;
 
 use crate::do_if_eq;
 use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
-use crate::field::Sgn0;
+use crate::field::{Field, Sgn0};
 use crate::ops::ScMulRef;
 use crate::ops::Serialize;
 use crate::primefield::FpElt;
+use crate::scalarfield::{mul_int, Scalar};
 use crate::weierstrass::curve::Curve;
-use crate::weierstrass::scalar::Scalar;
+use crate::Error;
 
 #[derive(Clone)]
 pub struct ProyCoordinates {
@@ -35,18 +37,48 @@ impl EcPoint<Scalar> for Point {
     fn is_zero(&self) -> bool {
         self.c.x.is_zero() && !self.c.y.is_zero() && self.c.z.is_zero()
     }
+    fn try_add(&self, p: &Self) -> Result<Self, Error> {
+        if self.e == p.e {
+            Ok(self.core_add(p))
+        } else {
+            Err(Error::CurveMismatch)
+        }
+    }
+    fn try_mul(&self, k: &Scalar) -> Result<Self, Error> {
+        if self.e.0.q == k.get_field() {
+            Ok(self.core_mul(k))
+        } else {
+            Err(Error::GroupMismatch)
+        }
+    }
+    fn mul_int(&self, k: &BigInt) -> Self {
+        mul_int(self, k, self.e.identity(), Point::core_doubling)
+    }
+    fn is_torsion_free(&self) -> bool {
+        self.e.0.h.is_one() || self.mul_int(&self.e.0.q.get_modulus()).is_zero()
+    }
+    fn is_small_order(&self) -> bool {
+        self.mul_int(&self.e.get_cofactor()).is_zero()
+    }
+    fn torsion_component(&self) -> Self {
+        let r = self.e.0.q.get_modulus();
+        let h = self.e.get_cofactor();
+        // [r*u]P with u = 1/r mod h removes the component of order r.
+        let u = r.extended_gcd(&h).x.mod_floor(&h);
+        self.mul_int(&r).mul_int(&u)
+    }
 }
 impl Encode for Point {
     fn encode(&self, compress: bool) -> Vec<u8> {
-        // normalize the point to ensure that z = 1
-        // clone so that we don't mutate the original point
-        let mut p_normal = self.clone();
-        p_normal.normalize();
         // if the point is the point at infinity, then return a single
         // zeroed byte
-        if p_normal.is_zero() {
+        if self.is_zero() {
             return vec![0];
         }
+        // normalize the point to ensure that z = 1
+        // clone so that we don't mutate the original point
+        let mut p_normal = self.clone();
+        p_normal.normalize();
         let coords = &p_normal.c;
         let x = &coords.x;
         let y = &coords.y;
@@ -69,6 +101,16 @@ impl Encode for Point {
 }
 
 impl Point {
+    /// Encodes the point in the hybrid format of SEC1, i.e., as an
+    /// uncompressed point whose tag (0x06 or 0x07) also carries the parity
+    /// of the y-coordinate.
+    pub fn encode_hybrid(&self) -> Vec<u8> {
+        let mut o = self.encode(false);
+        if o[0] == 0x04 {
+            o[0] = 0x06 | (o[o.len() - 1] & 0x01);
+        }
+        o
+    }
     pub fn normalize(&mut self) {
         let inv_z = 1u32 / &self.c.z;
         self.c.x = &self.c.x * &inv_z;
@@ -76,7 +118,7 @@ impl Point {
         self.c.z.set_one();
     }
     fn core_neg(&self) -> <Curve as EllipticCurve>::Point {
-        self.e.new_proy_point(ProyCoordinates {
+        self.e.new_proy_point_unchecked(ProyCoordinates {
             x: self.c.x.clone(),
             y: -&self.c.y,
             z: self.c.z.clone(),
@@ -86,8 +128,8 @@ impl Point {
     // Reference: "Complete addition formulas for prime order elliptic curves" by
     // Costello-Renes-Batina. [Alg.1] (eprint.iacr.org/2015/1060).
     fn core_add(&self, p: &<Curve as EllipticCurve>::Point) -> <Curve as EllipticCurve>::Point {
-        let a = &self.e.a;
-        let b3 = &self.e.b + &self.e.b + &self.e.b;
+        let a = &self.e.0.a;
+        let b3 = &self.e.0.b + &self.e.0.b + &self.e.0.b;
         let (x1, x2) = (&self.c.x, &p.c.x);
         let (y1, y2) = (&self.c.y, &p.c.y);
         let (z1, z2) = (&self.c.z, &p.c.z);
@@ -133,7 +175,7 @@ impl Point {
         t0 = t3 * t1; //   38. t0 = t3 * t1
         z3 = t5 * z3; //   39. Z3 = t5 * Z3
         z3 = z3 + t0; //   40. Z3 = Z3 + t0
-        self.e.new_proy_point(ProyCoordinates {
+        self.e.new_proy_point_unchecked(ProyCoordinates {
             x: x3,
             y: y3,
             z: z3,
@@ -143,8 +185,8 @@ impl Point {
     // Reference: "Complete addition formulas for prime order elliptic curves" by
     // Costello-Renes-Batina. [Alg.3] (eprint.iacr.org/2015/1060).
     fn core_doubling(&self) -> <Curve as EllipticCurve>::Point {
-        let a = &self.e.a;
-        let b3 = &self.e.b + &self.e.b + &self.e.b;
+        let a = &self.e.0.a;
+        let b3 = &self.e.0.b + &self.e.0.b + &self.e.0.b;
         let x = &self.c.x;
         let y = &self.c.y;
         let z = &self.c.z;
@@ -181,7 +223,7 @@ impl Point {
         z3 = &t2 * &t1; // 29. Z3 = t2 * t1
         z3 = &z3 + &z3; // 30. Z3 = Z3 + Z3
         z3 = &z3 + &z3; // 31. Z3 = Z3 + Z3
-        self.e.new_proy_point(ProyCoordinates {
+        self.e.new_proy_point_unchecked(ProyCoordinates {
             x: x3,
             y: y3,
             z: z3,
@@ -220,10 +262,31 @@ impl_op_ex!(+|a: &Point , b: &Point | -> Point  {
 impl_op_ex!(-|a: &Point, b: &Point| -> Point { a + (-b) });
 impl_op_ex!(-|a: &Point| -> Point { a.core_neg() });
 impl_op_ex!(*|a: &Point, b: &Scalar| -> Point {
-    let r = a.e.r.to_bigint().unwrap();
-    do_if_eq!(r == b.r, a.core_mul(b), ERR_MUL_OP)
+    do_if_eq!(a.e.0.q == b.get_field(), a.core_mul(b), ERR_MUL_OP)
 });
 
+impl<'b> std::ops::Mul<&'b Point> for &Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: &'b Point) -> Self::Output {
+        other * self
+    }
+}
+impl<'b> std::ops::Mul<&'b Point> for Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: &'b Point) -> Self::Output {
+        other * &self
+    }
+}
+impl std::ops::Mul<Point> for Scalar {
+    type Output = Point;
+    #[inline]
+    fn mul(self, other: Point) -> Self::Output {
+        other * &self
+    }
+}
+
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "\nx: {}\ny: {}\nz: {}", self.c.x, self.c.y, self.c.z)
@@ -5,6 +5,7 @@ use crate::field::{CMov, Field, Sgn0, Sqrt};
 use crate::ops::FromFactory;
 use crate::primefield::FpElt;
 use crate::weierstrass::Curve;
+use crate::Error;
 
 #[derive(Clone)]
 pub struct SSWU {
@@ -16,21 +17,28 @@ pub struct SSWU {
 
 impl SSWU {
     pub fn new(e: Curve, z: FpElt) -> SSWU {
+        match SSWU::try_new(e, z) {
+            Ok(m) => m,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    /// Returns an error if the curve or `z` do not satisfy the requirements of the map.
+    pub fn try_new(e: Curve, z: FpElt) -> Result<SSWU, Error> {
         if !SSWU::verify(&e, &z) {
-            panic!("wrong input parameters")
+            Err(Error::InvalidParameters)
         } else {
-            let c1 = -&e.b * (1u32 / &e.a);
+            let c1 = -&e.0.b * (1u32 / &e.0.a);
             let c2 = -(1u32 / &z);
-            SSWU { e, c1, c2, z }
+            Ok(SSWU { e, c1, c2, z })
         }
     }
     fn verify(e: &Curve, z: &FpElt) -> bool {
-        let precond1 = !e.a.is_zero(); //              A != 0
-        let precond2 = !e.b.is_zero(); //              B != 0
+        let precond1 = !e.0.a.is_zero(); //            A != 0
+        let precond2 = !e.0.b.is_zero(); //            B != 0
         let cond1 = !z.is_square(); //                 Z is non-square
         let cond2 = *z != e.get_field().from(-1); //               Z != -1
-        let x = &e.b * &(1u32 / &(z * &e.a)); //       B/(Z*A)
-        let gx = &x * &((&x ^ 2u32) + &e.a) + &e.b; // g(B/(Z*A))
+        let x = &e.0.b * &(1u32 / &(z * &e.0.a)); //     B/(Z*A)
+        let gx = &x * &((&x ^ 2u32) + &e.0.a) + &e.0.b; // g(B/(Z*A))
         let cond4 = gx.is_square(); //                 g(B/(Z*A)) is square
         precond1 && precond2 && cond1 && cond2 && cond4
     }
@@ -54,9 +62,9 @@ impl MapToCurve for SSWU {
         x1 = cmov(&x1, &self.c2, e1); //  7.   x1 = CMOV(x1, c2, e1)
         x1 = x1 * &self.c1; //            8.   x1 = x1 * c1
         let mut gx1 = &x1 ^ 2u32; //      9.  gx1 = x1^2
-        gx1 = gx1 + &self.e.a; //         10. gx1 = gx1 + A
+        gx1 = gx1 + &self.e.0.a; //       10. gx1 = gx1 + A
         gx1 = gx1 * &x1; //               11. gx1 = gx1 * x1
-        gx1 = gx1 + &self.e.b; //         12. gx1 = gx1 + B
+        gx1 = gx1 + &self.e.0.b; //       12. gx1 = gx1 + B
         let x2 = &t1 * &x1; //            13.  x2 = t1 * x1
         t2 = t1 * t2; //                  14.  t2 = t1 * t2
         let gx2 = &gx1 * &t2; //          15. gx2 = gx1 * t2
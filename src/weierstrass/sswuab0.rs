@@ -4,6 +4,7 @@ use crate::ellipticcurve::{EllipticCurve, Isogeny, MapToCurve};
 use crate::field::Field;
 use crate::primefield::FpElt;
 use crate::weierstrass::{Curve, SSWU};
+use crate::Error;
 
 pub struct SSWUAB0 {
     // e: Curve,
@@ -13,17 +14,29 @@ pub struct SSWUAB0 {
 
 impl SSWUAB0 {
     pub fn new(e: Curve, z: FpElt, iso: Box<dyn Isogeny<E0 = Curve, E1 = Curve>>) -> SSWUAB0 {
+        match SSWUAB0::try_new(e, z, iso) {
+            Ok(m) => m,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    /// Returns an error if the isogeny does not map to the curve, or if its
+    /// domain does not satisfy the requirements of the SSWU map.
+    pub fn try_new(
+        e: Curve,
+        z: FpElt,
+        iso: Box<dyn Isogeny<E0 = Curve, E1 = Curve>>,
+    ) -> Result<SSWUAB0, Error> {
         if !SSWUAB0::verify(&e, iso.as_ref()) {
-            panic!("wrong input parameters")
+            Err(Error::InvalidParameters)
         } else {
-            let sswu = Box::new(SSWU::new(iso.domain(), z));
-            SSWUAB0 { iso, sswu }
+            let sswu = Box::new(SSWU::try_new(iso.domain(), z)?);
+            Ok(SSWUAB0 { iso, sswu })
         }
     }
     fn verify(e: &Curve, iso: &dyn Isogeny<E0 = Curve, E1 = Curve>) -> bool {
         let cond0 = *e == iso.codomain();
-        let cond1 = e.a.is_zero(); // A == 0
-        let cond2 = e.b.is_zero(); // B == 0
+        let cond1 = e.0.a.is_zero(); // A == 0
+        let cond2 = e.0.b.is_zero(); // B == 0
         cond0 && (cond1 ^ cond2) // A == 0 xor B == 0
     }
 }
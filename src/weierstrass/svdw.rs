@@ -5,6 +5,7 @@ use crate::field::{CMov, Field, Sgn0, Sqrt};
 use crate::ops::FromFactory;
 use crate::primefield::FpElt;
 use crate::weierstrass::Curve;
+use crate::Error;
 
 pub struct SVDW {
     e: Curve,
@@ -17,39 +18,46 @@ pub struct SVDW {
 
 impl SVDW {
     pub fn new(e: Curve, z: FpElt) -> SVDW {
+        match SVDW::try_new(e, z) {
+            Ok(m) => m,
+            Err(e) => panic!("{}", e),
+        }
+    }
+    /// Returns an error if the curve or `z` do not satisfy the requirements of the map.
+    pub fn try_new(e: Curve, z: FpElt) -> Result<SVDW, Error> {
         if !SVDW::verify(&e, &z) {
-            panic!("wrong input parameters")
+            Err(Error::InvalidParameters)
         } else {
             let f = e.get_field();
             let (f2, f3, f4) = (f.from(2u32), f.from(3u32), f.from(4u32));
             let gz = -SVDW::gx(&e, &z);
             let c1 = -&gz;
             let c2 = -&z * (1u32 / &f2);
-            let t0 = (f3 * (&z ^ 2u32)) + &(&f4 * &e.a);
+            let t0 = (f3 * (&z ^ 2u32)) + &(&f4 * &e.0.a);
             let mut c3 = (&gz * &t0).sqrt();
             if c3.sgn0() == -1 {
                 c3 = -c3;
             }
             let c4 = (f4 * gz) * (1u32 / &t0);
-            SVDW {
+            Ok(SVDW {
                 e,
                 c1,
                 c2,
                 c3,
                 c4,
                 z,
-            }
+            })
         }
     }
     fn gx(e: &Curve, x: &FpElt) -> FpElt {
-        x * &((x ^ 2u32) + &e.a) + &e.b
+        x * &((x ^ 2u32) + &e.0.a) + &e.0.b
     }
     fn verify(e: &Curve, z: &FpElt) -> bool {
         let f = e.get_field();
         let (f2, f3, f4) = (f.from(2u32), f.from(3u32), f.from(4u32));
         let gz = SVDW::gx(e, z);
         let gz2 = SVDW::gx(e, &((-z) * (1u32 / &f2)));
-        let t0 = -(f3 * (z ^ 2u32) + &f4 * &e.a) * (1u32 / &(&f4 * &gz));
+        let t0 = -(f3 * (z ^ 2u32) + &f4 * &e.0.a) * (1u32 / &(&f4 * &gz));
         let cond1 = !gz.is_zero(); //   g(Z) != 0
         let cond2 = !t0.is_zero(); //   -(3 * Z^2 + 4 * A) / (4 * g(Z)) != 0
         let cond3 = t0.is_square(); //  -(3 * Z^2 + 4 * A) / (4 * g(Z)) is square
@@ -79,15 +87,15 @@ impl MapToCurve for SVDW {
         t4 = t4 * &self.c3; //              9.   t4 = t4 * c3
         let x1 = &self.c2 - &t4; //         10.  x1 = c2 - t4
         let mut gx1 = &x1 ^ 2u32; //        11. gx1 = x1^2
-        gx1 = gx1 + &self.e.a; //           12. gx1 = gx1 + A
+        gx1 = gx1 + &self.e.0.a; //         12. gx1 = gx1 + A
         gx1 = gx1 * &x1; //                 13. gx1 = gx1 * x1
-        gx1 = gx1 + &self.e.b; //           14. gx1 = gx1 + B
+        gx1 = gx1 + &self.e.0.b; //         14. gx1 = gx1 + B
         let e1 = gx1.is_square(); //        15.  e1 = is_square(gx1)
         let x2 = &self.c2 + &t4; //         16.  x2 = c2 + t4
         let mut gx2 = &x2 ^ 2u32; //        17. gx2 = x2^2
-        gx2 = gx2 + &self.e.a; //           18. gx2 = gx2 + A
+        gx2 = gx2 + &self.e.0.a; //         18. gx2 = gx2 + A
         gx2 = gx2 * &x2; //                 19. gx2 = gx2 * x2
-        gx2 = gx2 + &self.e.b; //           20. gx2 = gx2 + B
+        gx2 = gx2 + &self.e.0.b; //         20. gx2 = gx2 + B
         let e2 = gx2.is_square() && !e1; // 21.  e2 = is_square(gx2) AND NOT e1     // Avoid short-circuit logic ops
         let mut x3 = &t2 ^ 2u32; //         22.  x3 = t2^2
         x3 = x3 * t3; //                    23.  x3 = x3 * t3
@@ -97,9 +105,9 @@ impl MapToCurve for SVDW {
         let mut x = cmov(&x3, &x1, e1); //  27.   x = CMOV(x3, x1, e1)      // x = x1 if gx1 is square, else x = x3
         x = cmov(&x, &x2, e2); //           28.   x = CMOV(x, x2, e2)       // x = x2 if gx2 is square and gx1 is not
         let mut gx = &x ^ 2u32; //          29.  gx = x^2
-        gx = gx + &self.e.a; //             30.  gx = gx + A
+        gx = gx + &self.e.0.a; //           30.  gx = gx + A
         gx = gx * &x; //                    31.  gx = gx * x
-        gx = gx + &self.e.b; //             32.  gx = gx + B
+        gx = gx + &self.e.0.b; //           32.  gx = gx + B
         let mut y = gx.sqrt(); //           33.   y = sqrt(gx)
         let e3 = u.sgn0() == y.sgn0(); //   34.  e3 = sgn0(u) == sgn0(y)
         y = cmov(&(-&y), &y, e3); //        35.   y = CMOV(-y, y, e3)
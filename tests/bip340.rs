@@ -0,0 +1,36 @@
+use redox_ecc::bip340::{public_key, sign, verify, XOnlyPublicKey};
+use redox_ecc::ellipticcurve::EllipticCurve;
+use redox_ecc::instances::{GetCurve, SECP256K1};
+use redox_ecc::ops::Deserialize;
+
+// The test vectors of BIP-340, from bip-0340/test-vectors.csv.
+const VECTORS: &str = include_str!("data/bip340-vectors.csv");
+
+#[test]
+fn bip340_vectors() {
+    let fq = SECP256K1.get().get_scalar_field();
+    let mut lines = VECTORS.lines();
+    assert_eq!(
+        lines.next(),
+        Some("index,secret key,public key,aux_rand,message,signature,verification result,comment")
+    );
+    for line in lines {
+        let cols: Vec<_> = line.split(',').collect();
+        let (index, sk, pk, aux_rand, msg, sig, result, comment) = (
+            cols[0], cols[1], cols[2], cols[3], cols[4], cols[5], cols[6], cols[7],
+        );
+        let pk = hex::decode(pk).unwrap();
+        let msg = hex::decode(msg).unwrap();
+        let sig = hex::decode(sig).unwrap();
+        if !sk.is_empty() {
+            let d = fq.from_bytes_be(&hex::decode(sk).unwrap()).unwrap();
+            assert_eq!(public_key(&d).unwrap().to_bytes(), pk, "vector {index}");
+            let aux_rand = hex::decode(aux_rand).unwrap().try_into().unwrap();
+            assert_eq!(sign(&d, &msg, &aux_rand).unwrap(), sig, "vector {index}");
+        }
+        let valid = XOnlyPublicKey::from_bytes(&pk)
+            .and_then(|public| verify(&public, &msg, &sig))
+            .is_ok();
+        assert_eq!(valid, result == "TRUE", "vector {index}: {comment}");
+    }
+}